@@ -0,0 +1,49 @@
+//! Benchmarks the oracle on representative positions.
+//!
+//! Run them with `cargo bench`. Criterion keeps the results of earlier runs in `target/criterion`, so that a change can be compared with the baseline before it.
+use camel_up::{oracle::project_count_only, prelude::*};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Positions with three dice in the pyramid, by name: the race and the dice.
+const THREE_DICE: [(&str, &str, &str); 3] = [
+    ("spread field", "r,o,y,g,w", "ryg"),
+    ("fully stacked tower", "roygw", "rog"),
+    ("traps", "r,+,o,,-,y,,+,g,,-,w", "oyw"),
+];
+
+/// Positions at the start of a leg, with every die in the pyramid.
+const FIVE_DICE: [(&str, &str, &str); 3] = [
+    ("spread field", "r,o,y,g,w", "roygw"),
+    ("fully stacked tower", "roygw", "roygw"),
+    ("traps", "r,+,o,,-,y,,+,g,,-,w", "roygw"),
+];
+
+fn bench_positions(
+    c: &mut Criterion,
+    group: &str,
+    positions: &[(&str, &str, &str)],
+    samples: usize,
+    projection: fn(&Race, &Dice) -> LegChances,
+) {
+    let mut group = c.benchmark_group(group);
+    group.sample_size(samples);
+    for (name, race, dice) in positions {
+        let race = race.parse::<Race>().expect("to parse");
+        let dice = dice.parse::<Dice>().expect("to parse");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &(race, dice),
+            |b, (race, dice)| b.iter(|| projection(black_box(race), black_box(dice))),
+        );
+    }
+    group.finish();
+}
+
+fn projection(c: &mut Criterion) {
+    bench_positions(c, "project 3 dice", &THREE_DICE, 100, project);
+    bench_positions(c, "project 5 dice", &FIVE_DICE, 20, project);
+    bench_positions(c, "count only 5 dice", &FIVE_DICE, 20, project_count_only);
+}
+
+criterion_group!(benches, projection);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+#![no_main]
+use camel_up::{
+    camel::{Roll, CAPACITY},
+    fuzzing::check_perform,
+    prelude::*,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// The most tiles a roll adds to the front of a race, a three and an oasis.
+const GROWTH: usize = 4;
+
+fuzz_target!(|input: (Race, Vec<Roll>)| {
+    let (mut race, rolls) = input;
+    for roll in rolls {
+        if race.positions().len() + GROWTH > CAPACITY {
+            break;
+        }
+        check_perform(&race, roll);
+        race = race.perform(roll);
+    }
+});
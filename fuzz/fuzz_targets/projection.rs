@@ -0,0 +1,13 @@
+#![no_main]
+use camel_up::{fuzzing::check_projection, prelude::*};
+use libfuzzer_sys::fuzz_target;
+
+/// More dice make every run of the target too slow to find anything.
+const MOST_DICE: usize = 3;
+
+fuzz_target!(|input: (Race, Dice)| {
+    let (race, dice) = input;
+    if dice.len() <= MOST_DICE {
+        check_projection(&race, &dice);
+    }
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use camel_up::fuzzing::check_race_parser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    check_race_parser(input);
+});
@@ -0,0 +1,383 @@
+//! Advice on which action to take during a turn.
+//!
+//! On their turn a player can take a leg betting ticket, roll a die from the pyramid, or bet on the overall winner or loser of the race. The advisor ranks these actions by the number of coins they are expected to earn.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::advisor::{rank, Action};
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//! let dice = "r".parse::<Dice>().expect("to parse");
+//! let chances = project(&race, &dice);
+//! let tickets = vec![Ticket::from((Camel::Red, 5))];
+//!
+//! let ranked = rank(&chances, &tickets, None);
+//!
+//! assert_eq!(ranked[0], (Action::TakeTicket(tickets[0]), Fraction::from(5)));
+//! ```
+use crate::{
+    bets::Ticket,
+    camel::{Camel, Marker},
+    fraction::Fraction,
+    oracle::{Distribution, LegChances, RaceChances},
+    rules::RuleSet,
+};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+/// The coins earned by rolling a die from the pyramid.
+pub const ROLL_REWARD: i64 = 1;
+
+/// The coins paid out for the first correct bet on the overall winner or loser.
+pub const OVERALL_REWARD: i64 = 8;
+
+/// The coins lost for an incorrect bet on the overall winner or loser.
+pub const OVERALL_PENALTY: i64 = 1;
+
+/// The actions a player can take on their turn.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Action {
+    /// Take a leg betting ticket.
+    TakeTicket(Ticket),
+    /// Roll a die from the pyramid.
+    Roll,
+    /// Bet that the camel wins the race.
+    BetOnWinner(Camel),
+    /// Bet that the camel loses the race.
+    BetOnLoser(Camel),
+    /// Place a spectator tile, i.e. an oasis or a fata morgana, on the position counted from the start of the race.
+    PlaceTrap(usize, Marker),
+    /// Form a partnership with the player, counted from 0. Only the second edition has partnerships.
+    Partner(usize),
+    /// Place the betting photographer of the Supercup expansion on the position counted from the start of the race.
+    Photograph(usize),
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::TakeTicket(ticket) => {
+                write!(f, "take {:?} ticket worth {}", ticket.camel, ticket.value)
+            }
+            Action::Roll => write!(f, "roll a die"),
+            Action::BetOnWinner(camel) => write!(f, "bet on {:?} winning the race", camel),
+            Action::BetOnLoser(camel) => write!(f, "bet on {:?} losing the race", camel),
+            Action::PlaceTrap(position, Marker::Oasis) => {
+                write!(f, "place an oasis on position {}", position)
+            }
+            Action::PlaceTrap(position, _) => {
+                write!(f, "place a fata morgana on position {}", position)
+            }
+            Action::Partner(player) => {
+                write!(f, "form a partnership with player {}", player + 1)
+            }
+            Action::Photograph(position) => {
+                write!(f, "place the photographer on position {}", position)
+            }
+        }
+    }
+}
+
+/// The coins a correct bet on the overall winner or loser is expected to pay out, for each camel, and the coins an incorrect bet costs.
+///
+/// Earlier correct bets on the same camel lower the payout. Camels that are missing pay out the `first` payout. The default rewards follow the default rules.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Rewards {
+    /// The expected payout of a correct bet on the camel winning the race.
+    pub winner: HashMap<Camel, Fraction>,
+    /// The expected payout of a correct bet on the camel losing the race.
+    pub loser: HashMap<Camel, Fraction>,
+    /// The payout of the first correct bet on a camel.
+    pub first: i64,
+    /// The coins lost for an incorrect bet.
+    pub penalty: i64,
+}
+
+impl Rewards {
+    fn winner(&self, camel: Camel) -> Fraction {
+        self.winner
+            .get(&camel)
+            .copied()
+            .unwrap_or_else(|| Fraction::from(self.first))
+    }
+
+    fn loser(&self, camel: Camel) -> Fraction {
+        self.loser
+            .get(&camel)
+            .copied()
+            .unwrap_or_else(|| Fraction::from(self.first))
+    }
+}
+
+impl From<&RuleSet> for Rewards {
+    fn from(rules: &RuleSet) -> Self {
+        Self {
+            winner: HashMap::new(),
+            loser: HashMap::new(),
+            first: rules.overall_payout(0),
+            penalty: rules.overall_penalty,
+        }
+    }
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self::from(&RuleSet::default())
+    }
+}
+
+/// Ranks the legal actions by their expected number of coins, best first.
+///
+/// The `leg` chances are used for the tickets. Bets on the outcome of the race are only considered when the chances for the `race` are known.
+pub fn rank(
+    leg: &LegChances,
+    tickets: &[Ticket],
+    race: Option<&RaceChances>,
+) -> Vec<(Action, Fraction)> {
+    rank_with_rewards(leg, tickets, race, &Rewards::default())
+}
+
+/// Ranks the legal actions like `rank`, with the given payouts and penalty for bets on the outcome of the race.
+pub fn rank_with_rewards(
+    leg: &LegChances,
+    tickets: &[Ticket],
+    race: Option<&RaceChances>,
+    rewards: &Rewards,
+) -> Vec<(Action, Fraction)> {
+    let mut ranked: Vec<(Action, Fraction)> = tickets
+        .iter()
+        .map(|ticket| (Action::TakeTicket(*ticket), ticket.expected_value(leg)))
+        .collect();
+    ranked.push((Action::Roll, Fraction::from(ROLL_REWARD)));
+    if let Some(race) = race {
+        let mut camels: Vec<Camel> = race
+            .places
+            .iter()
+            .flat_map(Distribution::values)
+            .map(|(camel, _)| *camel)
+            .collect();
+        camels.sort();
+        camels.dedup();
+        for camel in camels {
+            ranked.push((
+                Action::BetOnWinner(camel),
+                overall(race.winner[&camel], rewards.winner(camel), rewards.penalty),
+            ));
+            ranked.push((
+                Action::BetOnLoser(camel),
+                overall(race.loser[&camel], rewards.loser(camel), rewards.penalty),
+            ));
+        }
+    }
+    ranked.sort_by(|(_, left), (_, right)| right.cmp(left));
+
+    ranked
+}
+
+/// The leg betting ticket you can expect to take on your next turn, with its expected value, after the opponents that act before it.
+///
+/// The stacks hold the tickets that are left for each camel, the top of a stack is last. Every opponent takes the top ticket with the best expected value while one is expected to win coins, and rolls a die otherwise. When the opponents roll the last of the dice in the pyramid, the leg ends before your next turn and there is no ticket to take. The `leg` chances are those of the current leg, the rolls of the opponents are not taken into account.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::advisor::next_ticket;
+/// # use std::collections::HashMap;
+/// let race = "r,,,,y".parse::<Race>().expect("to parse");
+/// let dice = "ry".parse::<Dice>().expect("to parse");
+/// let chances = project(&race, &dice);
+/// let mut stacks = HashMap::new();
+/// stacks.insert(Camel::Yellow, vec![2, 3, 5]);
+///
+/// let next = next_ticket(&chances, &stacks, 1, dice.len());
+///
+/// assert_eq!(next, Some((Ticket::from((Camel::Yellow, 3)), Fraction::from(3))));
+/// ```
+pub fn next_ticket(
+    leg: &LegChances,
+    stacks: &HashMap<Camel, Vec<u8>>,
+    opponents: usize,
+    dice: usize,
+) -> Option<(Ticket, Fraction)> {
+    let mut stacks = stacks.clone();
+    let mut dice = dice;
+    let best = |stacks: &HashMap<Camel, Vec<u8>>| {
+        let mut tickets: Vec<(Ticket, Fraction)> = stacks
+            .iter()
+            .filter_map(|(camel, stack)| stack.last().map(|value| Ticket::from((*camel, *value))))
+            .map(|ticket| (ticket, ticket.expected_value(leg)))
+            .collect();
+        tickets.sort_by(|(left, left_value), (right, right_value)| {
+            right_value
+                .cmp(left_value)
+                .then_with(|| left.camel.cmp(&right.camel))
+        });
+        tickets.first().copied()
+    };
+    for _ in 0..opponents {
+        if dice == 0 {
+            return None;
+        }
+        match best(&stacks) {
+            Some((ticket, value)) if value > Fraction::zero() => {
+                if let Some(stack) = stacks.get_mut(&ticket.camel) {
+                    stack.pop();
+                }
+            }
+            _ => dice -= 1,
+        }
+    }
+    if dice == 0 {
+        return None;
+    }
+    best(&stacks)
+}
+
+/// What a partnership is expected to be worth to both partners during the current leg.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Partnership {
+    /// The coins expected from the winning tickets of the partner.
+    pub gained: Fraction,
+    /// The coins the partner expects from your winning tickets.
+    pub given: Fraction,
+}
+
+impl Partnership {
+    /// How many coins the partnership is expected to gain you over your partner.
+    pub fn advantage(&self) -> Fraction {
+        self.gained - self.given
+    }
+}
+
+/// Values a partnership given your leg betting tickets and those of the partner.
+///
+/// Partners receive the winnings of each other's tickets, but do not share losses. The `leg` chances tell how likely each ticket is to win.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::advisor::partnership;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let chances = project(&race, &dice);
+///
+/// let value = partnership(&chances, &[Ticket::from((Camel::Yellow, 5))], &[Ticket::from((Camel::Red, 5))]);
+///
+/// assert_eq!(value.gained, Fraction::from(5));
+/// assert_eq!(value.given, Fraction::one());
+/// ```
+pub fn partnership(leg: &LegChances, own: &[Ticket], partner: &[Ticket]) -> Partnership {
+    let winnings = |tickets: &[Ticket]| {
+        tickets
+            .iter()
+            .map(|ticket| ticket.expected_winnings(leg))
+            .sum::<Fraction>()
+    };
+    Partnership {
+        gained: winnings(partner),
+        given: winnings(own),
+    }
+}
+
+fn overall(chance: Fraction, reward: Fraction, penalty: i64) -> Fraction {
+    chance * reward - (Fraction::one() - chance) * penalty
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        camel::{Dice, Race},
+        oracle::{project, sample_race},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn chances(race: &str, dice: &str) -> LegChances {
+        let race = race.parse::<Race>().expect("to parse");
+        let dice = dice.parse::<Dice>().expect("to parse");
+        project(&race, &dice)
+    }
+
+    fn race_chances(race: &str, dice: &str) -> RaceChances {
+        let race = race.parse::<Race>().expect("to parse");
+        let dice = dice.parse::<Dice>().expect("to parse");
+        sample_race(&race, &dice, 100, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn opponents_take_the_best_tickets_before_your_next_turn() {
+        let race = "r,,,,y".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+        let mut stacks = HashMap::new();
+        stacks.insert(Camel::Yellow, vec![2, 3, 5]);
+        stacks.insert(Camel::Red, vec![2, 3, 5]);
+
+        assert_eq!(
+            next_ticket(&chances, &stacks, 0, 2),
+            Some((Ticket::from((Camel::Yellow, 5)), Fraction::from(5)))
+        );
+        assert_eq!(
+            next_ticket(&chances, &stacks, 3, 2),
+            Some((Ticket::from((Camel::Red, 5)), Fraction::one()))
+        );
+        assert_eq!(next_ticket(&chances, &stacks, 9, 2), None);
+    }
+
+    #[test]
+    fn rolling_beats_a_losing_ticket() {
+        let leg = chances("g,y,,,,r", "y");
+        let tickets = vec![Ticket::from((Camel::Green, 5))];
+
+        let ranked = rank(&leg, &tickets, None);
+
+        assert_eq!(ranked[0], (Action::Roll, Fraction::one()));
+        assert_eq!(
+            ranked[1],
+            (Action::TakeTicket(tickets[0]), -Fraction::one())
+        );
+    }
+
+    #[test]
+    fn certain_race_outcomes_are_worth_the_reward() {
+        let leg = chances("g,r,y!", "r");
+        let race = race_chances("g,r,y!", "r");
+
+        let ranked = rank(&leg, &[], Some(&race));
+
+        assert_eq!(ranked.len(), 7);
+        assert_eq!(ranked[0].1, Fraction::from(OVERALL_REWARD));
+        assert_eq!(ranked[6].1, -Fraction::from(OVERALL_PENALTY));
+    }
+
+    #[test]
+    fn lower_rewards_lower_the_value_of_bets() {
+        let leg = chances("g,r,y!", "r");
+        let race = race_chances("g,r,y!", "r");
+        let mut rewards = Rewards::default();
+        rewards.winner.insert(Camel::Red, Fraction::from(3));
+
+        let ranked = rank_with_rewards(&leg, &[], Some(&race), &rewards);
+
+        assert!(ranked.contains(&(
+            Action::BetOnLoser(Camel::Green),
+            Fraction::from(OVERALL_REWARD)
+        )));
+        assert!(ranked.contains(&(Action::BetOnWinner(Camel::Red), Fraction::from(3))));
+    }
+
+    #[test]
+    fn rewards_follow_the_rules() {
+        let leg = chances("g,r,y!", "r");
+        let race = race_chances("g,r,y!", "r");
+        let rules = RuleSet {
+            overall_payouts: vec![4, 2],
+            overall_penalty: 3,
+            ..RuleSet::default()
+        };
+
+        let ranked = rank_with_rewards(&leg, &[], Some(&race), &Rewards::from(&rules));
+
+        assert_eq!(ranked[0].1, Fraction::from(4));
+        assert_eq!(ranked[6].1, -Fraction::from(3));
+    }
+}
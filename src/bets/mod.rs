@@ -0,0 +1,146 @@
+//! Betting on the outcome of a leg.
+//!
+//! At the start of each leg there are betting tickets for every camel. A ticket pays its value when the camel wins the leg, a single coin when the camel is runner up, and costs a coin otherwise.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//! let dice = "r".parse::<Dice>().expect("to parse");
+//! let chances = project(&race, &dice);
+//!
+//! let ticket = "r5".parse::<Ticket>().expect("to parse");
+//!
+//! assert_eq!(ticket.expected_value(&chances), Fraction::from(5));
+//! ```
+use crate::{
+    camel::{Camel, Marker, NotAMarker},
+    fraction::Fraction,
+    oracle::LegChances,
+};
+use std::str::FromStr;
+
+/// A leg betting ticket for a camel.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct Ticket {
+    /// The camel this ticket bets on.
+    pub camel: Camel,
+    /// The number of coins paid out when the camel wins the leg.
+    pub value: u8,
+}
+
+impl Ticket {
+    /// The expected number of coins this ticket earns, given the chances for the leg.
+    pub fn expected_value(&self, chances: &LegChances) -> Fraction {
+        let win = chances.winner[&self.camel];
+        let runner_up = chances.runner_up[&self.camel];
+        let lose = Fraction::one() - win - runner_up;
+
+        win * (self.value as i64) + runner_up - lose
+    }
+
+    /// The expected number of coins this ticket wins, leaving out the coin it costs when the camel does neither win nor finish runner up.
+    pub fn expected_winnings(&self, chances: &LegChances) -> Fraction {
+        chances.winner[&self.camel] * (self.value as i64) + chances.runner_up[&self.camel]
+    }
+}
+
+impl From<(Camel, u8)> for Ticket {
+    fn from((camel, value): (Camel, u8)) -> Self {
+        Self { camel, value }
+    }
+}
+
+impl FromStr for Ticket {
+    type Err = TicketParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut symbols = input.chars();
+        let camel = symbols
+            .next()
+            .ok_or(TicketParseError::Empty)?
+            .to_string()
+            .parse::<Marker>()?
+            .to_camel()
+            .ok_or(TicketParseError::NotACamel)?;
+        let value = symbols
+            .as_str()
+            .parse::<u8>()
+            .map_err(|_| TicketParseError::NotAValue(symbols.as_str().to_owned()))?;
+        Ok(Ticket::from((camel, value)))
+    }
+}
+
+/// When parsing of a Ticket goes wrong, this enumeration tells you precisely what went down.
+#[derive(PartialEq, Debug)]
+pub enum TicketParseError {
+    /// There was nothing to parse.
+    Empty,
+    /// The first symbol isn't even a marker.
+    NotAMarker(NotAMarker),
+    /// The first symbol is a marker, but not a camel.
+    NotACamel,
+    /// What follows the camel is not a value. The argument tells you what it was.
+    NotAValue(String),
+}
+
+impl From<NotAMarker> for TicketParseError {
+    fn from(problem: NotAMarker) -> Self {
+        Self::NotAMarker(problem)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        camel::{Dice, Race},
+        oracle::project,
+    };
+
+    #[test]
+    fn tickets_can_be_parsed() {
+        let ticket = "w3".parse::<Ticket>().expect("to parse");
+
+        assert_eq!(ticket, Ticket::from((Camel::White, 3)));
+    }
+
+    #[test]
+    fn tickets_need_a_camel() {
+        assert_eq!("+3".parse::<Ticket>(), Err(TicketParseError::NotACamel));
+    }
+
+    #[test]
+    fn tickets_may_start_with_any_symbol() {
+        assert_eq!("".parse::<Ticket>(), Err(TicketParseError::Empty));
+        assert!(matches!(
+            "é5".parse::<Ticket>(),
+            Err(TicketParseError::NotAMarker(_))
+        ));
+        assert_eq!(
+            "ré".parse::<Ticket>(),
+            Err(TicketParseError::NotAValue("é".to_owned()))
+        );
+    }
+
+    #[test]
+    fn ticket_for_certain_runner_up_is_worth_a_coin() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+        let dice = "r".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+
+        let ticket = Ticket::from((Camel::Yellow, 5));
+
+        assert_eq!(ticket.expected_value(&chances), Fraction::one());
+    }
+
+    #[test]
+    fn ticket_value_is_weighed_by_chances() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "r".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+
+        let ticket = Ticket::from((Camel::Red, 5));
+
+        assert_eq!(ticket.expected_value(&chances), Fraction::new(11, 3));
+    }
+}
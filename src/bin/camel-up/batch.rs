@@ -0,0 +1,194 @@
+//! Projects many positions in one go.
+//!
+//! Each line of the input holds a race and, optionally, the dice in the pyramid, separated by whitespace. Without dice, the dice of the camels in the race are used. Blank lines and lines starting with `#` are skipped.
+//!
+//! ```plain
+//! # opening positions
+//! r,o,y,g,w
+//! ry,,g ryg
+//! ```
+use super::trace;
+use camel_up::{
+    camel::{Dice, Race},
+    oracle::project,
+};
+use std::{
+    collections::HashMap,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread::{self, available_parallelism},
+    time::Instant,
+};
+
+/// Projects every position in the content, returning one result line for each position in the same order.
+///
+/// Positions are divided over the available cores. Results are shared between them, so that repeated positions are computed once.
+pub fn run(content: &str, format: &super::Format) -> Vec<Result<String, String>> {
+    let positions: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let cache: Mutex<HashMap<(String, String), String>> = Mutex::new(HashMap::new());
+    let hits = AtomicUsize::new(0);
+    let workers = available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk = positions.len().div_ceil(workers).max(1);
+    let start = Instant::now();
+
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = positions
+            .chunks(chunk)
+            .map(|chunk| {
+                let cache = &cache;
+                let hits = &hits;
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(number, line)| {
+                            evaluate(line, format, cache, hits)
+                                .map_err(|problem| format!("line {}: {}", number, problem))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| process::exit(super::exit::COMPUTATION))
+            })
+            .collect()
+    });
+    trace::event(
+        trace::SUMMARY,
+        "batch",
+        &[
+            ("positions", &positions.len()),
+            ("workers", &workers),
+            ("cache_hits", &hits.load(Ordering::Relaxed)),
+            ("elapsed_ms", &trace::millis(start.elapsed())),
+        ],
+    );
+    results
+}
+
+fn evaluate(
+    line: &str,
+    format: &super::Format,
+    cache: &Mutex<HashMap<(String, String), String>>,
+    hits: &AtomicUsize,
+) -> Result<String, String> {
+    let start = Instant::now();
+    let (race, dice) = parse(line)?;
+    let notation = super::config::notation();
+    let key = (
+        notation.localize(&race.to_string()),
+        notation.localize(&dice.to_string()),
+    );
+    let cached = cache
+        .lock()
+        .expect("cache to be available")
+        .get(&key)
+        .cloned();
+    let hit = cached.is_some();
+    let chances = match cached {
+        Some(chances) => {
+            hits.fetch_add(1, Ordering::Relaxed);
+            chances
+        }
+        None => {
+            let chances = super::line(&super::ordered(&project(&race, &dice)), format);
+            cache
+                .lock()
+                .expect("cache to be available")
+                .insert(key.clone(), chances.clone());
+            chances
+        }
+    };
+    trace::event(
+        trace::DETAIL,
+        "position",
+        &[
+            ("race", &key.0),
+            ("dice", &key.1),
+            ("cache", &if hit { "hit" } else { "miss" }),
+            ("elapsed_ms", &trace::millis(start.elapsed())),
+        ],
+    );
+    Ok(format!("{} {} {}", key.0, key.1, chances))
+}
+
+fn parse(line: &str) -> Result<(Race, Dice), String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (race, dice) = match words.as_slice() {
+        [race] => (race, None),
+        [race, dice] => (race, Some(dice)),
+        _ => return Err("expected a race, optionally followed by dice".to_owned()),
+    };
+    let notation = super::config::notation();
+    let race = notation
+        .parse_race(race)
+        .map(|race| race.with_edition(super::config::edition()))
+        .map_err(|error| format!("could not parse race {}: {}", race, error))?;
+    let dice = match dice {
+        Some(dice) => notation
+            .parse_dice(dice)
+            .map_err(|error| format!("could not parse dice {}: {}", dice, error))?,
+        None => Dice::from(&race),
+    };
+    race.check_dice(&dice)
+        .map_err(|error| format!("could not roll dice {}: {}", dice, error))?;
+    Ok((race, dice))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Format;
+
+    #[test]
+    fn every_position_gets_a_result_in_order() {
+        let content = "# positions\nr,y r\n\nr,,y ry\nr,y r\n";
+
+        let results = run(content, &Format::Fraction);
+
+        assert_eq!(
+            results,
+            vec![
+                Ok("r,y r (Red,1)".to_owned()),
+                Ok("r,,y ry (Yellow,7/9)(Red,2/9)".to_owned()),
+                Ok("r,y r (Red,1)".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn problems_mention_the_line() {
+        let results = run("r,y\nr|y r\n", &Format::Fraction);
+
+        assert_eq!(results[0], Ok("r,y ry (Yellow,11/18)(Red,7/18)".to_owned()));
+        assert_eq!(
+            results[1],
+            Err("line 2: could not parse race r|y: '|' is not a marker".to_owned())
+        );
+    }
+
+    #[test]
+    fn dice_of_camels_that_are_not_in_the_race_are_a_problem() {
+        let results = run(
+            "r,y w
+r,y r
+",
+            &Format::Fraction,
+        );
+
+        assert!(results[0].is_err());
+        assert_eq!(results[1], Ok("r,y r (Red,1)".to_owned()));
+    }
+}
@@ -0,0 +1,211 @@
+//! Defaults for the command line, read from a configuration file.
+//!
+//! The file lives at `~/.config/camel-up/config.toml`, or under `$XDG_CONFIG_HOME` when set. The environment variable `CAMEL_UP_CONFIG` points to a different file. Every setting is optional and flags on the command line take precedence, e.g.
+//!
+//! ```toml
+//! color = "never"
+//! format = "markdown"
+//! percent = true
+//! precision = 2
+//! letters = "r=b,w=s"
+//! edition = "second"
+//! lang = "nl"
+//! max_tiles = 16
+//! ```
+use camel_up::{camel::Edition, i18n::Language, notation::Notation, vis::render::Style};
+use clap::ArgMatches;
+use serde::Deserialize;
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+/// When to paint the board with colors.
+#[derive(Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    /// Colors when standard output is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+/// How chances are laid out.
+#[derive(Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    Plain,
+    Markdown,
+}
+
+/// The defaults read from the configuration file.
+#[derive(Deserialize, Default, PartialEq, Debug)]
+pub struct Config {
+    pub color: Option<Color>,
+    pub format: Option<Output>,
+    pub percent: Option<bool>,
+    pub precision: Option<usize>,
+    /// Letters for the camels, see `Notation`.
+    pub letters: Option<String>,
+    /// The edition whose rules races follow, see `Edition`.
+    pub edition: Option<String>,
+    /// The language of camel names and labels, see `Language`.
+    pub lang: Option<String>,
+    /// The number of tiles of the board, races that span more are refused.
+    pub max_tiles: Option<usize>,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static STYLE: OnceLock<Style> = OnceLock::new();
+static NOTATION: OnceLock<Notation> = OnceLock::new();
+static EDITION: OnceLock<Edition> = OnceLock::new();
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+static MAX_TILES: OnceLock<Option<usize>> = OnceLock::new();
+
+impl Config {
+    /// Reads the configuration file. A missing file is the same as an empty one.
+    pub fn load() -> Result<Self, String> {
+        let path = match path() {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|error| format!("could not parse {}: {}", path.display(), error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(format!("could not read {}: {}", path.display(), error)),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("CAMEL_UP_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let directory = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(directory.join("camel-up").join("config.toml"))
+}
+
+/// Makes the configuration available, combining it with the global arguments.
+pub fn init(config: Config, matches: &ArgMatches) -> Result<(), String> {
+    let notation = match matches.value_of("letters").or(config.letters.as_deref()) {
+        Some(letters) => letters
+            .parse::<Notation>()
+            .map_err(|error| format!("could not parse letters {}: {}", letters, error))?,
+        None => Notation::default(),
+    };
+    let edition = match matches.value_of("edition").or(config.edition.as_deref()) {
+        Some(edition) => edition
+            .parse::<Edition>()
+            .map_err(|error| format!("could not parse edition: {}", error))?,
+        None => Edition::default(),
+    };
+    let language = match matches.value_of("lang").or(config.lang.as_deref()) {
+        Some(language) => language
+            .parse::<Language>()
+            .map_err(|error| format!("could not parse language: {}", error))?,
+        None => Language::default(),
+    };
+    let max_tiles = match matches.value_of("max-tiles") {
+        Some(tiles) => Some(
+            tiles
+                .parse::<usize>()
+                .map_err(|error| format!("could not parse max tiles {}: {}", tiles, error))?,
+        ),
+        None => config.max_tiles,
+    };
+    let color = match matches.value_of("color") {
+        Some("always") => Color::Always,
+        Some("never") => Color::Never,
+        Some(_) => Color::Auto,
+        None => config.color.unwrap_or(Color::Auto),
+    };
+    let style = match color {
+        Color::Always => Style::Colored,
+        Color::Never => Style::Plain,
+        Color::Auto if io::stdout().is_terminal() => Style::Colored,
+        Color::Auto => Style::Plain,
+    };
+    STYLE.set(style).ok();
+    NOTATION.set(notation).ok();
+    EDITION.set(edition).ok();
+    LANGUAGE.set(language).ok();
+    MAX_TILES.set(max_tiles).ok();
+    CONFIG.set(config).ok();
+    Ok(())
+}
+
+/// The configuration, or the defaults when it was not initialized.
+pub fn current() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// The letters for the camels, in which races and dice are read and written.
+pub fn notation() -> &'static Notation {
+    NOTATION.get_or_init(Notation::default)
+}
+
+/// The edition whose rules the races follow.
+pub fn edition() -> Edition {
+    *EDITION.get().unwrap_or(&Edition::Classic)
+}
+
+/// The language of camel names and labels.
+pub fn language() -> Language {
+    *LANGUAGE.get().unwrap_or(&Language::English)
+}
+
+/// The number of tiles of the board, when races that span more should be refused.
+pub fn max_tiles() -> Option<usize> {
+    MAX_TILES.get().copied().flatten()
+}
+
+/// The style to paint boards with.
+pub fn style() -> Style {
+    *STYLE.get().unwrap_or(&Style::Colored)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn configurations_can_be_parsed() {
+        let config = Config::parse("color = \"never\"\nformat = \"markdown\"\nprecision = 2\n")
+            .expect("to parse");
+
+        assert_eq!(
+            config,
+            Config {
+                color: Some(Color::Never),
+                format: Some(Output::Markdown),
+                percent: None,
+                precision: Some(2),
+                letters: None,
+                edition: None,
+                lang: None,
+                max_tiles: None,
+            }
+        );
+    }
+
+    #[test]
+    fn maximum_tiles_can_be_configured() {
+        let config = Config::parse("max_tiles = 16\n").expect("to parse");
+
+        assert_eq!(config.max_tiles, Some(16));
+    }
+
+    #[test]
+    fn unknown_colors_are_rejected() {
+        assert!(Config::parse("color = \"sometimes\"\n").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Plays complete games between bots, to compare how well they do.
+//!
+//! The games are played by the engine in the `game` module of the library, with the bots as its strategies. The races follow the rules of the selected edition.
+use camel_up::game::{
+    setup::{setup, SetupError},
+    strategy::{AlwaysRoll, Expectimax, GreedyLegEv, GreedyRaceEv, Random, Strategy},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// The number of races the ev bot samples to value overall bets.
+const EV_SAMPLES: usize = 200;
+/// The number of turns the search bot looks ahead, unless told otherwise.
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// How a bot decides on its action.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Bot {
+    /// Picks any legal action.
+    Random,
+    /// Takes the leg betting ticket with the best expected value, or rolls when that is better.
+    Greedy,
+    /// Like greedy, but considers bets on the overall winner and loser as well.
+    Ev,
+    /// Always rolls a die.
+    Roll,
+    /// Looks ahead the given number of turns over its own actions and the dice.
+    Search(usize),
+}
+
+impl Bot {
+    fn strategy<R: Rng>(self, rng: &mut R) -> Box<dyn Strategy> {
+        match self {
+            Bot::Random => Box::new(Random::new(StdRng::seed_from_u64(rng.gen()))),
+            Bot::Greedy => Box::new(GreedyLegEv),
+            Bot::Ev => Box::new(GreedyRaceEv::new(
+                StdRng::seed_from_u64(rng.gen()),
+                EV_SAMPLES,
+            )),
+            Bot::Roll => Box::new(AlwaysRoll),
+            Bot::Search(depth) => Box::new(Expectimax::new(depth)),
+        }
+    }
+}
+
+impl FromStr for Bot {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "random" => Ok(Bot::Random),
+            "greedy" => Ok(Bot::Greedy),
+            "ev" => Ok(Bot::Ev),
+            "roll" => Ok(Bot::Roll),
+            "search" => Ok(Bot::Search(DEFAULT_DEPTH)),
+            _ => Err(format!(
+                "'{}' is not a bot, use random, greedy, ev, roll or search",
+                input
+            )),
+        }
+    }
+}
+
+impl Display for Bot {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Bot::Random => write!(f, "random"),
+            Bot::Greedy => write!(f, "greedy"),
+            Bot::Ev => write!(f, "ev"),
+            Bot::Roll => write!(f, "roll"),
+            Bot::Search(_) => write!(f, "search"),
+        }
+    }
+}
+
+/// Plays a complete game, returning the final coins of each player.
+pub fn play<R: Rng>(bots: &[Bot], rng: &mut R) -> Result<Vec<i64>, SetupError> {
+    let mut strategies: Vec<Box<dyn Strategy>> = bots.iter().map(|bot| bot.strategy(rng)).collect();
+    let mut state = setup(bots.len(), super::config::edition(), rng)?;
+    while !state.is_over() {
+        let player = state.current;
+        let action = strategies[player].choose(&state, player);
+        state
+            .apply(action, rng)
+            .expect("strategies to choose legal actions");
+    }
+    Ok(state.players.iter().map(|player| player.coins).collect())
+}
+
+/// Statistics over a number of games.
+pub struct Statistics {
+    pub games: usize,
+    pub total: Vec<i64>,
+    pub wins: Vec<f64>,
+}
+
+/// Plays the given number of games, tracking the coins and the wins of every player. Shared wins are split.
+pub fn tournament<R: Rng>(
+    bots: &[Bot],
+    games: usize,
+    rng: &mut R,
+) -> Result<Statistics, SetupError> {
+    let mut statistics = Statistics {
+        games,
+        total: vec![0; bots.len()],
+        wins: vec![0.0; bots.len()],
+    };
+    for _ in 0..games {
+        let coins = play(bots, rng)?;
+        let best = *coins.iter().max().unwrap_or(&0);
+        let winners = coins.iter().filter(|coins| **coins == best).count();
+        for (player, coins) in coins.iter().enumerate() {
+            statistics.total[player] += coins;
+            if *coins == best {
+                statistics.wins[player] += 1.0 / winners as f64;
+            }
+        }
+    }
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn games_end_with_a_camel_across_the_finish() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let bots = [Bot::Random, Bot::Roll];
+
+        let coins = play(&bots, &mut rng).expect("to set up");
+
+        assert_eq!(coins.len(), 2);
+        assert!(coins.iter().all(|coins| *coins >= 0));
+    }
+
+    #[test]
+    fn bots_can_be_parsed() {
+        assert_eq!("ev".parse::<Bot>(), Ok(Bot::Ev));
+        assert!("smart".parse::<Bot>().is_err());
+    }
+}
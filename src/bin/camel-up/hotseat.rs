@@ -0,0 +1,312 @@
+//! A game for several players at a single terminal, the players take turns at the keyboard.
+//!
+//! The engine in the `game` module of the library keeps track of the game. Taking a pyramid ticket rolls a die for the player.
+use camel_up::{
+    advisor::Action,
+    camel::{Camel, Face, Marker, Roll},
+    game::{
+        history::History,
+        setup::{setup, SetupError},
+        GameState,
+    },
+    rules::RuleSet,
+    vis::types::{start_tile, Board},
+};
+use rand::{seq::SliceRandom, Rng};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "commands:
+  roll                take a pyramid ticket and roll a die
+  ticket <camel>      take the top leg betting ticket of a camel, e.g. ticket r
+  winner <camel>      bet on the camel winning the race
+  loser <camel>       bet on the camel losing the race
+  trap <+|-><tile>    place your oasis or fata morgana on a tile, e.g. trap +7
+  partner <player>    form a partnership with a player, second edition only
+  photo <tile>        place the photographer on a tile, Supercup only
+  hint                show the actions ranked by the coins expected by your next turn
+  undo                undo the last turn
+  help                show this message
+  quit                end the game";
+
+/// The commands a player can give on their turn.
+#[derive(PartialEq, Debug)]
+enum Command {
+    Roll,
+    Ticket(Camel),
+    Winner(Camel),
+    Loser(Camel),
+    Trap(Marker, usize),
+    Partner(usize),
+    Photo(usize),
+    Hint,
+    Undo,
+    Help,
+    Quit,
+}
+
+fn parse(line: &str) -> Result<Command, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["roll"] => Ok(Command::Roll),
+        ["ticket", camel] => camel_of(camel).map(Command::Ticket),
+        ["winner", camel] => camel_of(camel).map(Command::Winner),
+        ["loser", camel] => camel_of(camel).map(Command::Loser),
+        ["trap", trap] => {
+            let mut symbols = trap.chars();
+            let marker = match symbols.next() {
+                Some('+') => Marker::Oasis,
+                Some('-') => Marker::FataMorgana,
+                _ => return Err(format!("'{}' is not a trap, use + or -", trap)),
+            };
+            let tile = symbols
+                .as_str()
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a tile", symbols.as_str()))?;
+            Ok(Command::Trap(marker, tile))
+        }
+        ["photo", tile] => tile
+            .parse::<usize>()
+            .map(Command::Photo)
+            .map_err(|_| format!("'{}' is not a tile", tile)),
+        ["partner", player] => player
+            .parse::<usize>()
+            .ok()
+            .filter(|player| *player > 0)
+            .map(|player| Command::Partner(player - 1))
+            .ok_or_else(|| format!("'{}' is not a player", player)),
+        ["hint"] => Ok(Command::Hint),
+        ["undo"] => Ok(Command::Undo),
+        ["help"] => Ok(Command::Help),
+        ["quit"] | ["exit"] => Ok(Command::Quit),
+        _ => Err(format!("unknown command '{}', try help", line.trim())),
+    }
+}
+
+fn camel_of(letter: &str) -> Result<Camel, String> {
+    let mut symbols = letter.chars();
+    match (symbols.next(), symbols.next()) {
+        (Some(symbol), None) => super::config::notation().camel(symbol),
+        _ => None,
+    }
+    .ok_or_else(|| format!("'{}' is not a camel", letter))
+}
+
+/// A game in progress, with its history so that turns can be undone.
+pub struct Game<R> {
+    history: History,
+    rng: R,
+    hints: bool,
+}
+
+impl<R> Game<R>
+where
+    R: Rng,
+{
+    /// Sets up a game for the number of players, following the rules.
+    pub fn new(
+        players: usize,
+        rules: RuleSet,
+        mut rng: R,
+        hints: bool,
+    ) -> Result<Self, SetupError> {
+        let state = setup(players, rules, &mut rng)?;
+        Ok(Self {
+            history: History::new(state),
+            rng,
+            hints,
+        })
+    }
+
+    fn state(&self) -> &GameState {
+        self.history.state()
+    }
+
+    /// The action of the command, with the tile of a trap translated to a position in the race.
+    fn action(&self, command: &Command) -> Result<Action, String> {
+        let state = self.state();
+        match *command {
+            Command::Ticket(camel) => state
+                .available_tickets()
+                .into_iter()
+                .find(|ticket| ticket.camel == camel)
+                .map(Action::TakeTicket)
+                .ok_or_else(|| format!("there are no {:?} tickets left", camel)),
+            Command::Winner(camel) => Ok(Action::BetOnWinner(camel)),
+            Command::Loser(camel) => Ok(Action::BetOnLoser(camel)),
+            Command::Trap(marker, tile) => {
+                let start = start_tile(&state.race) + 1;
+                if tile < start {
+                    return Err("traps behind all camels have no effect".to_owned());
+                }
+                Ok(Action::PlaceTrap(tile - start, marker))
+            }
+            Command::Photo(tile) => {
+                let start = start_tile(&state.race) + 1;
+                if tile < start {
+                    return Err("no camel lands behind all camels".to_owned());
+                }
+                Ok(Action::Photograph(tile - start))
+            }
+            Command::Partner(player) => Ok(Action::Partner(player)),
+            Command::Roll | Command::Hint | Command::Undo | Command::Help | Command::Quit => {
+                unreachable!(/* rolls are drawn, the others are not turns */)
+            }
+        }
+    }
+
+    fn take_turn(&mut self, command: Command) -> Result<(), String> {
+        let legs = self.state().legs.len();
+        if command == Command::Roll {
+            let roll = self.roll()?;
+            self.history
+                .apply_roll(roll)
+                .map_err(|problem| problem.to_string())?;
+            println!(
+                "the die shows {}",
+                super::config::notation().localize(&roll.to_string())
+            );
+        } else {
+            let action = self.action(&command)?;
+            self.history
+                .apply(action, &mut self.rng)
+                .map_err(|problem| problem.to_string())?;
+        }
+        if let Some(summary) = self.state().legs.get(legs) {
+            println!("the leg is over\n{}", summary);
+        }
+        Ok(())
+    }
+
+    /// Draws a die from the pyramid and rolls it.
+    fn roll(&mut self) -> Result<Roll, String> {
+        let mut dice: Vec<Camel> = self.state().pyramid.iter().collect();
+        dice.sort();
+        let camel = *dice.choose(&mut self.rng).ok_or("the game is over")?;
+        let face = *[Face::One, Face::Two, Face::Three]
+            .choose(&mut self.rng)
+            .unwrap(/* faces is not empty */);
+        Ok(Roll::from((camel, face)))
+    }
+
+    fn show(&self) {
+        let state = self.state();
+        super::show_board(&Board::from(&state.race));
+        let notation = super::config::notation();
+        println!(
+            "race: {}  pyramid: {}",
+            notation.localize(&state.race.to_string()),
+            notation.localize(&state.pyramid.to_string())
+        );
+        let tickets: Vec<String> = state
+            .available_tickets()
+            .iter()
+            .map(|ticket| format!("{}{}", Marker::Camel(ticket.camel), ticket.value))
+            .collect();
+        println!("tickets: {}", notation.localize(&tickets.join(" ")));
+        for (index, player) in state.players.iter().enumerate() {
+            println!(
+                "player {}: {} coins, {} pyramid tickets",
+                index + 1,
+                player.coins,
+                player.pyramid_tickets
+            );
+        }
+        if self.hints {
+            self.hint();
+        }
+    }
+
+    fn hint(&self) {
+        let state = self.state();
+        let start = start_tile(&state.race) + 1;
+        for (action, value) in state.rank_actions_ahead(state.current).iter().take(3) {
+            let description = match action {
+                Action::PlaceTrap(position, Marker::Oasis) => {
+                    format!("place an oasis on tile {}", position + start)
+                }
+                Action::PlaceTrap(position, _) => {
+                    format!("place a fata morgana on tile {}", position + start)
+                }
+                Action::Photograph(position) => {
+                    format!("place the photographer on tile {}", position + start)
+                }
+                action => action.to_string(),
+            };
+            println!(
+                "hint: {} ({:.2} coins by your next turn)",
+                description,
+                value.to_f64()
+            );
+        }
+    }
+}
+
+/// Reads the turns of the players from standard input until the game is over or ended.
+pub fn run<R: Rng>(mut game: Game<R>) {
+    let stdin = io::stdin();
+    game.show();
+    while !game.state().is_over() {
+        print!("player {}> ", game.state().current + 1);
+        io::stdout().flush().expect("to flush");
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).expect("to read") == 0 {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(Command::Quit) => break,
+            Ok(Command::Help) => println!("{}", HELP),
+            Ok(Command::Hint) => game.hint(),
+            Ok(Command::Undo) => {
+                if game.history.undo() {
+                    game.show();
+                } else {
+                    eprintln!("error: nothing to undo");
+                }
+            }
+            Ok(command) => match game.take_turn(command) {
+                Ok(()) => game.show(),
+                Err(problem) => eprintln!("error: {}", problem),
+            },
+            Err(problem) => eprintln!("error: {}", problem),
+        }
+    }
+    if let Some(standings) = game.state().standings() {
+        println!("the game is over\n{}", standings);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn commands_can_be_parsed() {
+        assert_eq!(parse("roll"), Ok(Command::Roll));
+        assert_eq!(parse("ticket y"), Ok(Command::Ticket(Camel::Yellow)));
+        assert_eq!(parse("trap -7"), Ok(Command::Trap(Marker::FataMorgana, 7)));
+        assert_eq!(parse("partner 2"), Ok(Command::Partner(1)));
+        assert_eq!(parse("photo 9"), Ok(Command::Photo(9)));
+        assert!(parse("partner 0").is_err());
+        assert!(parse("ticket q").is_err());
+    }
+
+    #[test]
+    fn turns_pass_to_the_next_player_and_can_be_undone() {
+        let mut game =
+            Game::new(3, RuleSet::default(), StdRng::seed_from_u64(7), false).expect("to set up");
+
+        game.take_turn(Command::Ticket(Camel::Red))
+            .expect("to be legal");
+        game.take_turn(Command::Roll).expect("to be legal");
+
+        assert_eq!(game.state().current, 2);
+        assert_eq!(game.state().players[0].tickets.len(), 1);
+        assert_eq!(game.state().players[1].pyramid_tickets, 1);
+        assert!(game.history.undo());
+        assert_eq!(game.state().current, 1);
+    }
+}
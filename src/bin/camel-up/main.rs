@@ -0,0 +1,1535 @@
+extern crate camel_up;
+extern crate clap;
+
+mod batch;
+mod config;
+mod game;
+mod hotseat;
+mod odds;
+mod progress;
+mod repl;
+mod state;
+mod stream;
+mod trace;
+mod watch;
+
+use camel_up::{
+    advisor::rank,
+    bets::Ticket,
+    camel::{Camel, Dice, Marker, Race, Roll},
+    fraction::Fraction,
+    game::{analysis::analyze, log::GameLog},
+    i18n::Label,
+    oracle::{
+        diff, expected_landings, explain, matchup, project, project_with_statistics, sample_race,
+        sample_with_progress, tree_size, Distribution, Explanation, LegChances,
+    },
+    query,
+    rules::{self, RuleSet, Supercup},
+    vis::{
+        markdown::table_in,
+        render::{render_board_styled, render_board_with_tickets_styled},
+        types::{start_tile, Board, BOARD_SIZE},
+    },
+    wire,
+};
+use clap::{App, AppSettings, Arg, ArgMatches, Shell, SubCommand};
+use config::{Config, Output};
+use game::Bot;
+use progress::Progress;
+use rand::{rngs::StdRng, SeedableRng};
+use state::State;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    process,
+    str::FromStr,
+    time::Instant,
+};
+use unicode_width::UnicodeWidthStr;
+
+fn main() {
+    let matches = app().get_matches();
+    match Config::load().and_then(|config| config::init(config, &matches)) {
+        Ok(()) => {}
+        Err(problem) => {
+            eprintln!("error: {}", problem);
+            process::exit(exit::PARSE)
+        }
+    }
+    trace::init(verbosity(&matches));
+
+    match matches.subcommand() {
+        ("project", Some(matches)) => project_command(matches),
+        ("advise", Some(matches)) => advise_command(matches),
+        ("trap", Some(matches)) => trap_command(matches),
+        ("compare", Some(matches)) => compare_command(matches),
+        ("repl", Some(matches)) => repl_command(matches),
+        ("render", Some(matches)) => render_command(matches),
+        ("simulate", Some(matches)) => simulate_command(matches),
+        ("game", Some(matches)) => game_command(matches),
+        ("hotseat", Some(matches)) => hotseat_command(matches),
+        ("batch", Some(matches)) => batch_command(matches),
+        ("analyze", Some(matches)) => analyze_command(matches),
+        ("query", Some(matches)) => query_command(matches),
+        ("schema", Some(_)) => println!("{}", wire::SCHEMA),
+        ("serve", Some(matches)) => serve_command(matches),
+        ("export", Some(matches)) => export_command(matches),
+        ("stream", Some(_)) => stream_command(),
+        ("completions", Some(matches)) => completions_command(matches),
+        _ => unreachable!(/* a subcommand is required */),
+    }
+}
+
+/// The number of times `-v` is given, before or after the subcommand.
+fn verbosity(matches: &ArgMatches) -> u64 {
+    let nested = matches
+        .subcommand()
+        .1
+        .map_or(0, |matches| matches.occurrences_of("verbose"));
+    matches.occurrences_of("verbose").max(nested)
+}
+
+/// Describes the command line interface.
+fn app() -> App<'static, 'static> {
+    App::new("Camel Up")
+        .version("1.0")
+        .author("Daan van Berkel <daan.v.berkel.1980@gmail.com>")
+        .about("Calculates odds of which camel is winning")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .help("prints only the result, without notes, boards or explanations")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("traces the computation on standard error, -vv for more detail")
+                .multiple(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("letters")
+                .long("letters")
+                .help("replaces the letters of camels, e.g. r=b,w=s")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("edition")
+                .long("edition")
+                .help("the rules races follow, defaults to classic")
+                .possible_values(&["classic", "second"])
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("lang")
+                .long("lang")
+                .help("the language of camel names and labels, defaults to en")
+                .possible_values(&["en", "nl", "de"])
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("max-tiles")
+                .long("max-tiles")
+                .help("refuses races that span more tiles than the board, e.g. 16")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .help("when to paint the board with colors, defaults to auto")
+                .possible_values(&["auto", "always", "never"])
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            SubCommand::with_name("project")
+                .about("projects the chances of each camel at the end of the leg")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .arg(
+                    Arg::with_name("all")
+                        .short("a")
+                        .long("all")
+                        .help("prints the runner up and loser chances as well"),
+                )
+                .arg(
+                    Arg::with_name("places")
+                        .short("p")
+                        .long("places")
+                        .help("prints the chance of each camel for every place")
+                        .conflicts_with("all"),
+                )
+                .arg(
+                    Arg::with_name("watch")
+                        .short("w")
+                        .long("watch")
+                        .help("reprints the board and chances whenever the state file changes")
+                        .requires("file"),
+                )
+                .arg(
+                    Arg::with_name("samples")
+                        .long("samples")
+                        .help("approximates the chances by sampling this many legs")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("seeds the sampling, so that results can be reproduced")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .help("projects on N threads, 0 follows RAYON_NUM_THREADS or uses a thread per CPU")
+                        .value_name("N")
+                        .takes_value(true)
+                        .conflicts_with_all(&["samples", "explain"]),
+                )
+                .arg(
+                    Arg::with_name("board")
+                        .long("board")
+                        .help("renders the race on the board above the chances"),
+                )
+                .arg(
+                    Arg::with_name("top")
+                        .long("top")
+                        .help("only prints the first N camels")
+                        .value_name("N")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("sort")
+                        .long("sort")
+                        .help("orders the camels by their chance to win, be runner up or lose, defaults to win")
+                        .possible_values(&["win", "runner-up", "lose"])
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("prints a plain line or a markdown table, defaults to plain")
+                        .possible_values(&["plain", "markdown"])
+                        .conflicts_with("places")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("what-if")
+                        .long("what-if")
+                        .help("performs the comma separated rolls before projecting, e.g. r2,g1")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("matchup")
+                        .long("matchup")
+                        .help("prints the chance that the first camel ends the leg ahead of the second, e.g. r w")
+                        .value_names(&["camel", "rival"])
+                        .number_of_values(2)
+                        .conflicts_with_all(&["all", "places", "samples"]),
+                )
+                .arg(
+                    Arg::with_name("explain")
+                        .long("explain")
+                        .help("prints the outcome counts behind the chances, with example rolls")
+                        .conflicts_with("samples"),
+                )
+                .args(&format_arguments()),
+        )
+        .subcommand(
+            SubCommand::with_name("advise")
+                .about("ranks the legal actions by expected coins")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .arg(
+                    Arg::with_name("tickets")
+                        .short("t")
+                        .long("tickets")
+                        .help("comma separated betting tickets that are available, e.g. r5,g3")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("coins")
+                        .short("c")
+                        .long("coins")
+                        .help("the number of coins the player currently has")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("samples")
+                        .long("samples")
+                        .help(
+                            "the number of races sampled for bets on the overall winner and loser",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("seeds the sampling, so that results can be reproduced")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("trap")
+                .about("shows the effect of placing a trap on each tile")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .args(&format_arguments()),
+        )
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("prints how the chances of each camel change from race a to race b")
+                .arg(race_argument("race-a"))
+                .arg(race_argument("race-b"))
+                .arg(dice_argument())
+                .args(&format_arguments()),
+        )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("starts an interactive session that keeps track of the race")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument()),
+        )
+        .subcommand(
+            SubCommand::with_name("render")
+                .about("renders the race on the board")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .arg(
+                    Arg::with_name("tickets")
+                        .short("t")
+                        .long("tickets")
+                        .help("comma separated betting tickets to show next to the board, e.g. r5,g3")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("simulate")
+                .about("estimates the chances of each camel to win and lose the race by sampling complete races")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .arg(
+                    Arg::with_name("samples")
+                        .long("samples")
+                        .help("the number of complete races to sample")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("seeds the sampling, so that results can be reproduced")
+                        .takes_value(true),
+                )
+                .args(&format_arguments()),
+        )
+        .subcommand(
+            SubCommand::with_name("game")
+                .about("plays complete games between bots and reports their scores")
+                .arg(
+                    Arg::with_name("players")
+                        .long("players")
+                        .help("the number of players, defaults to the number of bots")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("bots")
+                        .long("bots")
+                        .help("comma separated bots for the players: random, greedy, ev, roll or search, defaults to greedy")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("depth")
+                        .long("depth")
+                        .help("the number of turns the search bot looks ahead, defaults to 2")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("games")
+                        .short("n")
+                        .long("games")
+                        .help("the number of games to play, defaults to 10")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("seeds the games, so that results can be reproduced")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("hotseat")
+                .about("plays a game with several players taking turns at this terminal")
+                .arg(
+                    Arg::with_name("players")
+                        .long("players")
+                        .help("the number of players, from 3 to 8, defaults to 3")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("hints")
+                        .long("hints")
+                        .help("shows the best actions according to the oracle on every turn"),
+                )
+                .arg(
+                    Arg::with_name("supercup")
+                        .long("supercup")
+                        .help("plays with the modules of the Supercup expansion"),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("seeds the setup and the dice, so that a game can be replayed")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("batch")
+                .about("projects the positions in a file, one race and dice per line")
+                .arg(
+                    Arg::with_name("positions")
+                        .help("the file with positions, use - for standard input")
+                        .required(true),
+                )
+                .args(&format_arguments()),
+        )
+        .subcommand(
+            SubCommand::with_name("analyze")
+                .about("compares every decision in a game log with the best decision")
+                .arg(
+                    Arg::with_name("log")
+                        .help("the file with the game log, use - for standard input")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("answers a query like 'P(win=r | race=r,,w; rolled=w2)'")
+                .arg(
+                    Arg::with_name("query")
+                        .help("the query, see the documentation of the query module")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("schema")
+                .about("prints the JSON Schema of the wire format that JSON clients use"),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("answers questions for the oracle over HTTP, needs the server feature")
+                .arg(
+                    Arg::with_name("address")
+                        .long("address")
+                        .help("the address to listen on, defaults to 127.0.0.1:8080")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("exports placements, matchups and expected values for spreadsheets, needs the spreadsheet feature")
+                .arg(race_argument("race"))
+                .arg(state_argument())
+                .arg(dice_argument())
+                .arg(
+                    Arg::with_name("path")
+                        .help("a directory for CSV files, or a file ending in .xlsx for a workbook")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("stream").about(
+                "answers JSON requests on standard input, one per line, with JSON on standard output",
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("generates a completion script for a shell")
+                .arg(
+                    Arg::with_name("shell")
+                        .help("the shell to generate completions for")
+                        .possible_values(&Shell::variants())
+                        .required(true),
+                ),
+        )
+}
+
+fn query_command(matches: &ArgMatches) {
+    let query = matches.value_of("query").unwrap(/* query is required */);
+    match query::ask(query) {
+        Ok(answer) => println!("{}", answer),
+        Err(problem) => {
+            eprintln!("error: {}", problem);
+            process::exit(exit::PARSE);
+        }
+    }
+}
+
+fn stream_command() {
+    if let Err(problem) = stream::run() {
+        eprintln!("error: {}", problem);
+        process::exit(exit::COMPUTATION);
+    }
+}
+
+#[cfg(feature = "server")]
+fn serve_command(matches: &ArgMatches) {
+    let address = matches.value_of("address").unwrap_or("127.0.0.1:8080");
+    eprintln!("note: serving on http://{}", address);
+    if let Err(problem) = camel_up::server::serve(address) {
+        eprintln!("error: {}", problem);
+        process::exit(exit::COMPUTATION);
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn serve_command(_matches: &ArgMatches) {
+    eprintln!("error: this build does not include the server, build with --features server");
+    process::exit(exit::COMPUTATION);
+}
+
+#[cfg(feature = "spreadsheet")]
+fn export_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+    let path = matches.value_of("path").unwrap(/* path is required */);
+    let rules = RuleSet::from(config::edition());
+    let export = camel_up::spreadsheet::Export::new(&race, &dice, &rules);
+    let result = if path.ends_with(".xlsx") {
+        export.write_xlsx(path)
+    } else {
+        export.write_csv(path)
+    };
+    if let Err(problem) = result {
+        eprintln!("error: {}", problem);
+        process::exit(exit::COMPUTATION);
+    }
+}
+
+#[cfg(not(feature = "spreadsheet"))]
+fn export_command(_matches: &ArgMatches) {
+    eprintln!("error: this build does not include the export, build with --features spreadsheet");
+    process::exit(exit::COMPUTATION);
+}
+
+fn game_command(matches: &ArgMatches) {
+    let mut bots: Vec<Bot> = matches
+        .value_of("bots")
+        .unwrap_or("greedy")
+        .split(',')
+        .map(|bot| {
+            bot.parse::<Bot>().unwrap_or_else(|problem| {
+                eprintln!("error: {}", problem);
+                process::exit(exit::PARSE)
+            })
+        })
+        .collect();
+    if let Some(depth) = matches.value_of("depth") {
+        let depth = number_of("depth", depth);
+        for bot in bots.iter_mut() {
+            if let Bot::Search(_) = bot {
+                *bot = Bot::Search(depth);
+            }
+        }
+    }
+    if let Some(players) = matches.value_of("players") {
+        let players: usize = number_of("players", players);
+        if players < 2 || (bots.len() > 1 && bots.len() != players) {
+            eprintln!("error: there should be at least two players, and a bot for each player");
+            process::exit(exit::PARSE);
+        }
+        bots = bots.iter().cycle().take(players).copied().collect();
+    } else if bots.len() < 2 {
+        bots = vec![bots[0]; 4];
+    }
+    let games = matches
+        .value_of("games")
+        .map(|games| number_of("games", games))
+        .unwrap_or(10);
+    let mut rng = StdRng::seed_from_u64(seed_of(matches, false));
+
+    let statistics = game::tournament(&bots, games, &mut rng).unwrap_or_else(|problem| {
+        eprintln!("error: {}", problem);
+        process::exit(exit::PARSE)
+    });
+    println!(
+        "{:<8}{:<8}{:>12}{:>8}",
+        "player", "bot", "mean coins", "wins"
+    );
+    for (player, bot) in bots.iter().enumerate() {
+        println!(
+            "{:<8}{:<8}{:>12.2}{:>8.1}",
+            player + 1,
+            bot.to_string(),
+            statistics.total[player] as f64 / statistics.games.max(1) as f64,
+            statistics.wins[player]
+        );
+    }
+}
+
+fn hotseat_command(matches: &ArgMatches) {
+    let players: usize = matches
+        .value_of("players")
+        .map(|players| number_of("players", players))
+        .unwrap_or(*rules::PLAYERS.start());
+    let mut rules = RuleSet::from(config::edition());
+    if matches.is_present("supercup") {
+        rules = rules.with_supercup(Supercup::default());
+    }
+    let allowed = rules.players.clone();
+    if !allowed.contains(&players) {
+        eprintln!(
+            "error: the rules are written for {} to {} players",
+            allowed.start(),
+            allowed.end()
+        );
+        process::exit(exit::PARSE);
+    }
+    let rng = StdRng::seed_from_u64(seed_of(matches, false));
+
+    let game = hotseat::Game::new(players, rules, rng, matches.is_present("hints")).unwrap_or_else(
+        |problem| {
+            eprintln!("error: {}", problem);
+            process::exit(exit::PARSE)
+        },
+    );
+
+    hotseat::run(game);
+}
+
+fn batch_command(matches: &ArgMatches) {
+    let path = matches.value_of("positions").unwrap(/* positions are required */);
+    let content = if path == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content).map(|_| content)
+    } else {
+        fs::read_to_string(path)
+    }
+    .unwrap_or_else(|error| {
+        eprintln!("error: could not read {}: {}", path, error);
+        process::exit(exit::PARSE)
+    });
+
+    let mut failed = false;
+    for result in batch::run(&content, &Format::of(matches)) {
+        match result {
+            Ok(line) => println!("{}", line),
+            Err(problem) => {
+                eprintln!("error: {}", problem);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(exit::PARSE);
+    }
+}
+
+fn analyze_command(matches: &ArgMatches) {
+    let path = matches.value_of("log").unwrap(/* log is required */);
+    let content = if path == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content).map(|_| content)
+    } else {
+        fs::read_to_string(path)
+    }
+    .unwrap_or_else(|error| {
+        eprintln!("error: could not read {}: {}", path, error);
+        process::exit(exit::PARSE)
+    });
+    let mut log = content.parse::<GameLog>().unwrap_or_else(|problem| {
+        eprintln!("error: {}", problem);
+        process::exit(exit::PARSE)
+    });
+    log.race = log.race.with_edition(config::edition());
+
+    match analyze(&log) {
+        Ok(analysis) => print!("{}", analysis),
+        Err(problem) => {
+            eprintln!("error: {}", problem);
+            process::exit(exit::COMPUTATION)
+        }
+    }
+}
+
+fn completions_command(matches: &ArgMatches) {
+    let shell = matches
+        .value_of("shell")
+        .unwrap(/* shell is required */)
+        .parse::<Shell>()
+        .unwrap(/* shell is one of the possible values */);
+
+    app().gen_completions_to("camel-up", shell, &mut io::stdout());
+}
+
+fn race_argument(name: &str) -> Arg<'_, '_> {
+    let argument = Arg::with_name(name)
+        .long(name)
+        .help("describe the current race")
+        .required(true)
+        .takes_value(true);
+    if name == "race" {
+        argument.short("r").required_unless("file")
+    } else {
+        argument
+    }
+}
+
+fn state_argument<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("file")
+        .short("f")
+        .long("file")
+        .help("reads the race and dice from a TOML file, use - for standard input")
+        .conflicts_with("race")
+        .takes_value(true)
+}
+
+fn dice_argument<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("dice")
+        .short("d")
+        .long("dice")
+        .help("determines which dice are present, defaults to the dice of the camels in the race")
+        .takes_value(true)
+}
+
+fn format_arguments<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("percent")
+            .long("percent")
+            .help("prints chances as percentages instead of fractions"),
+        Arg::with_name("precision")
+            .long("precision")
+            .help("number of decimals for percentages, implies --percent, defaults to 1")
+            .takes_value(true),
+        Arg::with_name("odds")
+            .long("odds")
+            .help("prints chances as odds, e.g. 2:1 on or 3.00")
+            .possible_values(&["fractional", "decimal"])
+            .conflicts_with_all(&["percent", "precision"])
+            .takes_value(true),
+    ]
+}
+
+/// How chances are printed.
+enum Format {
+    Fraction,
+    Percent(usize),
+    FractionalOdds,
+    DecimalOdds,
+}
+
+impl Format {
+    fn of(matches: &ArgMatches) -> Self {
+        let config = config::current();
+        let precision = matches
+            .value_of("precision")
+            .map(|precision| number_of("precision", precision));
+        match matches.value_of("odds") {
+            Some("fractional") => return Format::FractionalOdds,
+            Some("decimal") => return Format::DecimalOdds,
+            _ => {}
+        }
+        if matches.is_present("percent") || precision.is_some() || config.percent == Some(true) {
+            Format::Percent(precision.or(config.precision).unwrap_or(1))
+        } else {
+            Format::Fraction
+        }
+    }
+
+    fn apply(&self, fraction: &Fraction) -> String {
+        match self {
+            Format::Fraction => format!("{}", fraction),
+            Format::Percent(precision) => {
+                format!("{:.*}%", precision, fraction.to_f64() * 100.0)
+            }
+            Format::FractionalOdds => odds::fractional(fraction),
+            Format::DecimalOdds => odds::decimal(fraction),
+        }
+    }
+
+    /// Applies the format to a change in chance, marking increases with a plus.
+    fn signed(&self, fraction: &Fraction) -> String {
+        if *fraction > Fraction::zero() {
+            format!("+{}", self.apply(fraction))
+        } else {
+            self.apply(fraction)
+        }
+    }
+}
+
+/// Exit codes, so that scripts can tell what went wrong.
+///
+/// Mistakes in the use of the arguments themselves exit with 1.
+mod exit {
+    /// The input could not be read or parsed.
+    pub const PARSE: i32 = 2;
+    /// The input is fine, but the result could not be computed.
+    pub const COMPUTATION: i32 = 3;
+}
+
+fn project_command(matches: &ArgMatches) {
+    if matches.is_present("watch") {
+        let path = matches.value_of("file").unwrap(/* watch requires a file */);
+        watch::watch(path, || {
+            if let Some((race, dice)) = load(path, matches) {
+                if race.positions().contains(&Marker::Finish) && !matches.is_present("quiet") {
+                    show_board(&Board::from(&race));
+                }
+                show_projection(&race, &dice, matches);
+            }
+        });
+    }
+    let (race, dice) = state_of(matches);
+
+    show_projection(&race, &dice, matches);
+}
+
+/// Above this number of nodes an exact projection takes too long, and sampling is used instead.
+const NODE_BUDGET: u64 = 10_000_000;
+
+/// The number of samples to use when the node budget is exceeded and no number is given.
+const DEFAULT_SAMPLES: usize = 100_000;
+/// Complete races take many rolls, so fewer of them are sampled by default.
+const DEFAULT_RACE_SAMPLES: usize = 10_000;
+
+fn show_projection(race: &Race, dice: &Dice, matches: &ArgMatches) {
+    let (race, dice) = &match matches.value_of("what-if") {
+        Some(rolls) => what_if(race, dice, rolls).unwrap_or_else(|problem| {
+            eprintln!("error: could not apply --what-if: {}", problem);
+            process::exit(exit::PARSE)
+        }),
+        None => (*race, *dice),
+    };
+    if let Some(letters) = matches.values_of("matchup") {
+        let camels: Vec<Camel> = letters.map(camel_of).collect();
+        let chance = matchup(race, dice, camels[0], camels[1]);
+        println!(
+            "P({} ahead of {}) = {}",
+            config::language().camel(camels[0]),
+            config::language().camel(camels[1]),
+            Format::of(matches).apply(&chance)
+        );
+        return;
+    }
+    let samples = matches
+        .value_of("samples")
+        .map(|samples| number_of("samples", samples));
+    let quiet = matches.is_present("quiet");
+    let result = match samples {
+        None if matches.is_present("threads") && tree_size(dice) <= NODE_BUDGET => {
+            project_on_threads(race, dice, matches)
+        }
+        None if tree_size(dice) <= NODE_BUDGET => {
+            let mut progress = Progress::new("nodes", quiet);
+            let start = Instant::now();
+            let (result, statistics) = project_with_statistics(race, dice, |done, total| {
+                trace::event(
+                    trace::DETAIL,
+                    "expanded",
+                    &[
+                        ("nodes", &done),
+                        ("total", &total),
+                        ("elapsed_ms", &trace::millis(start.elapsed())),
+                    ],
+                );
+                progress.update(done, total)
+            });
+            progress.finish();
+            trace::event(
+                trace::SUMMARY,
+                "projection",
+                &[
+                    ("nodes", &statistics.nodes),
+                    ("leaves", &statistics.leaves),
+                    ("expansion_ms", &trace::millis(statistics.expansion)),
+                    ("counting_ms", &trace::millis(statistics.counting)),
+                ],
+            );
+            result
+        }
+        _ => {
+            let samples = samples.unwrap_or(DEFAULT_SAMPLES);
+            let mut rng = StdRng::seed_from_u64(seed_of(matches, quiet));
+            if !quiet {
+                eprintln!("note: approximate result, based on {} samples", samples);
+            }
+            let mut progress = Progress::new("samples", quiet);
+            let start = Instant::now();
+            let result = sample_with_progress(race, dice, samples, &mut rng, |done, total| {
+                trace::event(
+                    trace::DETAIL,
+                    "sampled",
+                    &[
+                        ("samples", &done),
+                        ("total", &total),
+                        ("elapsed_ms", &trace::millis(start.elapsed())),
+                    ],
+                );
+                progress.update(done, total)
+            });
+            progress.finish();
+            trace::event(
+                trace::SUMMARY,
+                "sampling",
+                &[
+                    ("samples", &samples),
+                    ("elapsed_ms", &trace::millis(start.elapsed())),
+                ],
+            );
+            result
+        }
+    };
+    if matches.is_present("board") && !quiet {
+        if let Some(board) = board_of(race) {
+            show_board(&board);
+        }
+    }
+    let selection = Selection::of(matches);
+    let output = match matches.value_of("format") {
+        Some("markdown") => Output::Markdown,
+        Some(_) => Output::Plain,
+        None => config::current().format.unwrap_or(Output::Plain),
+    };
+    if output == Output::Markdown && !matches.is_present("places") {
+        let format = Format::of(matches);
+        let camels = selected(&result, &selection);
+        println!(
+            "{}",
+            table_in(config::language(), &result, &camels, &|chance| {
+                format.apply(chance)
+            })
+        );
+    } else if matches.is_present("all") {
+        print_table(&result, &Format::of(matches), &selection);
+    } else if matches.is_present("places") {
+        print_places(&result, &Format::of(matches), &selection);
+    } else {
+        let mut elements = ordered_by(&result, &selection.sort);
+        selection.limit(&mut elements);
+        print(&elements, &Format::of(matches));
+    }
+    if matches.is_present("explain") && !matches.is_present("quiet") {
+        if tree_size(dice) > NODE_BUDGET {
+            eprintln!("error: too many rolls to explain, remove some dice");
+            process::exit(exit::COMPUTATION);
+        }
+        print_explanation(&explain(race, dice, EXAMPLES), &ordered(&result));
+    }
+}
+
+/// The number of example roll sequences that are shown for each winning camel.
+const EXAMPLES: usize = 3;
+
+/// Prints how often each camel wins, is runner up and loses, together with examples of winning rolls.
+fn print_explanation(explanation: &Explanation, camels: &[(Camel, Fraction)]) {
+    for (camel, _) in camels {
+        let count = |outcomes: &HashMap<Camel, usize>| outcomes.get(camel).copied().unwrap_or(0);
+        println!(
+            "{} wins in {} of {} orderings, is runner up in {} and loses in {}",
+            config::language().camel(*camel),
+            count(&explanation.winner),
+            explanation.total,
+            count(&explanation.runner_up),
+            count(&explanation.loser)
+        );
+        for rolls in explanation.examples.get(camel).into_iter().flatten() {
+            let rolls: Vec<String> = rolls
+                .iter()
+                .map(|roll| config::notation().localize(&roll.to_string()))
+                .collect();
+            println!("  {}", rolls.join(" "));
+        }
+    }
+}
+
+/// The outcome camels are ordered by.
+enum Sort {
+    Win,
+    RunnerUp,
+    Lose,
+}
+
+impl Sort {
+    fn distribution<'a>(&self, chances: &'a LegChances) -> &'a Distribution {
+        match self {
+            Sort::Win => &chances.winner,
+            Sort::RunnerUp => &chances.runner_up,
+            Sort::Lose => &chances.loser,
+        }
+    }
+}
+
+/// Which camels are printed, and in what order.
+struct Selection {
+    sort: Sort,
+    top: Option<usize>,
+}
+
+impl Selection {
+    fn of(matches: &ArgMatches) -> Self {
+        let sort = match matches.value_of("sort") {
+            Some("runner-up") => Sort::RunnerUp,
+            Some("lose") => Sort::Lose,
+            _ => Sort::Win,
+        };
+        let top = matches.value_of("top").map(|top| number_of("top", top));
+        Self { sort, top }
+    }
+
+    fn limit<T>(&self, elements: &mut Vec<T>) {
+        if let Some(top) = self.top {
+            elements.truncate(top);
+        }
+    }
+}
+
+fn number_of<T: FromStr>(argument: &str, value: &str) -> T {
+    value.parse::<T>().unwrap_or_else(|_| {
+        eprintln!(
+            "error: could not parse --{}: {} is not a number",
+            argument, value
+        );
+        process::exit(exit::PARSE)
+    })
+}
+
+/// Projects on the number of threads passed with --threads, where zero lets the thread pool decide.
+#[cfg(feature = "parallel")]
+fn project_on_threads(race: &Race, dice: &Dice, matches: &ArgMatches) -> LegChances {
+    let threads = matches
+        .value_of("threads")
+        .map(|threads| number_of::<usize>("threads", threads))
+        .filter(|threads| *threads > 0);
+    camel_up::oracle::project_parallel(race, dice, threads).unwrap_or_else(|problem| {
+        eprintln!("error: could not start the threads: {}", problem);
+        process::exit(exit::COMPUTATION)
+    })
+}
+
+#[cfg(not(feature = "parallel"))]
+fn project_on_threads(_race: &Race, _dice: &Dice, _matches: &ArgMatches) -> LegChances {
+    eprintln!("error: this build does not include threads, build with --features parallel");
+    process::exit(exit::COMPUTATION);
+}
+
+/// The seed passed with --seed, or a fresh one that is reported so that the run can be reproduced.
+fn seed_of(matches: &ArgMatches, quiet: bool) -> u64 {
+    match matches.value_of("seed") {
+        Some(seed) => number_of("seed", seed),
+        None => {
+            let seed = rand::random();
+            if !quiet {
+                eprintln!(
+                    "note: seeded with {}, pass --seed {} to reproduce",
+                    seed, seed
+                );
+            }
+            seed
+        }
+    }
+}
+
+fn advise_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+    let tickets = tickets_of(matches);
+    let coins = matches
+        .value_of("coins")
+        .map(|coins| number_of::<i64>("coins", coins))
+        .unwrap_or(0);
+
+    let leg = project(&race, &dice);
+    let overall = if race.positions().contains(&Marker::Finish) {
+        let (samples, mut rng) = race_sampling_of(matches);
+        Some(sample_race(&race, &dice, samples, &mut rng))
+    } else {
+        None
+    };
+    let ranked = rank(&leg, &tickets, overall.as_ref());
+    let width = ranked
+        .iter()
+        .map(|(action, _)| action.to_string().width())
+        .max()
+        .unwrap_or(0);
+    for (action, value) in ranked {
+        println!(
+            "{:<width$}  {:>8.3}  {:>8.3}",
+            action.to_string(),
+            value.to_f64(),
+            (value + coins).to_f64(),
+            width = width
+        );
+    }
+}
+
+/// Prints, for every tile a trap can be placed on, the coins it is expected to earn and the change in win chance of each camel.
+///
+/// Tiles are numbered like the board, the best placement is marked with an asterisk.
+fn trap_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+    let format = Format::of(matches);
+
+    let base = project(&race, &dice);
+    let camels = race.ranking();
+    let reach = camels
+        .first()
+        .and_then(|front| race.position_of(*front))
+        .unwrap_or(0)
+        + 3;
+    let mut placements: Vec<(usize, Marker, Fraction, Vec<Fraction>)> = Vec::new();
+    for position in 1..=reach {
+        for trap in [Marker::Oasis, Marker::FataMorgana] {
+            let trapped = race.place_trap(position, trap).ok();
+            let placement = trapped.and_then(|trapped| {
+                expected_landings(&trapped, &dice, position).map(|landings| (trapped, landings))
+            });
+            if let Some((trapped, landings)) = placement {
+                let chances = project(&trapped, &dice);
+                let deltas = camels
+                    .iter()
+                    .map(|camel| chances.winner[camel] - base.winner[camel])
+                    .collect();
+                placements.push((position, trap, landings, deltas));
+            }
+        }
+    }
+    let best = placements
+        .iter()
+        .map(|(_, _, landings, _)| *landings)
+        .max()
+        .unwrap_or_default();
+
+    let start = start_tile(&race) + 1;
+    print!("  {:>4}{:>6}{:>10}", "tile", "trap", "coins");
+    for camel in &camels {
+        print!("{:>10}", config::language().camel(*camel));
+    }
+    println!();
+    for (position, trap, landings, deltas) in placements {
+        let marker = if landings == best { "*" } else { " " };
+        print!(
+            "{} {:>4}{:>6}{:>10}",
+            marker,
+            start + position,
+            trap.to_string(),
+            format!("{:.3}", landings.to_f64())
+        );
+        for delta in deltas {
+            print!("{:>10}", format.apply(&delta));
+        }
+        println!();
+    }
+}
+
+fn compare_command(matches: &ArgMatches) {
+    let (race_a, race_b) = match (race_of(matches, "race-a"), race_of(matches, "race-b")) {
+        (Some(race_a), Some(race_b)) => (race_a, race_b),
+        _ => process::exit(exit::PARSE),
+    };
+    let dice = dice_of(matches, &race_a).unwrap_or_else(|| process::exit(exit::PARSE));
+    for race in [&race_a, &race_b] {
+        if let Err(problem) = race.check_dice(&dice) {
+            eprintln!("error: {}", problem);
+            process::exit(exit::PARSE)
+        }
+    }
+
+    let format = Format::of(matches);
+    if let Format::FractionalOdds | Format::DecimalOdds = format {
+        eprintln!("error: odds can not express a difference in chances");
+        process::exit(exit::PARSE);
+    }
+
+    let change = diff(&project(&race_a, &dice), &project(&race_b, &dice));
+    let mut camels: Vec<Camel> = change.winner.values().map(|(camel, _)| *camel).collect();
+    camels.sort_by(|left, right| {
+        (change.winner[right], change.runner_up[right])
+            .cmp(&(change.winner[left], change.runner_up[left]))
+    });
+
+    print_header();
+    for camel in camels {
+        println!(
+            "{:<8}{:>10}{:>10}{:>10}",
+            config::language().camel(camel),
+            format.signed(&change.winner[&camel]),
+            format.signed(&change.runner_up[&camel]),
+            format.signed(&change.loser[&camel]),
+        );
+    }
+}
+
+fn repl_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+
+    match repl::Session::new(race, dice) {
+        Ok(session) => repl::run(session),
+        Err(problem) => {
+            eprintln!("error: {}", problem);
+            process::exit(exit::PARSE)
+        }
+    }
+}
+
+fn render_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+
+    let board = board_of(&race).unwrap_or_else(|| process::exit(exit::PARSE));
+    if matches.is_present("tickets") {
+        let chances = project(&race, &dice);
+        let lines = render_board_with_tickets_styled(
+            &board,
+            &tickets_of(matches),
+            &chances,
+            config::style(),
+        );
+        for line in lines {
+            println!("{}", line);
+        }
+    } else {
+        show_board(&board);
+    }
+}
+
+/// Prints the chances of each camel to be the overall winner and loser, estimated by sampling complete races.
+fn simulate_command(matches: &ArgMatches) {
+    let (race, dice) = state_of(matches);
+    if !race.positions().contains(&Marker::Finish) {
+        eprintln!("error: a race needs a finish to end, e.g. r,,y,,,!");
+        process::exit(exit::PARSE);
+    }
+    let format = Format::of(matches);
+
+    let (samples, mut rng) = race_sampling_of(matches);
+    let chances = sample_race(&race, &dice, samples, &mut rng);
+    let mut camels: Vec<Camel> = chances
+        .places
+        .iter()
+        .flat_map(Distribution::values)
+        .map(|(camel, _)| *camel)
+        .collect();
+    camels.sort();
+    camels.dedup();
+    camels.sort_by(|left, right| {
+        (chances.winner[right], chances.loser[left])
+            .cmp(&(chances.winner[left], chances.loser[right]))
+    });
+
+    let language = config::language();
+    println!(
+        "{:<8}{:>10}{:>10}",
+        language.label(Label::Camel),
+        language.label(Label::Winner),
+        language.label(Label::Loser)
+    );
+    for camel in camels {
+        println!(
+            "{:<8}{:>10}{:>10}",
+            language.camel(camel),
+            format.apply(&chances.winner[&camel]),
+            format.apply(&chances.loser[&camel]),
+        );
+    }
+}
+
+/// The number of complete races to sample and the generator to sample them with, from the samples and seed arguments.
+fn race_sampling_of(matches: &ArgMatches) -> (usize, StdRng) {
+    let samples = matches
+        .value_of("samples")
+        .map(|samples| number_of::<usize>("samples", samples))
+        .unwrap_or(DEFAULT_RACE_SAMPLES);
+    let seed = matches
+        .value_of("seed")
+        .map(|seed| number_of::<u64>("seed", seed))
+        .unwrap_or(0);
+    (samples, StdRng::seed_from_u64(seed))
+}
+
+/// The betting tickets in the comma separated tickets argument, if any.
+fn tickets_of(matches: &ArgMatches) -> Vec<Ticket> {
+    matches
+        .value_of("tickets")
+        .map(|tickets| {
+            tickets
+                .split(',')
+                .map(|ticket| {
+                    parse_ticket(ticket).unwrap_or_else(|error| {
+                        eprintln!("error: could not parse ticket {}: {:?}", ticket, error);
+                        process::exit(exit::PARSE)
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Performs the comma separated rolls in order, removing their dice from the pyramid.
+fn what_if(race: &Race, dice: &Dice, rolls: &str) -> Result<(Race, Dice), String> {
+    let mut race = *race;
+    let mut dice = *dice;
+    for description in rolls.split(',') {
+        let roll = config::notation()
+            .standardize(description)
+            .map_err(|(_, symbol)| format!("'{}' is not a camel", symbol))?
+            .parse::<Roll>()
+            .map_err(|error| format!("{}: {}", description, error))?;
+        if !dice.contains(roll.camel()) {
+            return Err(format!("the {:?} die is not in the pyramid", roll.camel()));
+        }
+        race = race
+            .perform_checked(roll)
+            .map_err(|problem| problem.to_string())?;
+        dice = dice.remove(roll.camel());
+    }
+    Ok((race, dice))
+}
+
+/// The camel for a single letter, exiting when it is not a camel.
+fn camel_of(letter: &str) -> Camel {
+    let mut symbols = letter.chars();
+    match (symbols.next(), symbols.next()) {
+        (Some(symbol), None) => config::notation().camel(symbol),
+        _ => None,
+    }
+    .unwrap_or_else(|| {
+        eprintln!("error: '{}' is not a camel", letter);
+        process::exit(exit::PARSE)
+    })
+}
+
+fn parse_ticket(description: &str) -> Result<Ticket, String> {
+    config::notation()
+        .standardize(description)
+        .map_err(|(_, symbol)| format!("'{}' is not a camel", symbol))?
+        .parse::<Ticket>()
+        .map_err(|error| format!("{:?}", error))
+}
+
+/// Prints the board, in the style the configuration asks for.
+fn show_board(board: &Board) {
+    for line in render_board_styled(board, config::style()) {
+        println!("{}", line);
+    }
+}
+
+/// The race on the board, or a note when the race has no finish to place it on the board by or does not fit on the board.
+fn board_of(race: &Race) -> Option<Board> {
+    if !race.positions().contains(&Marker::Finish) {
+        eprintln!("note: the board needs a race with a finish, e.g. r,,y,,,!");
+        None
+    } else if race.tiles() >= BOARD_SIZE {
+        // the board shows the finish on its last tile
+        eprintln!(
+            "note: the board has {} tiles before the finish, the race spans {}",
+            BOARD_SIZE - 1,
+            race.tiles()
+        );
+        None
+    } else {
+        Some(Board::from(race))
+    }
+}
+
+/// Determines the race and dice either from the arguments or from the state file.
+fn state_of(matches: &ArgMatches) -> (Race, Dice) {
+    let state = match matches.value_of("file") {
+        Some(path) => load(path, matches),
+        None => race_of(matches, "race").and_then(|race| {
+            let dice = dice_of(matches, &race)?;
+            Some((race, dice))
+        }),
+    };
+    let (race, dice) = state.unwrap_or_else(|| process::exit(exit::PARSE));
+    if let Err(problem) = race.check_dice(&dice) {
+        eprintln!("error: {}", problem);
+        process::exit(exit::PARSE)
+    }
+    (race, dice)
+}
+
+/// Loads the race and dice from the state file, reporting any problems.
+///
+/// Dice given as an argument take precedence over dice in the state file.
+fn load(path: &str, matches: &ArgMatches) -> Option<(Race, Dice)> {
+    let state = State::read(path)
+        .map_err(|problem| eprintln!("error: {}", problem))
+        .ok()?;
+    let race = parse_race(&format!("race in {}", path), &state.race)?;
+    let dice = match (matches.value_of("dice"), &state.dice) {
+        (None, Some(description)) => parse_dice(&format!("dice in {}", path), description)?,
+        _ => dice_of(matches, &race)?,
+    };
+    Some((race, dice))
+}
+
+/// The dice in the dice argument, or the dice of the camels in the race when the argument is absent.
+fn dice_of(matches: &ArgMatches, race: &Race) -> Option<Dice> {
+    match matches.value_of("dice") {
+        Some(description) => parse_dice("--dice", description),
+        None => Some(Dice::from(race)),
+    }
+}
+
+fn parse_dice(source: &str, description: &str) -> Option<Dice> {
+    config::notation()
+        .parse_dice(description)
+        .map_err(|error| report(source, description, error.position(), &error))
+        .ok()
+}
+
+fn race_of(matches: &ArgMatches, argument: &str) -> Option<Race> {
+    let description = matches.value_of(argument).unwrap(/* race is required */);
+    parse_race(&format!("--{}", argument), description)
+}
+
+fn parse_race(source: &str, description: &str) -> Option<Race> {
+    config::notation()
+        .parse_race(description)
+        .map(|race| race.with_edition(config::edition()))
+        .and_then(|race| match config::max_tiles() {
+            Some(tiles) => race.check_length(tiles).map(|_| race),
+            None => Ok(race),
+        })
+        .map_err(|error| report(source, description, error.position(), &error))
+        .ok()
+}
+
+/// Reports which input failed to parse, underlining the offending character with a caret.
+fn report(source: &str, input: &str, position: usize, problem: &dyn Display) {
+    let preceding: String = input.chars().take(position).collect();
+    eprintln!("error: could not parse {}: {}", source, problem);
+    eprintln!("  {}", input);
+    eprintln!(
+        "  {}^",
+        " ".repeat(UnicodeWidthStr::width(preceding.as_str()))
+    );
+}
+
+fn ordered(chances: &LegChances) -> Vec<(Camel, Fraction)> {
+    ordered_by(chances, &Sort::Win)
+}
+
+fn ordered_by(chances: &LegChances, sort: &Sort) -> Vec<(Camel, Fraction)> {
+    let mut ordered: Vec<(Camel, Fraction)> = sort
+        .distribution(chances)
+        .values()
+        .map(|(k, v)| (*k, *v))
+        .collect();
+    ordered.sort_by(|(_, left), (_, right)| right.cmp(left));
+    ordered
+}
+
+/// Prints a table with the winner, runner up and loser chances of each camel, ordered by the selected chance.
+fn print_table(chances: &LegChances, format: &Format, selection: &Selection) {
+    let camels = selected(chances, selection);
+
+    print_header();
+    for camel in camels {
+        println!(
+            "{:<8}{:>10}{:>10}{:>10}",
+            config::language().camel(camel),
+            format.apply(&chances.winner[&camel]),
+            format.apply(&chances.runner_up[&camel]),
+            format.apply(&chances.loser[&camel]),
+        );
+    }
+}
+
+/// Prints the headings of a table with the winner, runner up and loser chances.
+fn print_header() {
+    let language = config::language();
+    println!(
+        "{:<8}{:>10}{:>10}{:>10}",
+        language.label(Label::Camel),
+        language.label(Label::Winner),
+        language.label(Label::RunnerUp),
+        language.label(Label::Loser)
+    );
+}
+
+/// The camels in the chances, ordered and limited by the selection.
+fn selected(chances: &LegChances, selection: &Selection) -> Vec<Camel> {
+    let mut camels: Vec<Camel> = chances
+        .winner
+        .values()
+        .chain(chances.runner_up.values())
+        .chain(chances.loser.values())
+        .map(|(camel, _)| *camel)
+        .collect::<BTreeSet<Camel>>()
+        .into_iter()
+        .collect();
+    let outcome = selection.sort.distribution(chances);
+    camels.sort_by(|left, right| {
+        (
+            outcome[right],
+            chances.winner[right],
+            chances.runner_up[right],
+        )
+            .cmp(&(outcome[left], chances.winner[left], chances.runner_up[left]))
+    });
+    selection.limit(&mut camels);
+
+    camels
+}
+
+/// Prints a matrix with the chance of each camel for every place, ordered by winning chance.
+fn print_places(chances: &LegChances, format: &Format, selection: &Selection) {
+    let mut camels: Vec<Camel> = chances
+        .places
+        .iter()
+        .flat_map(|place| place.values().map(|(camel, _)| *camel))
+        .collect::<BTreeSet<Camel>>()
+        .into_iter()
+        .collect();
+    camels.sort_by(|left, right| {
+        let left: Vec<Fraction> = chances.places.iter().map(|place| place[left]).collect();
+        let right: Vec<Fraction> = chances.places.iter().map(|place| place[right]).collect();
+        right.cmp(&left)
+    });
+    selection.limit(&mut camels);
+
+    print!("{:<8}", config::language().label(Label::Camel));
+    for place in 1..=chances.places.len() {
+        print!("{:>10}", place);
+    }
+    println!();
+    for camel in camels {
+        print!("{:<8}", config::language().camel(camel));
+        for place in &chances.places {
+            print!("{:>10}", format.apply(&place[&camel]));
+        }
+        println!();
+    }
+}
+
+fn print(elements: &[(Camel, Fraction)], format: &Format) {
+    println!("{}", line(elements, format))
+}
+
+fn line(elements: &[(Camel, Fraction)], format: &Format) -> String {
+    elements
+        .iter()
+        .map(|(camel, fraction)| {
+            format!(
+                "({},{})",
+                config::language().camel(*camel),
+                format.apply(fraction)
+            )
+        })
+        .collect()
+}
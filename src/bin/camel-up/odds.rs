@@ -0,0 +1,47 @@
+//! LegChances written as odds, the way bettors talk about them.
+use camel_up::fraction::Fraction;
+
+/// Fractional odds, e.g. `3:1 against` for a chance of 1/4 and `2:1 on` for a chance of 2/3.
+pub fn fractional(chance: &Fraction) -> String {
+    let favourable = chance.numerator();
+    let unfavourable = chance.denominator() as i64 - favourable;
+    if favourable <= 0 {
+        "no chance".to_owned()
+    } else if unfavourable <= 0 {
+        "certain".to_owned()
+    } else if favourable == unfavourable {
+        "evens".to_owned()
+    } else if favourable > unfavourable {
+        format!("{}:{} on", favourable, unfavourable)
+    } else {
+        format!("{}:{} against", unfavourable, favourable)
+    }
+}
+
+/// Decimal odds, i.e. the total return of a stake of one, e.g. `4.00` for a chance of 1/4.
+pub fn decimal(chance: &Fraction) -> String {
+    if chance.numerator() <= 0 {
+        "-".to_owned()
+    } else {
+        format!("{:.2}", 1.0 / chance.to_f64())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fractional_odds_read_like_bets() {
+        assert_eq!(fractional(&Fraction::new(2, 3)), "2:1 on");
+        assert_eq!(fractional(&Fraction::new(1, 4)), "3:1 against");
+        assert_eq!(fractional(&Fraction::new(1, 2)), "evens");
+        assert_eq!(fractional(&Fraction::zero()), "no chance");
+    }
+
+    #[test]
+    fn decimal_odds_are_the_return_of_a_stake() {
+        assert_eq!(decimal(&Fraction::new(1, 4)), "4.00");
+        assert_eq!(decimal(&Fraction::zero()), "-");
+    }
+}
@@ -0,0 +1,86 @@
+//! Shows the progress of long computations on standard error.
+use std::{
+    io::{self, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+/// Computations shorter than this do not show any progress.
+const GRACE: Duration = Duration::from_secs(1);
+/// The minimal time between two updates of the progress line.
+const REFRESH: Duration = Duration::from_millis(100);
+const WIDTH: usize = 30;
+
+/// A progress bar that only appears once a computation takes longer than a second.
+pub struct Progress {
+    unit: &'static str,
+    enabled: bool,
+    start: Instant,
+    last: Option<Instant>,
+}
+
+impl Progress {
+    /// A progress bar counting the given unit, e.g. nodes or samples.
+    ///
+    /// Nothing is shown when quiet, or when standard error is not a terminal.
+    pub fn new(unit: &'static str, quiet: bool) -> Self {
+        Self {
+            unit,
+            enabled: !quiet && io::stderr().is_terminal(),
+            start: Instant::now(),
+            last: None,
+        }
+    }
+
+    /// Reports that `done` out of `total` units are finished.
+    pub fn update(&mut self, done: u64, total: u64) {
+        let now = Instant::now();
+        if !self.enabled || now - self.start < GRACE {
+            return;
+        }
+        if self.last.is_some_and(|last| now - last < REFRESH) {
+            return;
+        }
+        self.last = Some(now);
+        eprint!("\r{}", line(done, total, self.unit));
+        io::stderr().flush().ok();
+    }
+
+    /// Removes the progress line, if it was shown.
+    pub fn finish(&mut self) {
+        if self.last.take().is_some() {
+            eprint!("\r\x1b[2K");
+            io::stderr().flush().ok();
+        }
+    }
+}
+
+fn line(done: u64, total: u64, unit: &str) -> String {
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        (done.min(total) as f64) / (total as f64)
+    };
+    let filled = (fraction * WIDTH as f64).round() as usize;
+    format!(
+        "[{}{}] {:>3.0}% {} of {} {}",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled),
+        fraction * 100.0,
+        done,
+        total,
+        unit
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lines_show_the_fraction_done() {
+        assert_eq!(
+            line(5, 10, "nodes"),
+            "[###############               ]  50% 5 of 10 nodes"
+        );
+    }
+}
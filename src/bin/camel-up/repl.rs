@@ -0,0 +1,229 @@
+//! An interactive session that keeps track of a race during an actual game.
+use camel_up::{
+    camel::{Dice, Marker, Race, Roll},
+    oracle::{project_with_transitions, Transitions},
+    vis::types::{start_tile, Board},
+};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "commands:
+  roll <camel><face>  roll a die, e.g. roll r2
+  trap <+|-><tile>    place an oasis or fata morgana on a tile, e.g. trap +7
+  dice [dice]         show the dice in the pyramid, or set them, e.g. dice ryg
+  odds                show the board and the chances
+  undo                undo the last command
+  help                show this message
+  quit                end the session";
+
+/// The commands a session understands.
+#[derive(PartialEq, Debug)]
+enum Command {
+    Roll(Roll),
+    Trap(Marker, usize),
+    Dice(Option<Dice>),
+    Odds,
+    Undo,
+    Help,
+    Quit,
+}
+
+fn parse(line: &str) -> Result<Command, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["roll", roll] => super::config::notation()
+            .standardize(roll)
+            .map_err(|(_, symbol)| format!("'{}' is not a camel", symbol))?
+            .parse::<Roll>()
+            .map(Command::Roll)
+            .map_err(|error| error.to_string()),
+        ["trap", trap] => {
+            let mut symbols = trap.chars();
+            let marker = match symbols.next() {
+                Some('+') => Marker::Oasis,
+                Some('-') => Marker::FataMorgana,
+                _ => return Err(format!("'{}' is not a trap, use + or -", trap)),
+            };
+            let tile = symbols
+                .as_str()
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a tile", symbols.as_str()))?;
+            Ok(Command::Trap(marker, tile))
+        }
+        ["dice"] => Ok(Command::Dice(None)),
+        ["dice", dice] => super::config::notation()
+            .parse_dice(dice)
+            .map(|dice| Command::Dice(Some(dice)))
+            .map_err(|error| error.to_string()),
+        ["odds"] => Ok(Command::Odds),
+        ["undo"] => Ok(Command::Undo),
+        ["help"] => Ok(Command::Help),
+        ["quit"] | ["exit"] => Ok(Command::Quit),
+        _ => Err(format!("unknown command '{}', try help", line.trim())),
+    }
+}
+
+/// The state of the race during a session.
+///
+/// The transitions are kept for the whole session, since the races after a roll were already met when projecting the race before it.
+pub struct Session {
+    race: Race,
+    dice: Dice,
+    history: Vec<(Race, Dice)>,
+    transitions: Transitions,
+}
+
+impl Session {
+    /// Starts a session. The race needs a finish, so that tiles can be placed on the board.
+    pub fn new(race: Race, dice: Dice) -> Result<Self, String> {
+        if !race.positions().contains(&Marker::Finish) {
+            return Err("the race needs a finish, e.g. r,,y,,,!".to_owned());
+        }
+        race.check_dice(&dice)
+            .map_err(|problem| problem.to_string())?;
+        Ok(Self {
+            race,
+            dice,
+            history: Vec::new(),
+            transitions: Transitions::default(),
+        })
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        match command {
+            Command::Roll(roll) => {
+                if !self.dice.contains(roll.camel()) {
+                    return Err(format!("the {:?} die is not in the pyramid", roll.camel()));
+                }
+                let race = self
+                    .race
+                    .perform_checked(roll)
+                    .map_err(|problem| problem.to_string())?;
+                self.remember();
+                self.race = race;
+                self.dice = self.dice.remove(roll.camel());
+                if self.dice.is_empty() {
+                    println!("the leg is over, all dice are back in the pyramid");
+                    self.dice = Dice::from(&self.race);
+                }
+            }
+            Command::Trap(marker, tile) => {
+                let start = start_tile(&self.race) + 1;
+                if tile < start {
+                    return Err("traps behind all camels have no effect".to_owned());
+                }
+                let race = self
+                    .race
+                    .place_trap(tile - start, marker)
+                    .map_err(|error| error.to_string())?;
+                self.remember();
+                self.race = race;
+            }
+            Command::Dice(None) => println!("dice: {}", self.dice),
+            Command::Dice(Some(dice)) => {
+                self.race
+                    .check_dice(&dice)
+                    .map_err(|problem| problem.to_string())?;
+                self.remember();
+                self.dice = dice;
+            }
+            Command::Undo => {
+                let (race, dice) = self.history.pop().ok_or("nothing to undo")?;
+                self.race = race;
+                self.dice = dice;
+            }
+            Command::Odds | Command::Help | Command::Quit => {}
+        }
+        Ok(())
+    }
+
+    fn remember(&mut self) {
+        self.history.push((self.race, self.dice));
+    }
+
+    fn show(&mut self) {
+        super::show_board(&Board::from(&self.race));
+        let notation = super::config::notation();
+        println!(
+            "race: {}  dice: {}",
+            notation.localize(&self.race.to_string()),
+            notation.localize(&self.dice.to_string())
+        );
+        let chances = project_with_transitions(&self.race, &self.dice, &mut self.transitions);
+        super::print(&super::ordered(&chances), &super::Format::Fraction);
+    }
+}
+
+/// Reads commands from standard input until the session is ended.
+pub fn run(mut session: Session) {
+    session.show();
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("to flush");
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).expect("to read") == 0 {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(Command::Quit) => break,
+            Ok(Command::Help) => println!("{}", HELP),
+            Ok(command) => match session.execute(command) {
+                Ok(()) => session.show(),
+                Err(problem) => eprintln!("error: {}", problem),
+            },
+            Err(problem) => eprintln!("error: {}", problem),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use camel_up::camel::{Camel, Face};
+
+    fn session() -> Session {
+        let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        Session::new(race, dice).expect("to start")
+    }
+
+    #[test]
+    fn commands_can_be_parsed() {
+        assert_eq!(
+            parse("roll r2"),
+            Ok(Command::Roll(Roll::from((Camel::Red, Face::Two))))
+        );
+        assert_eq!(parse("trap -7"), Ok(Command::Trap(Marker::FataMorgana, 7)));
+        assert_eq!(parse("undo\n"), Ok(Command::Undo));
+        assert!(parse("jump r2").is_err());
+    }
+
+    #[test]
+    fn rolls_remove_dice_and_can_be_undone() {
+        let mut session = session();
+
+        session
+            .execute(Command::Roll(Roll::from((Camel::Red, Face::Two))))
+            .expect("to roll");
+        assert_eq!(session.race.to_string(), "yr,,,,,,,,,,,,,!");
+        assert_eq!(session.dice.to_string(), "y");
+
+        session.execute(Command::Undo).expect("to undo");
+        assert_eq!(session.race.to_string(), "r,,y,,,,,,,,,,,,,!");
+        assert_eq!(session.dice.to_string(), "ry");
+    }
+
+    #[test]
+    fn traps_are_placed_on_board_tiles() {
+        let mut session = session();
+
+        session
+            .execute(Command::Trap(Marker::Oasis, 4))
+            .expect("to place");
+
+        assert_eq!(session.race.to_string(), "r,,y,+,,,,,,,,,,,,!");
+    }
+}
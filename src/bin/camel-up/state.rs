@@ -0,0 +1,66 @@
+//! Game state kept in a file, so that multiple tools can share it.
+//!
+//! The file is in TOML format, e.g.
+//!
+//! ```toml
+//! race = "r,,y,,,,,,,,,,,,,!"
+//! dice = "ry"
+//! ```
+use serde::Deserialize;
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+/// The description of a race and the dice in the pyramid.
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct State {
+    pub race: String,
+    pub dice: Option<String>,
+}
+
+impl State {
+    /// Reads the state from the file at path, or from standard input when the path is `-`.
+    pub fn read(path: &str) -> Result<Self, String> {
+        let content = if path == "-" {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|error| format!("could not read standard input: {}", error))?;
+            content
+        } else {
+            fs::read_to_string(path)
+                .map_err(|error| format!("could not read {}: {}", path, error))?
+        };
+        Self::parse(&content).map_err(|error| format!("could not parse {}: {}", path, error))
+    }
+
+    fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn state_can_be_parsed() {
+        let state = State::parse("race = \"r,,y\"\ndice = \"ry\"\n").expect("to parse");
+
+        assert_eq!(
+            state,
+            State {
+                race: "r,,y".to_owned(),
+                dice: Some("ry".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn dice_are_optional() {
+        let state = State::parse("race = \"r,,y\"").expect("to parse");
+
+        assert_eq!(state.dice, None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Answers requests in the JSON wire format, one per line, for integrations that keep the process running.
+//!
+//! Every line of the input is a request like `{"id": 7, "race": "r,,w", "dice": "rw"}`, every line of the output the response to the request on the same line: `{"id": 7, "chances": {...}}`, or `{"id": 7, "error": "..."}` when the request could not be answered. The `id` is optional and echoed as is. Blank lines are skipped.
+//!
+//! Answers are remembered for the rest of the session, so that repeated positions are only computed once.
+use super::trace;
+use camel_up::{
+    camel::{Dice, Race},
+    wire::Request,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+/// The number of answers a session remembers, before it forgets all of them.
+const CACHE_LIMIT: usize = 10_000;
+
+/// A request on a single line, without its `id`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Line {
+    race: Race,
+    #[serde(default)]
+    dice: Option<Dice>,
+}
+
+/// The answers given so far.
+#[derive(Default)]
+pub struct Session {
+    cache: HashMap<(String, String), Value>,
+    hits: usize,
+}
+
+impl Session {
+    /// The response to a line of input.
+    pub fn respond(&mut self, line: &str) -> String {
+        let mut request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(problem) => return json!({ "error": problem.to_string() }).to_string(),
+        };
+        let id = request
+            .as_object_mut()
+            .and_then(|fields| fields.remove("id"));
+        let mut response = match self.answer(request) {
+            Ok(chances) => json!({ "chances": chances }),
+            Err(problem) => json!({ "error": problem }),
+        };
+        if let Some(id) = id {
+            response["id"] = id;
+        }
+        response.to_string()
+    }
+
+    fn answer(&mut self, request: Value) -> Result<Value, String> {
+        let line: Line = serde_json::from_value(request).map_err(|problem| problem.to_string())?;
+        let request = Request {
+            race: line.race.with_edition(super::config::edition()),
+            dice: line.dice,
+        };
+        let key = (request.race.to_string(), request.dice().to_string());
+        if let Some(chances) = self.cache.get(&key) {
+            self.hits += 1;
+            return Ok(chances.clone());
+        }
+        let chances = request.answer().map_err(|problem| problem.to_string())?;
+        let chances = serde_json::to_value(chances).map_err(|problem| problem.to_string())?;
+        if self.cache.len() >= CACHE_LIMIT {
+            self.cache.clear();
+        }
+        self.cache.insert(key, chances.clone());
+        Ok(chances)
+    }
+}
+
+/// Answers the lines of standard input on standard output until the input ends.
+pub fn run() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut session = Session::default();
+    let mut requests = 0;
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        requests += 1;
+        let mut out = stdout.lock();
+        writeln!(out, "{}", session.respond(&line))?;
+        out.flush()?;
+    }
+    trace::event(
+        trace::SUMMARY,
+        "stream",
+        &[("requests", &requests), ("cache_hits", &session.hits)],
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn requests_are_answered_with_their_id() {
+        let mut session = Session::default();
+
+        let response = session.respond(r#"{"id": "a", "race": "r,y", "dice": "r"}"#);
+
+        assert_eq!(
+            response,
+            r#"{"chances":{"loser":{},"places":[{"red":"1"},{"yellow":"1"}],"runner_up":{"yellow":"1"},"winner":{"red":"1"}},"id":"a"}"#
+        );
+    }
+
+    #[test]
+    fn repeated_requests_are_remembered() {
+        let mut session = Session::default();
+
+        let first = session.respond(r#"{"race": "r,,y"}"#);
+        let second = session.respond(r#"{"race": "r,,y", "dice": "yr"}"#);
+
+        assert_eq!(first, second);
+        assert_eq!(session.hits, 1);
+    }
+
+    #[test]
+    fn problems_are_answered_with_an_error() {
+        let mut session = Session::default();
+
+        let response: Value = serde_json::from_str(&session.respond(r#"{"id": 3, "race": "r|y"}"#))
+            .expect("to be JSON");
+
+        assert!(response["error"].is_string());
+        assert_eq!(response["id"], 3);
+    }
+
+    #[test]
+    fn dice_of_camels_that_are_not_in_the_race_are_an_error() {
+        let mut session = Session::default();
+
+        let response: Value =
+            serde_json::from_str(&session.respond(r#"{"race": "r,y", "dice": "w"}"#))
+                .expect("to be JSON");
+        let next = session.respond(r#"{"race": "r,y", "dice": "r"}"#);
+
+        assert!(response["error"].is_string());
+        assert!(next.starts_with(r#"{"chances":"#));
+    }
+}
@@ -0,0 +1,62 @@
+//! Traces what a computation does on standard error, one event per line.
+//!
+//! Every line starts with `trace:`, followed by the name of the event and its fields as `key=value` pairs, so that the output can be filtered with the usual tools.
+//!
+//! ```plain
+//! trace: event=projection nodes=226 leaves=162 expansion_ms=0.315 counting_ms=0.042
+//! ```
+use std::{fmt::Display, sync::OnceLock, time::Duration};
+
+/// Traces the outcome of a computation, e.g. the number of nodes expanded and the time it took.
+pub const SUMMARY: u64 = 1;
+/// Traces the steps of a computation as well, e.g. progress of the expansion and cache hits.
+pub const DETAIL: u64 = 2;
+
+static VERBOSITY: OnceLock<u64> = OnceLock::new();
+
+/// Sets the verbosity, i.e. the number of times `-v` is given.
+pub fn init(verbosity: u64) {
+    VERBOSITY.set(verbosity).ok();
+}
+
+/// Whether events of the given level are traced.
+pub fn enabled(level: u64) -> bool {
+    *VERBOSITY.get().unwrap_or(&0) >= level
+}
+
+/// Traces an event with its fields, when the level is enabled.
+pub fn event(level: u64, name: &str, fields: &[(&str, &dyn Display)]) {
+    if enabled(level) {
+        eprintln!("{}", line(name, fields));
+    }
+}
+
+/// Formats a duration in milliseconds, the unit of the `_ms` fields.
+pub fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+fn line(name: &str, fields: &[(&str, &dyn Display)]) -> String {
+    let mut line = format!("trace: event={}", name);
+    for (key, value) in fields {
+        line.push_str(&format!(" {}={}", key, value));
+    }
+    line
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn events_are_written_as_key_value_pairs() {
+        let line = line("projection", &[("nodes", &19), ("expansion_ms", &"0.125")]);
+
+        assert_eq!(line, "trace: event=projection nodes=19 expansion_ms=0.125");
+    }
+
+    #[test]
+    fn durations_are_written_in_milliseconds() {
+        assert_eq!(millis(Duration::from_micros(2315)), "2.315");
+    }
+}
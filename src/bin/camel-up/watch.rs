@@ -0,0 +1,29 @@
+//! Watches a file for changes.
+use std::{
+    fs,
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+const INTERVAL: Duration = Duration::from_millis(500);
+
+/// Calls `show` whenever the file at path is modified, clearing the screen beforehand.
+///
+/// The file is polled, which works on every platform and for files on network shares.
+pub fn watch<F>(path: &str, mut show: F) -> !
+where
+    F: FnMut(),
+{
+    let mut last_seen: Option<SystemTime> = None;
+    loop {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified != last_seen {
+            last_seen = modified;
+            print!("\x1b[2J\x1b[H");
+            show();
+        }
+        sleep(INTERVAL);
+    }
+}
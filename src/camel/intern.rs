@@ -0,0 +1,120 @@
+//! Interning of races, for analyses that keep millions of them.
+//!
+//! An `Interner` keeps a single copy of every distinct race and hands out a `RaceId` for it. A handle takes four bytes, a race takes several words, so searches over whole races and graphs of positions that share states can store handles instead of races.
+//!
+//! ```
+//! # use camel_up::camel::{intern::Interner, Race};
+//! let mut interner = Interner::default();
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//!
+//! let id = interner.intern(race);
+//!
+//! assert_eq!(interner.intern(",,r,y,,".parse::<Race>().expect("to parse")), id);
+//! assert_eq!(interner.len(), 1);
+//! assert_eq!(interner[id], race);
+//! ```
+use super::{Race, DEFAULT_WORDS};
+use std::{collections::HashMap, convert::TryFrom, ops::Index};
+
+/// A handle to a race in an `Interner`.
+///
+/// Handles are handed out in the order in which races are first interned, so they can index a vector of data about the races.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub struct RaceId(u32);
+
+impl RaceId {
+    /// The index of the race in the order in which races were interned.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Keeps a single copy of every race it is handed.
+#[derive(Clone, Debug)]
+pub struct Interner<const WORDS: usize = DEFAULT_WORDS> {
+    races: Vec<Race<WORDS>>,
+    ids: HashMap<Race<WORDS>, RaceId>,
+}
+
+impl<const WORDS: usize> Interner<WORDS> {
+    /// The handle of the race, which is the same for equal races.
+    ///
+    /// Panics when more than `u32::MAX` races are interned.
+    pub fn intern(&mut self, race: Race<WORDS>) -> RaceId {
+        if let Some(id) = self.ids.get(&race) {
+            return *id;
+        }
+        let id = RaceId(u32::try_from(self.races.len()).expect("at most u32::MAX races"));
+        self.races.push(race);
+        self.ids.insert(race, id);
+        id
+    }
+
+    /// The handle of the race, when it is interned.
+    pub fn get(&self, race: &Race<WORDS>) -> Option<RaceId> {
+        self.ids.get(race).copied()
+    }
+
+    /// The race of the handle.
+    pub fn race(&self, id: RaceId) -> &Race<WORDS> {
+        &self.races[id.index()]
+    }
+
+    /// The number of distinct races.
+    pub fn len(&self) -> usize {
+        self.races.len()
+    }
+
+    /// Whether no race is interned.
+    pub fn is_empty(&self) -> bool {
+        self.races.is_empty()
+    }
+
+    /// The interned races, in the order of their handles.
+    pub fn races(&self) -> impl Iterator<Item = (RaceId, &Race<WORDS>)> + '_ {
+        self.races
+            .iter()
+            .enumerate()
+            .map(|(index, race)| (RaceId(index as u32), race))
+    }
+}
+
+impl<const WORDS: usize> Default for Interner<WORDS> {
+    fn default() -> Self {
+        Self {
+            races: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+impl<const WORDS: usize> Index<RaceId> for Interner<WORDS> {
+    type Output = Race<WORDS>;
+
+    fn index(&self, id: RaceId) -> &Self::Output {
+        self.race(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::camel::{Dice, Face, Roll};
+
+    #[test]
+    fn rolls_in_any_order_share_a_handle() {
+        let race = "r,,,,y".parse::<Race>().expect("to parse");
+        let mut interner = Interner::default();
+
+        for camel in Dice::from(&race) {
+            for face in Face::values() {
+                interner.intern(race.perform(Roll::from((camel, face))));
+            }
+        }
+        let ahead = race.perform("y1".parse::<Roll>().expect("to parse"));
+
+        assert_eq!(interner.len(), 6);
+        assert_eq!(interner.get(&ahead), Some(RaceId(3)));
+        assert_eq!(interner.races().count(), interner.len());
+    }
+}
@@ -25,12 +25,22 @@
 //! assert_eq!(actual, expected);
 //! ```
 
-use std::collections::HashSet;
-use std::iter::repeat;
+pub mod intern;
+pub mod packed;
+pub mod stack;
+
+use crate::rules::RuleSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::{once, repeat_n, FromIterator};
 use std::str::FromStr;
 
+pub use packed::{Positions, CAPACITY, DEFAULT_WORDS};
+pub use stack::Stack;
+
 /// The various camels that race in the game.
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub enum Camel {
     /// The red camel, Rachel for friends.
     Red,
@@ -45,7 +55,7 @@ pub enum Camel {
 }
 
 /// A marker is used to describe a race.
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub enum Marker {
     /// Signals that a camel is present at this position. Its argument tells you which camel.
     Camel(Camel),
@@ -61,45 +71,30 @@ pub enum Marker {
 
 impl Marker {
     fn is_a_camel(self) -> bool {
-        match self {
-            Marker::Camel(_) => true,
-            _ => false,
-        }
+        matches!(self, Marker::Camel(_))
     }
 
-    fn is_a_divider(self) -> bool {
-        match self {
-            Marker::Divider => true,
-            _ => false,
-        }
+    pub(crate) fn is_a_divider(self) -> bool {
+        matches!(self, Marker::Divider)
     }
 
     fn is_an_oasis(self) -> bool {
-        match self {
-            Marker::Oasis => true,
-            _ => false,
-        }
+        matches!(self, Marker::Oasis)
     }
 
     fn is_a_fata_morgana(self) -> bool {
-        match self {
-            Marker::FataMorgana => true,
-            _ => false,
-        }
+        matches!(self, Marker::FataMorgana)
     }
 
-    fn is_a_finish(self) -> bool {
-        match self {
-            Marker::Finish => true,
-            _ => false,
-        }
+    pub(crate) fn is_a_finish(self) -> bool {
+        matches!(self, Marker::Finish)
     }
 
-    fn is_an_adjustment(self) -> bool {
+    pub(crate) fn is_an_adjustment(self) -> bool {
         self.is_an_oasis() || self.is_a_fata_morgana()
     }
 
-    fn to_camel(self) -> Option<Camel> {
+    pub(crate) fn to_camel(self) -> Option<Camel> {
         match self {
             Marker::Camel(camel) => Some(camel),
             _ => None,
@@ -126,6 +121,23 @@ impl FromStr for Marker {
     }
 }
 
+impl Display for Marker {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let symbol = match self {
+            Marker::Camel(Camel::Red) => "r",
+            Marker::Camel(Camel::Orange) => "o",
+            Marker::Camel(Camel::Yellow) => "y",
+            Marker::Camel(Camel::Green) => "g",
+            Marker::Camel(Camel::White) => "w",
+            Marker::Divider => ",",
+            Marker::Oasis => "+",
+            Marker::FataMorgana => "-",
+            Marker::Finish => "!",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
 /// When parsing of Marker goes wrong, this enumeration tells you precisely what went down.
 #[derive(PartialEq, Debug)]
 pub enum NotAMarker {
@@ -133,9 +145,130 @@ pub enum NotAMarker {
     But(String),
 }
 
+impl Display for NotAMarker {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NotAMarker::But(symbol) => write!(f, "'{}' is not a marker", symbol),
+        }
+    }
+}
+
+impl Error for NotAMarker {}
+
+/// The editions of Camel Up, which differ in some of their rules.
+///
+/// ```
+/// # use camel_up::camel::{Race, Edition, Camel, Face};
+/// let race = "yr,-".parse::<Race>().expect("to parse").with_edition(Edition::Second);
+///
+/// let actual = race.perform((Camel::Red, Face::One));
+///
+/// assert_eq!(actual.to_string(), "ry,-");
+/// ```
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Default)]
+pub enum Edition {
+    /// The original game from 2014.
+    #[default]
+    Classic,
+    /// The second edition from 2018.
+    Second,
+}
+
+impl Edition {
+    /// Whether camels that land on a fata morgana move under the camels already on the tile behind it, instead of on top.
+    pub fn stacks_under_on_fata_morgana(&self) -> bool {
+        matches!(self, Edition::Second)
+    }
+
+    /// Whether the edition has crazy camels that run the track backwards.
+    ///
+    /// Races do not model crazy camels yet, this only tells whether the rules call for them.
+    pub fn has_crazy_camels(&self) -> bool {
+        matches!(self, Edition::Second)
+    }
+
+    /// Whether players can form partnerships and share their leg bets.
+    ///
+    /// Partnerships are played out by the `game` module.
+    pub fn has_partner_bets(&self) -> bool {
+        matches!(self, Edition::Second)
+    }
+}
+
+/// Where a camel unit ends up when a fata morgana sends it back to a tile with camels.
+///
+/// ```
+/// # use camel_up::camel::{Race, Stacking, Camel, Face};
+/// let race = "yr,-".parse::<Race>().expect("to parse").with_stacking(Stacking::Under);
+///
+/// let actual = race.perform((Camel::Red, Face::One));
+///
+/// assert_eq!(actual.to_string(), "ry,-");
+/// ```
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Default)]
+pub enum Stacking {
+    /// On top of the camels already on the tile.
+    #[default]
+    OnTop,
+    /// Under the camels already on the tile.
+    Under,
+}
+
+impl From<Edition> for Stacking {
+    fn from(edition: Edition) -> Self {
+        if edition.stacks_under_on_fata_morgana() {
+            Stacking::Under
+        } else {
+            Stacking::OnTop
+        }
+    }
+}
+
+impl Display for Edition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Edition::Classic => write!(f, "classic"),
+            Edition::Second => write!(f, "second"),
+        }
+    }
+}
+
+impl FromStr for Edition {
+    type Err = NoEdition;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "classic" => Ok(Edition::Classic),
+            "second" => Ok(Edition::Second),
+            _ => Err(NoEdition::But(input.to_owned())),
+        }
+    }
+}
+
+/// Reasons why an edition could not be parsed.
+#[derive(PartialEq, Eq, Debug)]
+pub enum NoEdition {
+    /// The input is not one of the known editions.
+    But(String),
+}
+
+impl Display for NoEdition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NoEdition::But(input) => {
+                write!(f, "'{}' is not an edition, use classic or second", input)
+            }
+        }
+    }
+}
+
+impl Error for NoEdition {}
+
 /// Models a race as a sequence of markers.
 ///
-/// Note that a race is normalized, i.e. leading and trailing dividers are stripped.
+/// Note that a race is normalized, i.e. leading and trailing dividers are stripped. Only dividers are stripped, traps ahead of the frontmost camel and behind the rearmost camel stay in the race, as does the finish.
+///
+/// A race is packed in a few words, see `Positions`, so copying a race is cheap. It holds at most `CAPACITY` markers, races on longer tracks take more `WORDS`, see `packed::words_for`.
 ///
 /// ```
 /// # use camel_up::camel::{Race, Marker, Camel};
@@ -144,131 +277,232 @@ pub enum NotAMarker {
 ///
 /// assert_eq!(race_with_superfluous_dividers, minimal_race);
 /// ```
-#[derive(PartialEq, Eq, Debug)]
-pub struct Race {
+///
+/// A race without camels is the empty race, so that a board can be set up before the camels are placed. It is the default race, has no winner and no dice.
+///
+/// ```
+/// # use camel_up::camel::{Dice, Race};
+/// let empty = ",,,".parse::<Race>().expect("to parse");
+///
+/// assert_eq!(empty, Race::default());
+/// assert_eq!(empty.to_string(), "");
+/// assert_eq!(empty.winner(), None);
+/// assert!(Dice::from(&empty).is_empty());
+/// ```
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug)]
+pub struct Race<const WORDS: usize = DEFAULT_WORDS> {
     /// The individual markers in this race
-    pub positions: Vec<Marker>,
+    pub(crate) positions: Positions<WORDS>,
+    edition: Edition,
+    stacking: Stacking,
 }
 
-impl Clone for Race {
-    fn clone(&self) -> Self {
+impl From<Vec<Marker>> for Race {
+    /// Panics when the normalized race has more than `CAPACITY` markers.
+    fn from(positions: Vec<Marker>) -> Self {
+        Self::normalized(positions)
+    }
+}
+
+impl<const WORDS: usize> Race<WORDS> {
+    /// The race of the markers, without leading and trailing dividers. Markers that are all dividers make the empty race.
+    ///
+    /// Panics when the normalized race has more markers than fit in `WORDS` words.
+    fn normalized(positions: Vec<Marker>) -> Self {
+        let start = positions.iter().position(|marker| !marker.is_a_divider());
+        let end = positions.iter().rposition(|marker| !marker.is_a_divider());
+        let positions = match (start, end) {
+            (Some(start), Some(end)) => positions[start..=end].iter().copied().collect(),
+            _ => Positions::default(),
+        };
         Self {
-            positions: self.positions.to_vec(),
+            positions,
+            edition: Edition::default(),
+            stacking: Stacking::default(),
         }
     }
 }
 
-impl From<Vec<Marker>> for Race {
-    fn from(positions: Vec<Marker>) -> Self {
-        let (min, max) = positions
-            .iter()
-            .zip(0..)
-            .filter(|(marker, _)| !marker.is_a_divider())
-            .map(|(_, index)| index)
-            .fold(
-                (core::usize::MAX, core::usize::MIN),
-                |(minimum, maximum), index| (minimum.min(index), maximum.max(index)),
-            );
-        let positions = positions[min..=max]
-            .iter()
-            .skip_while(|marker| **marker == Marker::Divider)
-            .cloned()
-            .collect();
-        Self { positions }
+impl<const WORDS: usize> Display for Race<WORDS> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for marker in &self.positions {
+            write!(f, "{}", marker)?;
+        }
+        Ok(())
     }
 }
 
-impl FromStr for Race {
+impl<const WORDS: usize> FromStr for Race<WORDS> {
     type Err = RaceParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut result = vec![];
-        let mut cursor = 0;
-        while cursor < input.len() {
-            result.push(input[cursor..=cursor].parse::<Marker>()?);
-            cursor += 1;
-        }
-        if result
-            .iter()
-            .zip(result.iter().skip(1))
-            .filter(|(l, r)| l.is_a_camel() && r.is_an_oasis() || l.is_an_oasis() && r.is_a_camel())
-            .count()
-            > 0
-        {
-            return Err(RaceParseError::CamelInOasis);
+        for (position, symbol) in input.chars().enumerate() {
+            let marker = symbol
+                .to_string()
+                .parse::<Marker>()
+                .map_err(|problem| RaceParseError::NotAMarker(position, problem))?;
+            result.push(marker);
         }
+        validate(&result, Positions::<WORDS>::CAPACITY)?;
 
-        if result
-            .iter()
-            .zip(result.iter().skip(1))
-            .filter(|(l, r)| {
-                l.is_a_camel() && r.is_a_fata_morgana() || l.is_a_fata_morgana() && r.is_a_camel()
-            })
-            .count()
-            > 0
-        {
-            return Err(RaceParseError::CamelInFataMorgana);
-        }
+        Ok(Self::normalized(result))
+    }
+}
 
-        if result
-            .iter()
-            .zip(result.iter().skip(1))
-            .filter(|(l, r)| l.is_an_adjustment() && r.is_an_adjustment())
-            .count()
-            > 0
-        {
-            return Err(RaceParseError::ToManyAdjustmentsInOnePosition);
-        }
+/// Checks that a sequence of markers follows the rules of the game, and fits in `capacity` markers.
+///
+/// The positions in the errors are indices into `result`.
+fn validate(result: &[Marker], capacity: usize) -> Result<(), RaceParseError> {
+    if let Some(index) = result
+        .iter()
+        .zip(result.iter().skip(1))
+        .position(|(l, r)| l.is_a_camel() && r.is_an_oasis() || l.is_an_oasis() && r.is_a_camel())
+    {
+        let position = if result[index].is_an_oasis() {
+            index
+        } else {
+            index + 1
+        };
+        return Err(RaceParseError::CamelInOasis(position));
+    }
 
-        if result
-            .iter()
-            .zip(result.iter().skip(2))
-            .filter(|(l, r)| l.is_an_adjustment() && r.is_an_adjustment())
-            .count()
-            > 0
-        {
-            return Err(RaceParseError::ConsecutiveAdjustments);
-        }
+    if let Some(index) = result.iter().zip(result.iter().skip(1)).position(|(l, r)| {
+        l.is_a_camel() && r.is_a_fata_morgana() || l.is_a_fata_morgana() && r.is_a_camel()
+    }) {
+        let position = if result[index].is_a_fata_morgana() {
+            index
+        } else {
+            index + 1
+        };
+        return Err(RaceParseError::CamelInFataMorgana(position));
+    }
 
-        if result.iter().filter(|t| t.is_a_finish()).count() > 1 {
-            return Err(RaceParseError::MultipleFinishes);
-        }
+    if let Some(index) = result
+        .iter()
+        .zip(result.iter().skip(1))
+        .position(|(l, r)| l.is_an_adjustment() && r.is_an_adjustment())
+    {
+        return Err(RaceParseError::ToManyAdjustmentsInOnePosition(index + 1));
+    }
 
-        if result.iter().filter(|t| t.is_a_finish()).count() > 0
-            && !result.iter().last().map_or(true, |t| t.is_a_finish())
-        {
-            return Err(RaceParseError::MarkersAfterFinish);
+    if let Some(index) = result
+        .iter()
+        .zip(result.iter().skip(2))
+        .position(|(l, r)| l.is_an_adjustment() && r.is_an_adjustment())
+    {
+        return Err(RaceParseError::ConsecutiveAdjustments(index + 2));
+    }
+
+    if let Some(index) = result
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_a_finish())
+        .map(|(index, _)| index)
+        .nth(1)
+    {
+        return Err(RaceParseError::MultipleFinishes(index));
+    }
+
+    if let Some(index) = result.iter().position(|t| t.is_a_finish()) {
+        if index + 1 < result.len() {
+            return Err(RaceParseError::MarkersAfterFinish(index + 1));
         }
+    }
 
-        Ok(Race::from(result))
+    let mut markers = result
+        .iter()
+        .enumerate()
+        .filter(|(_, marker)| !marker.is_a_divider())
+        .map(|(index, _)| index);
+    if let Some(start) = markers.next() {
+        let end = markers.next_back().unwrap_or(start);
+        // Every camel rolls at most once in a leg, and each roll can take the race up to `PADDING` markers further.
+        let camels = result.iter().filter(|marker| marker.is_a_camel()).count();
+        let room = capacity.saturating_sub(camels * PADDING);
+        if end - start >= room {
+            return Err(RaceParseError::TooManyMarkers(start + room));
+        }
     }
+
+    Ok(())
 }
 
 /// When parsing of Race goes wrong, this enumeration tells you precisely what went down.
+///
+/// Each variant carries the position of the offending symbol in the input, counted in characters.
 #[derive(PartialEq, Debug)]
 pub enum RaceParseError {
     /// a race consists solely of markers, and this isn't a marker.
-    NotAMarker(NotAMarker),
+    NotAMarker(usize, NotAMarker),
     /// a camel can't be in an oasis.
-    CamelInOasis,
+    CamelInOasis(usize),
     /// a camel can't be in a fata morgana.
-    CamelInFataMorgana,
+    CamelInFataMorgana(usize),
     /// adjustments can't be in the same position.
-    ToManyAdjustmentsInOnePosition,
+    ToManyAdjustmentsInOnePosition(usize),
     /// adjustments can't be consecutive.
-    ConsecutiveAdjustments,
+    ConsecutiveAdjustments(usize),
     /// their can be only one finish
-    MultipleFinishes,
+    MultipleFinishes(usize),
     /// and finish should be the last marker
-    MarkersAfterFinish,
+    MarkersAfterFinish(usize),
+    /// a race holds at most `CAPACITY` markers from the rear camel to the front, unless it is given more words. Room is kept for the camels to move a whole leg, `PADDING` markers for every camel.
+    TooManyMarkers(usize),
+    /// the race spans this many tiles, more than the board has, see `Race::check_length`. Its position is the start of the race.
+    TooLong(usize),
 }
 
-impl From<NotAMarker> for RaceParseError {
-    fn from(problem: NotAMarker) -> Self {
-        Self::NotAMarker(problem)
+impl RaceParseError {
+    /// The position in the input, counted in characters, where parsing went wrong.
+    pub fn position(&self) -> usize {
+        match self {
+            RaceParseError::NotAMarker(position, _) => *position,
+            RaceParseError::CamelInOasis(position) => *position,
+            RaceParseError::CamelInFataMorgana(position) => *position,
+            RaceParseError::ToManyAdjustmentsInOnePosition(position) => *position,
+            RaceParseError::ConsecutiveAdjustments(position) => *position,
+            RaceParseError::MultipleFinishes(position) => *position,
+            RaceParseError::MarkersAfterFinish(position) => *position,
+            RaceParseError::TooManyMarkers(position) => *position,
+            RaceParseError::TooLong(_) => 0,
+        }
     }
 }
 
+impl Display for RaceParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RaceParseError::NotAMarker(_, problem) => write!(f, "{}", problem),
+            RaceParseError::CamelInOasis(_) => write!(f, "a camel can't be in an oasis"),
+            RaceParseError::CamelInFataMorgana(_) => {
+                write!(f, "a camel can't be in a fata morgana")
+            }
+            RaceParseError::ToManyAdjustmentsInOnePosition(_) => {
+                write!(f, "adjustments can't be in the same position")
+            }
+            RaceParseError::ConsecutiveAdjustments(_) => {
+                write!(f, "adjustments can't be consecutive")
+            }
+            RaceParseError::MultipleFinishes(_) => write!(f, "there can be only one finish"),
+            RaceParseError::MarkersAfterFinish(_) => {
+                write!(f, "the finish should be the last marker")
+            }
+            RaceParseError::TooManyMarkers(_) => {
+                write!(
+                    f,
+                    "the race holds more markers than fit while its camels move"
+                )
+            }
+            RaceParseError::TooLong(tiles) => {
+                write!(f, "the race spans {} tiles, more than the board has", tiles)
+            }
+        }
+    }
+}
+
+impl Error for RaceParseError {}
+
 /// A roll of the dice
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub struct Roll {
@@ -288,7 +522,7 @@ pub struct Roll {
 /// assert_eq!(usize::from(Face::Two), 2 as usize);
 /// assert_eq!(usize::from(Face::Three), 3 as usize);
 /// ```
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub enum Face {
     /// represents one step
     One,
@@ -299,21 +533,130 @@ pub enum Face {
 }
 
 impl Face {
-    /// Convenience function that retuns all the possible face values.
-    pub fn values() -> HashSet<Self> {
-        vec![Face::One, Face::Two, Face::Three]
+    /// Convenience function that retuns all the possible face values, from one to three.
+    pub fn values() -> BTreeSet<Self> {
+        [Face::One, Face::Two, Face::Three]
             .iter()
             .copied()
             .collect()
     }
 }
 
+impl Roll {
+    /// The camel that is allowed to move.
+    pub fn camel(&self) -> Camel {
+        self.camel
+    }
+
+    /// The number of steps they are allowed to take.
+    pub fn face(&self) -> Face {
+        self.face
+    }
+}
+
 impl From<(Camel, Face)> for Roll {
     fn from((camel, face): (Camel, Face)) -> Self {
         Self { camel, face }
     }
 }
 
+impl Display for Roll {
+    /// Writes the roll in the notation `FromStr` accepts, e.g. `r2`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", Marker::Camel(self.camel), usize::from(self.face))
+    }
+}
+
+impl FromStr for Roll {
+    type Err = RollParseError;
+
+    /// Parses a roll such as `r2`, i.e. the camel followed by the face.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut symbols = input.chars();
+        let camel = symbols
+            .next()
+            .ok_or(RollParseError::Empty)?
+            .to_string()
+            .parse::<Marker>()?
+            .to_camel()
+            .ok_or(RollParseError::NotACamel)?;
+        let face = match symbols.as_str() {
+            "1" => Face::One,
+            "2" => Face::Two,
+            "3" => Face::Three,
+            other => return Err(RollParseError::NotAFace(other.to_owned())),
+        };
+        Ok(Roll::from((camel, face)))
+    }
+}
+
+/// When parsing of Roll goes wrong, this enumeration tells you precisely what went down.
+#[derive(PartialEq, Debug)]
+pub enum RollParseError {
+    /// There was nothing to parse.
+    Empty,
+    /// The first symbol isn't even a marker.
+    NotAMarker(NotAMarker),
+    /// The first symbol is a marker, but not a camel.
+    NotACamel,
+    /// What follows the camel is not a face of the dice. The argument tells you what it was.
+    NotAFace(String),
+}
+
+impl From<NotAMarker> for RollParseError {
+    fn from(problem: NotAMarker) -> Self {
+        Self::NotAMarker(problem)
+    }
+}
+
+impl Display for RollParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RollParseError::Empty => write!(f, "a roll needs a camel and a face"),
+            RollParseError::NotAMarker(problem) => write!(f, "{}", problem),
+            RollParseError::NotACamel => write!(f, "only camels can be rolled"),
+            RollParseError::NotAFace(face) => write!(f, "'{}' is not a face of the dice", face),
+        }
+    }
+}
+
+impl Error for RollParseError {}
+
+/// The problems with performing a roll on a race.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MoveError {
+    /// The camel of the roll is not in the race.
+    CamelNotInRace(Camel),
+    /// The race after the roll holds more markers than fit in its words.
+    TooManyMarkers,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MoveError::CamelNotInRace(camel) => {
+                write!(f, "the {:?} camel is not in the race", camel)
+            }
+            MoveError::TooManyMarkers => {
+                write!(f, "the race outgrows the markers that fit")
+            }
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// What happened when a roll was performed, see `Race::perform_detailed`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Move<const WORDS: usize = DEFAULT_WORDS> {
+    /// The race after the roll.
+    pub race: Race<WORDS>,
+    /// The ordinal of the trap the unit landed on, counted from the rear of the race, when it landed on one.
+    ///
+    /// The owner of the trap earns a coin. Traps are counted by ordinal because the tiles of a race shift when the rear camel moves, and a roll never changes the order of the traps.
+    pub trap: Option<usize>,
+}
+
 impl From<Face> for usize {
     fn from(face: Face) -> Self {
         match face {
@@ -324,67 +667,439 @@ impl From<Face> for usize {
     }
 }
 
-impl Race {
+/// The number of dividers a roll can move a camel beyond the front of the race, a three and an oasis.
+///
+/// Parsing keeps room for this many markers for every camel, so that the race fits for a whole leg.
+const PADDING: usize = 4;
+
+impl<const WORDS: usize> Race<WORDS> {
     /// perform a roll on a race, returns the race with all the camels in their correct positions.
+    ///
+    /// A roll of a camel that is not in the race leaves the race as it is, use `perform_checked` to find out. So does a roll that would make the race outgrow the markers that fit in `WORDS` words, which parsing keeps room for during a leg.
     pub fn perform<R>(&self, roll: R) -> Self
+    where
+        R: Into<Roll>,
+    {
+        self.perform_checked(roll).unwrap_or(*self)
+    }
+
+    /// perform a roll on a race, like `perform`, but fails when the camel of the roll is not in the race.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel, Face, MoveError};
+    /// let race = "r,y".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.perform_checked((Camel::Red, Face::One)), Ok(race.perform((Camel::Red, Face::One))));
+    /// assert_eq!(race.perform_checked((Camel::White, Face::One)), Err(MoveError::CamelNotInRace(Camel::White)));
+    /// ```
+    pub fn perform_checked<R>(&self, roll: R) -> Result<Self, MoveError>
+    where
+        R: Into<Roll>,
+    {
+        self.perform_detailed(roll).map(|outcome| outcome.race)
+    }
+
+    /// perform a roll on a race, like `perform_checked`, and tell which trap the unit landed on.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel, Face};
+    /// let race = "r,+,y,,-".parse::<Race>().expect("to parse");
+    ///
+    /// let outcome = race.perform_detailed((Camel::Yellow, Face::Two)).expect("a camel in the race");
+    ///
+    /// assert_eq!(outcome.trap, Some(1));
+    /// assert_eq!(outcome.race, race.perform((Camel::Yellow, Face::Two)));
+    /// assert_eq!(race.perform_detailed((Camel::Red, Face::Two)).expect("a camel in the race").trap, None);
+    /// ```
+    pub fn perform_detailed<R>(&self, roll: R) -> Result<Move<WORDS>, MoveError>
     where
         R: Into<Roll>,
     {
         let roll: Roll = roll.into();
-        if self.positions.contains(&Marker::Camel(roll.camel)) {
-            let index = self.positions.iter().position(|marker| *marker == Marker::Camel(roll.camel)).unwrap(/* camel is present because of contains check */);
-            let offset = self.positions[index..]
-                .iter()
-                .take_while(|marker| marker.is_a_camel())
-                .count();
+        let index = self
+            .positions
+            .iter()
+            .position(|marker| marker == Marker::Camel(roll.camel))
+            .ok_or(MoveError::CamelNotInRace(roll.camel))?;
+        let unit = self
+            .positions
+            .iter()
+            .skip(index)
+            .take_while(|marker| marker.is_a_camel())
+            .count();
+
+        // The race without the unit, followed by enough dividers to move beyond the front.
+        let length = self.positions.len() - unit + PADDING;
+        let remaining = |at: usize| {
+            if at < index {
+                self.positions[at]
+            } else {
+                self.positions.get(at + unit).unwrap_or(Marker::Divider)
+            }
+        };
+        let divider = |nth: usize| {
+            (index..length)
+                .filter(|at| remaining(*at).is_a_divider())
+                .nth(nth)
+                .unwrap(/* divider is present because of the padding */)
+        };
+
+        let steps = usize::from(roll.face);
+        let landing = divider(steps);
+        let trap = if remaining(landing - 1).is_an_adjustment() {
+            Some(
+                (0..landing - 1)
+                    .filter(|at| remaining(*at).is_an_adjustment())
+                    .count(),
+            )
+        } else {
+            None
+        };
+        let destination = match remaining(landing - 1) {
+            Marker::Oasis => divider(steps + 1),
+            Marker::FataMorgana if self.stacking == Stacking::Under => (0..divider(steps - 1))
+                .rev()
+                .find(|at| remaining(*at).is_a_divider())
+                .map_or(0, |divider| divider + 1),
+            Marker::FataMorgana => divider(steps - 1),
+            _ => landing,
+        };
+
+        // The race with the unit spliced in at the destination.
+        let moved = |at: usize| {
+            if at < destination {
+                remaining(at)
+            } else if at < destination + unit {
+                self.positions[index + at - destination]
+            } else {
+                remaining(at - unit)
+            }
+        };
+        let total = length + unit;
+        let first = (0..total).find(|at| !moved(*at).is_a_divider()).unwrap(/* the unit is not a divider */);
+        let last = (0..total).rfind(|at| !moved(*at).is_a_divider()).unwrap(/* the unit is not a divider */);
+        if last - first >= Positions::<WORDS>::CAPACITY {
+            return Err(MoveError::TooManyMarkers);
+        }
+        Ok(Move {
+            race: Self {
+                positions: (first..=last).map(moved).collect(),
+                ..*self
+            },
+            trap,
+        })
+    }
 
-            let unit = &self.positions[index..(index + offset)];
-            let remaining: Vec<Marker> = self.positions[0..index]
-                .iter()
-                .chain(self.positions[(index + offset)..].iter())
-                .chain(repeat(&Marker::Divider).take(4))
-                .copied()
-                .collect();
-
-            let original_divider_offset = remaining[index..].iter().enumerate().filter(|(_, marker)| marker.is_a_divider()).map(|(index, _)| index).skip(roll.face as usize + 1).nth(0).unwrap(/* offset is present because of repeated divider */);
-            let delta: usize = match remaining[index + original_divider_offset - 1] {
-                Marker::Oasis => 2,
-                Marker::FataMorgana => 0,
-                _ => 1,
-            };
-            let divider_offset = remaining[index..].iter().enumerate().filter(|(_, marker)| marker.is_a_divider()).map(|(index, _)| index).skip(roll.face as usize + delta).nth(0).unwrap(/* offset is present because of repeated divider */);
-            let result: Vec<Marker> = remaining[0..(index + divider_offset)]
-                .iter()
-                .chain(unit.iter())
-                .chain(remaining[(index + divider_offset)..].iter())
-                .copied()
-                .collect();
-            Self::from(result)
+    /// The number of times each camel occurs in the race.
+    ///
+    /// Every camel occurs at most once in a race that follows the rules, and a roll neither adds nor loses a camel, which makes this a handy invariant for property tests.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel, Face};
+    /// let race = "r,+,yg".parse::<Race>().expect("to parse");
+    /// let after = race.perform((Camel::Red, Face::One));
+    ///
+    /// assert_eq!(race.camel_multiset().get(&Camel::Yellow), Some(&1));
+    /// assert_eq!(after.camel_multiset(), race.camel_multiset());
+    /// ```
+    pub fn camel_multiset(&self) -> BTreeMap<Camel, usize> {
+        let mut camels = BTreeMap::new();
+        for camel in self.positions.iter().filter_map(Marker::to_camel) {
+            *camels.entry(camel).or_insert(0) += 1;
+        }
+        camels
+    }
+
+    /// Whether the race has no leading or trailing dividers, see `Race`.
+    ///
+    /// Parsing and rolling always normalize a race. The empty race is normalized as well.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel, Face};
+    /// let race = ",,r,y,,".parse::<Race>().expect("to parse");
+    ///
+    /// assert!(race.is_normalized());
+    /// assert!(race.perform((Camel::Red, Face::Three)).is_normalized());
+    /// ```
+    pub fn is_normalized(&self) -> bool {
+        self.positions.first() != Some(Marker::Divider)
+            && self.positions.last() != Some(Marker::Divider)
+    }
+
+    /// The number of tiles the race spans, from its rearmost tile to its frontmost tile. With a finish, the race spans the tiles before it, the finish lies beyond the last one. The empty race spans no tiles.
+    ///
+    /// ```
+    /// # use camel_up::camel::Race;
+    /// assert_eq!("r,,y".parse::<Race>().expect("to parse").tiles(), 3);
+    /// assert_eq!("r,,y,,!".parse::<Race>().expect("to parse").tiles(), 4);
+    /// assert_eq!("".parse::<Race>().expect("to parse").tiles(), 0);
+    /// ```
+    pub fn tiles(&self) -> usize {
+        if self.positions.is_empty() {
+            return 0;
+        }
+        let dividers = self
+            .positions
+            .iter()
+            .take_while(|marker| !marker.is_a_finish())
+            .filter(|marker| marker.is_a_divider())
+            .count();
+        if self.positions.contains(&Marker::Finish) {
+            dividers
+        } else {
+            dividers + 1
+        }
+    }
+
+    /// Whether the race fits on a board with the given number of tiles, e.g. the `board_length` of a `RuleSet`.
+    ///
+    /// Parsing accepts races of any length that fits in `WORDS` words. A typo like a doubled comma can stretch a race beyond any board, which this catches before the race is projected.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, RaceParseError};
+    /// # use camel_up::rules::RuleSet;
+    /// let board = RuleSet::default().board_length;
+    ///
+    /// assert_eq!("r,,y,,!".parse::<Race>().expect("to parse").check_length(board), Ok(()));
+    /// assert_eq!(
+    ///     format!("r{}y", ",".repeat(39)).parse::<Race>().expect("to parse").check_length(board),
+    ///     Err(RaceParseError::TooLong(40))
+    /// );
+    /// ```
+    pub fn check_length(&self, board_length: usize) -> Result<(), RaceParseError> {
+        let tiles = self.tiles();
+        if tiles > board_length {
+            Err(RaceParseError::TooLong(tiles))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether every die is of a camel in the race, otherwise the first die that is not.
+    ///
+    /// The oracle only answers for dice of camels in the race, check dice that come from elsewhere before asking.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Dice, Camel, MoveError};
+    /// let race = "r,y".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.check_dice(&"ry".parse::<Dice>().expect("to parse")), Ok(()));
+    /// assert_eq!(race.check_dice(&"rw".parse::<Dice>().expect("to parse")), Err(MoveError::CamelNotInRace(Camel::White)));
+    /// ```
+    pub fn check_dice(&self, dice: &Dice) -> Result<(), MoveError> {
+        match dice
+            .iter()
+            .find(|camel| !self.positions.contains(&Marker::Camel(*camel)))
+        {
+            Some(camel) => Err(MoveError::CamelNotInRace(camel)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the dice can be rolled on the race for a whole leg, otherwise the problem a roll would run into.
+    ///
+    /// Every die is of a camel in the race, see `check_dice`, and the race keeps room for the markers its camels move into, which parsing reserves. Races made from markers directly may not.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Dice, Camel, MoveError};
+    /// let race = "r,y".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.check_leg(&"ry".parse::<Dice>().expect("to parse")), Ok(()));
+    /// assert_eq!(race.check_leg(&"rw".parse::<Dice>().expect("to parse")), Err(MoveError::CamelNotInRace(Camel::White)));
+    /// ```
+    pub fn check_leg(&self, dice: &Dice) -> Result<(), MoveError> {
+        self.check_dice(dice)?;
+        if self.positions.len() + dice.len() * PADDING > Positions::<WORDS>::CAPACITY {
+            Err(MoveError::TooManyMarkers)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The markers of the race, from the rear to the front.
+    pub fn positions(&self) -> Positions<WORDS> {
+        self.positions
+    }
+
+    /// The edition whose rules this race follows.
+    pub fn edition(&self) -> Edition {
+        self.edition
+    }
+
+    /// Returns this race, following the rules of the given edition.
+    ///
+    /// This sets the stacking on a fata morgana to the one of the edition.
+    pub fn with_edition(self, edition: Edition) -> Self {
+        Self {
+            edition,
+            stacking: Stacking::from(edition),
+            ..self
+        }
+    }
+
+    /// Where camel units end up when they land on a fata morgana.
+    pub fn stacking(&self) -> Stacking {
+        self.stacking
+    }
+
+    /// Returns this race, with camel units ending up at the given side of a stack when they land on a fata morgana.
+    pub fn with_stacking(self, stacking: Stacking) -> Self {
+        Self { stacking, ..self }
+    }
+
+    /// Returns this race, following the rules of the rule set.
+    pub fn with_rules(self, rules: &RuleSet) -> Self {
+        Self {
+            edition: rules.edition,
+            stacking: rules.fata_morgana,
+            ..self
+        }
+    }
+
+    /// Returns this race, following the same rules as the other race.
+    pub(crate) fn following(self, other: &Self) -> Self {
+        Self {
+            edition: other.edition,
+            stacking: other.stacking,
+            ..self
+        }
+    }
+
+    /// Place a trap, i.e. an oasis or a fata morgana, in the position with the given index.
+    ///
+    /// Positions are counted from the start of the race. The resulting race should follow the rules of the game, otherwise the violation is returned. A trap can not be placed on a tile with camels, nor on or next to a tile with another trap.
+    ///
+    /// The official rules keep the first tile of the track free as well. A race starts at its rearmost camel or trap, so its first tile is never free and no position lies behind it.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Marker, RaceParseError};
+    /// let race = "r,,,y".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.place_trap(2, Marker::Oasis), "r,,+,y".parse::<Race>());
+    /// assert_eq!(race.place_trap(3, Marker::Oasis), Err(RaceParseError::CamelInOasis(5)));
+    /// ```
+    ///
+    /// Panics when the marker is not a trap.
+    pub fn place_trap(&self, position: usize, trap: Marker) -> Result<Self, RaceParseError> {
+        assert!(trap.is_an_adjustment(), "only traps can be placed");
+        let dividers: Vec<usize> = self
+            .positions
+            .iter()
+            .enumerate()
+            .filter(|(_, marker)| marker.is_a_divider())
+            .map(|(index, _)| index)
+            .collect();
+        let mut positions = self.positions.to_vec();
+        if position < dividers.len() {
+            positions.insert(dividers[position], trap);
+        } else if position == dividers.len() {
+            let finish = positions.iter().position(|marker| marker.is_a_finish());
+            positions.insert(finish.unwrap_or(positions.len()), trap);
         } else {
-            let positions: Vec<Marker> = self.positions.to_vec();
-            Self::from(positions)
+            let padding = repeat_n(Marker::Divider, position - dividers.len());
+            positions.extend(padding.chain(once(trap)));
         }
+        validate(&positions, Positions::<WORDS>::CAPACITY)?;
+
+        Ok(Self::normalized(positions).following(self))
     }
 
-    /// Determines which camel is the winner, i.e. is at the front.
+    /// Determines which camel is the winner, i.e. is on top of the frontmost stack.
     pub fn winner(&self) -> Option<Camel> {
         self.positions
             .iter()
             .filter(|marker| marker.is_a_camel())
             .map(|marker| marker.to_camel().unwrap(/* camel is present because of filter on camel */))
-            .last()
+            .next_back()
     }
 
-    /// Determines which camel is the loser, i.e. is at the back.
+    /// Determines which camel is the loser, i.e. is at the bottom of the rearmost stack.
+    ///
+    /// A race with fewer than `DISTINCT_PLACES` camels has no loser, because the camel at the back is the winner or the runner up as well.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel};
+    /// assert_eq!("r,y,g".parse::<Race>().expect("to parse").loser(), Some(Camel::Red));
+    /// assert_eq!("r,y".parse::<Race>().expect("to parse").loser(), None);
+    /// ```
     pub fn loser(&self) -> Option<Camel> {
+        let mut camels = self.positions.iter().filter_map(Marker::to_camel);
+        let loser = camels.next();
+        if camels.count() + 1 < DISTINCT_PLACES {
+            None
+        } else {
+            loser
+        }
+    }
+
+    /// Whether a camel has crossed the finish, which ends the race.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel, Face};
+    /// let race = "r,,y!".parse::<Race>().expect("to parse");
+    /// assert!(!race.is_over());
+    ///
+    /// let race = race.perform((Camel::Yellow, Face::One));
+    /// assert!(race.is_over());
+    /// ```
+    pub fn is_over(&self) -> bool {
         self.positions
             .iter()
-            .filter(|marker| marker.is_a_camel())
-            .map(|marker| marker.to_camel().unwrap(/* camel is present because of filter on camel */))
-            .nth(0)
+            .skip_while(|marker| !marker.is_a_finish())
+            .any(|marker| marker.is_a_camel())
+    }
+
+    /// The position of the camel, counted in tiles from the start of the race.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel};
+    /// let race = "r,,yg".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.position_of(Camel::Green), Some(2));
+    /// assert_eq!(race.position_of(Camel::White), None);
+    /// ```
+    pub fn position_of(&self, camel: Camel) -> Option<usize> {
+        let index = self
+            .positions
+            .iter()
+            .position(|marker| marker == Marker::Camel(camel))?;
+        Some(
+            self.positions
+                .iter()
+                .take(index)
+                .filter(|marker| marker.is_a_divider())
+                .count(),
+        )
+    }
+
+    /// Ranks the camels from the front of the race to the back.
+    ///
+    /// A camel is ahead of the camels below it in its stack, see `Stack`.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel};
+    /// let race = "r,yg,w".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.ranking(), vec![Camel::White, Camel::Green, Camel::Yellow, Camel::Red]);
+    /// ```
+    pub fn ranking(&self) -> Vec<Camel> {
+        self.positions
+            .iter()
+            .rev()
+            .filter_map(|marker| marker.to_camel())
+            .collect()
     }
 
     /// Determines which camel is the runner up, i.e. is behind the winner.
+    ///
+    /// When the winner is on top of a stack, the runner up is the camel right below it. A race with a single camel has no runner up.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel};
+    /// let race = "w,gyor".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.runner_up(), Some(Camel::Orange));
+    /// ```
     pub fn runner_up(&self) -> Option<Camel> {
         self.positions
             .iter()
@@ -393,36 +1108,117 @@ impl Race {
             .rev()
             .nth(1)
     }
+
+    /// The camels on the tile, counted from the start of the race like `position_of`.
+    ///
+    /// ```
+    /// # use camel_up::camel::{Race, Camel};
+    /// let race = "r,gy".parse::<Race>().expect("to parse");
+    ///
+    /// assert_eq!(race.stack_at(1).top(), Some(Camel::Yellow));
+    /// assert!(race.stack_at(2).is_empty());
+    /// ```
+    pub fn stack_at(&self, tile: usize) -> Stack {
+        let camels: Vec<Camel> = self
+            .positions
+            .iter()
+            .scan(0, |dividers, marker| {
+                let on = *dividers;
+                if marker.is_a_divider() {
+                    *dividers += 1;
+                }
+                Some((on, marker))
+            })
+            .filter(|(on, _)| *on == tile)
+            .filter_map(|(_, marker)| marker.to_camel())
+            .collect();
+        Stack::from(camels)
+    }
+
+    /// The stack the camel is in, when the camel is in the race.
+    pub fn stack_of(&self, camel: Camel) -> Option<Stack> {
+        self.position_of(camel).map(|tile| self.stack_at(tile))
+    }
 }
 
 /// Represents the dice that still can be rolled.
-#[derive(PartialEq, Eq, Clone, Debug)]
-pub struct Dice(HashSet<Camel>);
+///
+/// Dice iterate in the order of the camels, red first and white last. The dice are a bit per camel, so copying them is cheap.
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Dice(u8);
+
+/// The bit of the die of the camel.
+fn bit(camel: Camel) -> u8 {
+    1 << camel as u8
+}
 
 impl Dice {
+    /// Whether the die of this camel is still in the pyramid.
+    pub fn contains(&self, camel: Camel) -> bool {
+        self.0 & bit(camel) != 0
+    }
+
+    /// Whether all dice have been thrown.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of dice in the pyramid.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
     /// Remove a dice from the pyramid, i.e. the options to throw are reduced.
     pub fn remove(&self, camel: Camel) -> Self {
-        let mut dice = self.0.clone();
-        dice.remove(&camel);
-        Self::from(dice)
+        Self(self.0 & !bit(camel))
+    }
+
+    /// Iterates over the dice, in the order of the camels.
+    pub fn iter(&self) -> DiceIter {
+        DiceIter(self.0)
     }
 }
 
 impl Default for Dice {
     fn default() -> Self {
-        let mut dice = HashSet::new();
-        dice.insert(Camel::Red);
-        dice.insert(Camel::Orange);
-        dice.insert(Camel::Yellow);
-        dice.insert(Camel::Green);
-        dice.insert(Camel::White);
-        Self::from(dice)
+        CAMELS.iter().copied().collect()
+    }
+}
+
+/// The number of places that are told apart: the winner, the runner up and the loser. A race with fewer camels leaves the later places without a camel, rather than give a camel two places.
+pub const DISTINCT_PLACES: usize = 3;
+
+/// The camels, in the order of their dice.
+pub(crate) const CAMELS: [Camel; 5] = [
+    Camel::Red,
+    Camel::Orange,
+    Camel::Yellow,
+    Camel::Green,
+    Camel::White,
+];
+
+impl FromIterator<Camel> for Dice {
+    fn from_iter<I: IntoIterator<Item = Camel>>(camels: I) -> Self {
+        Self(camels.into_iter().fold(0, |dice, camel| dice | bit(camel)))
+    }
+}
+
+impl From<BTreeSet<Camel>> for Dice {
+    fn from(dice: BTreeSet<Camel>) -> Self {
+        dice.into_iter().collect()
     }
 }
 
 impl From<HashSet<Camel>> for Dice {
     fn from(dice: HashSet<Camel>) -> Self {
-        Self(dice)
+        dice.into_iter().collect()
+    }
+}
+
+impl<const WORDS: usize> From<&Race<WORDS>> for Dice {
+    /// The dice of all the camels in the race.
+    fn from(race: &Race<WORDS>) -> Self {
+        race.positions.iter().filter_map(Marker::to_camel).collect()
     }
 }
 
@@ -430,48 +1226,114 @@ impl FromStr for Dice {
     type Err = NoDice;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut dice = HashSet::new();
-        let mut index = 0;
-        while index < input.len() {
-            let marker = input[index..=index].parse::<Marker>()?;
-            index += 1;
+        let mut dice = Dice(0);
+        for (position, symbol) in input.chars().enumerate() {
+            let marker = symbol
+                .to_string()
+                .parse::<Marker>()
+                .map_err(|problem| NoDice::NotAMarker(position, problem))?;
             match marker.to_camel() {
                 Some(camel) => {
-                    dice.insert(camel);
+                    dice.0 |= bit(camel);
                 }
                 None => {
-                    return Err(NoDice::NotACamel);
+                    return Err(NoDice::NotACamel(position));
                 }
             }
         }
-        Ok(Dice::from(dice))
+        Ok(dice)
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for camel in self {
+            write!(f, "{}", Marker::Camel(camel))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Dice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let camels: BTreeSet<Camel> = self.iter().collect();
+        f.debug_tuple("Dice").field(&camels).finish()
     }
 }
 
 impl IntoIterator for Dice {
     type Item = Camel;
-    type IntoIter = std::collections::hash_set::IntoIter<Self::Item>;
+    type IntoIter = DiceIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.iter()
+    }
+}
+
+impl IntoIterator for &Dice {
+    type Item = Camel;
+    type IntoIter = DiceIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over dice, in the order of the camels.
+pub struct DiceIter(u8);
+
+impl Iterator for DiceIter {
+    type Item = Camel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let camel = CAMELS
+            .iter()
+            .copied()
+            .find(|camel| self.0 & bit(*camel) != 0)?;
+        self.0 &= !bit(camel);
+        Some(camel)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.0.count_ones() as usize;
+        (remaining, Some(remaining))
     }
 }
 
+impl ExactSizeIterator for DiceIter {}
+
 /// When parsing of Dice goes wrong, this enumeration tells you precisely what went down.
+///
+/// Each variant carries the position of the offending symbol in the input, counted in characters.
 #[derive(PartialEq, Debug)]
 pub enum NoDice {
     /// What is encountered isn't even a marker.
-    NotAMarker(NotAMarker),
+    NotAMarker(usize, NotAMarker),
     /// It is a marker, but not a camel.
-    NotACamel,
+    NotACamel(usize),
+}
+
+impl NoDice {
+    /// The position in the input, counted in characters, where parsing went wrong.
+    pub fn position(&self) -> usize {
+        match self {
+            NoDice::NotAMarker(position, _) => *position,
+            NoDice::NotACamel(position) => *position,
+        }
+    }
 }
 
-impl From<NotAMarker> for NoDice {
-    fn from(error: NotAMarker) -> Self {
-        NoDice::NotAMarker(error)
+impl Display for NoDice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NoDice::NotAMarker(_, problem) => write!(f, "{}", problem),
+            NoDice::NotACamel(_) => write!(f, "dice can only be camels"),
+        }
     }
 }
 
+impl Error for NoDice {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -523,7 +1385,7 @@ mod test {
     #[test]
     fn camel_can_not_be_in_an_oasis() {
         let left = "r+,y".parse::<Race>();
-        let right = Err(RaceParseError::CamelInOasis);
+        let right = Err(RaceParseError::CamelInOasis(1));
 
         assert_eq!(left, right);
     }
@@ -531,7 +1393,7 @@ mod test {
     #[test]
     fn camel_can_not_be_in_a_fata_morgana() {
         let left = "r-,y".parse::<Race>();
-        let right = Err(RaceParseError::CamelInFataMorgana);
+        let right = Err(RaceParseError::CamelInFataMorgana(1));
 
         assert_eq!(left, right);
     }
@@ -539,7 +1401,7 @@ mod test {
     #[test]
     fn adjustments_can_not_be_in_same_position() {
         let left = "r,+-,y".parse::<Race>();
-        let right = Err(RaceParseError::ToManyAdjustmentsInOnePosition);
+        let right = Err(RaceParseError::ToManyAdjustmentsInOnePosition(3));
 
         assert_eq!(left, right);
     }
@@ -547,7 +1409,7 @@ mod test {
     #[test]
     fn adjustments_can_not_be_consecutive() {
         let left = "r,+,-,y".parse::<Race>();
-        let right = Err(RaceParseError::ConsecutiveAdjustments);
+        let right = Err(RaceParseError::ConsecutiveAdjustments(4));
 
         assert_eq!(left, right);
     }
@@ -564,6 +1426,38 @@ mod test {
         assert_eq!(left, right);
     }
 
+    #[test]
+    fn races_without_camels_do_not_panic() {
+        for markers in [vec![], vec![Marker::Divider; 3]] {
+            let race = Race::from(markers);
+
+            assert_eq!(race, Race::default());
+            assert_eq!(race.ranking(), vec![]);
+            assert_eq!(race.runner_up(), None);
+            assert!(!race.is_over());
+            assert_eq!(
+                crate::oracle::project(&race, &Dice::from(&race))
+                    .winner
+                    .values()
+                    .count(),
+                0
+            );
+        }
+
+        let traps = ",,+,,!".parse::<Race>().expect("to parse");
+        assert_eq!(traps.to_string(), "+,,!");
+        assert_eq!(traps.loser(), None);
+    }
+
+    #[test]
+    fn normalization_keeps_traps_beyond_the_camels() {
+        let race = ",,+,,r,y,,-,,!".parse::<Race>().expect("to parse");
+        assert_eq!(race.to_string(), "+,,r,y,,-,,!");
+
+        let after = race.perform("y1".parse::<Roll>().expect("to parse"));
+        assert_eq!(after.to_string(), "+,,r,,y,-,,!");
+    }
+
     #[test]
     fn races_can_have_a_finish() {
         let left = "r,y,!".parse::<Race>().expect("to parse");
@@ -581,7 +1475,7 @@ mod test {
     #[test]
     fn races_can_have_only_one_finish() {
         let left = "r,y,!!".parse::<Race>();
-        let right = Err(RaceParseError::MultipleFinishes);
+        let right = Err(RaceParseError::MultipleFinishes(5));
 
         assert_eq!(left, right);
     }
@@ -589,7 +1483,7 @@ mod test {
     #[test]
     fn races_can_have_only_finished_at_the_end() {
         let left = "r,y,!,w".parse::<Race>();
-        let right = Err(RaceParseError::MarkersAfterFinish);
+        let right = Err(RaceParseError::MarkersAfterFinish(5));
 
         assert_eq!(left, right);
     }
@@ -639,6 +1533,217 @@ mod test {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn second_edition_stacks_under_on_a_fata_morgana() {
+        let race = "gy,r,-"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_edition(Edition::Second);
+        let result = race.perform((Camel::Yellow, Face::Two));
+        let expected = "g,yr,-"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_edition(Edition::Second);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn classic_edition_stacks_on_top_on_a_fata_morgana() {
+        let race = "gy,r,-".parse::<Race>().expect("to parse");
+        let result = race.perform((Camel::Yellow, Face::Two));
+        let expected = "g,ry,-".parse::<Race>().expect("to parse");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn editions_can_be_parsed() {
+        assert_eq!("second".parse::<Edition>(), Ok(Edition::Second));
+        assert_eq!(
+            "third".parse::<Edition>(),
+            Err(NoEdition::But("third".to_owned()))
+        );
+    }
+
+    #[test]
+    fn races_hold_a_limited_number_of_markers() {
+        let room = CAPACITY - 2 * PADDING;
+        let longest = format!("r{}y", ",".repeat(room - 2));
+        let too_long = format!(",,r{}y", ",".repeat(room - 1));
+
+        assert!(longest.parse::<Race>().is_ok());
+        assert_eq!(
+            too_long.parse::<Race>(),
+            Err(RaceParseError::TooManyMarkers(room + 2))
+        );
+    }
+
+    #[test]
+    fn tracks_fit_a_board_up_to_its_last_tile() {
+        let board = 16;
+        let fitting = format!("r{}!", ",".repeat(board))
+            .parse::<Race>()
+            .expect("to parse");
+        let too_long = format!("r{}!", ",".repeat(board + 1))
+            .parse::<Race>()
+            .expect("to parse");
+
+        assert_eq!(fitting.tiles(), board);
+        assert_eq!(fitting.check_length(board), Ok(()));
+        assert_eq!(
+            too_long.check_length(board),
+            Err(RaceParseError::TooLong(board + 1))
+        );
+    }
+
+    #[test]
+    fn the_longest_races_fit_for_a_whole_leg() {
+        let longest = format!("r{}y", ",".repeat(CAPACITY - 2 * PADDING - 2))
+            .parse::<Race>()
+            .expect("to parse");
+
+        let ahead = longest
+            .perform_checked("y3".parse::<Roll>().expect("to parse"))
+            .expect("to fit");
+        let leapfrog = longest
+            .perform_checked("r3".parse::<Roll>().expect("to parse"))
+            .and_then(|race| race.perform_checked("y3".parse::<Roll>().expect("to parse")))
+            .expect("to fit");
+
+        assert_eq!(ahead.positions.len(), CAPACITY - 2 * PADDING + 3);
+        assert_eq!(leapfrog.positions.len(), CAPACITY - 2 * PADDING);
+        assert_eq!(
+            (0..3).try_fold(longest, |race, _| {
+                race.perform_checked("y3".parse::<Roll>().expect("to parse"))
+            }),
+            Err(MoveError::TooManyMarkers)
+        );
+        assert_eq!(
+            format!("r{}y", ",".repeat(CAPACITY - 2)).parse::<Race>(),
+            Err(RaceParseError::TooManyMarkers(CAPACITY - 2 * PADDING))
+        );
+    }
+
+    #[test]
+    fn parse_errors_carry_their_position() {
+        let left = "r,yé".parse::<Race>();
+        let right = Err(RaceParseError::NotAMarker(
+            3,
+            NotAMarker::But("é".to_owned()),
+        ));
+
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn dice_parse_errors_carry_their_position() {
+        assert_eq!("ry,".parse::<Dice>(), Err(NoDice::NotACamel(2)));
+    }
+
+    #[test]
+    fn rolls_can_be_parsed() {
+        assert_eq!("g3".parse::<Roll>(), Ok(Roll::from((Camel::Green, Face::Three))));
+        assert_eq!(
+            "g4".parse::<Roll>(),
+            Err(RollParseError::NotAFace("4".to_owned()))
+        );
+        assert_eq!("+1".parse::<Roll>(), Err(RollParseError::NotACamel));
+    }
+
+    #[test]
+    fn races_can_be_displayed() {
+        let race = "r,+,yg,,-,w!".parse::<Race>().expect("to parse");
+
+        assert_eq!(race.to_string(), "r,+,yg,,-,w!");
+    }
+
+    #[test]
+    fn traps_can_be_placed_ahead_of_the_race() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+
+        let actual = race.place_trap(3, Marker::FataMorgana);
+
+        assert_eq!(actual, "r,y,,-".parse::<Race>());
+    }
+
+    #[test]
+    fn traps_follow_the_official_placement_rules() {
+        let cases = [
+            ("r,yo,,w", 1, Err(RaceParseError::CamelInOasis(4))),
+            ("r,yo,,w", 2, "r,yo,+,w".parse::<Race>()),
+            ("r,+,,y", 2, Err(RaceParseError::ConsecutiveAdjustments(4))),
+            ("r,+,,,y", 3, "r,+,,+,y".parse::<Race>()),
+            (
+                "r,+,,y",
+                1,
+                Err(RaceParseError::ToManyAdjustmentsInOnePosition(3)),
+            ),
+            (
+                "-,r,,,y",
+                0,
+                Err(RaceParseError::ToManyAdjustmentsInOnePosition(1)),
+            ),
+            ("-,r,,,y", 1, Err(RaceParseError::CamelInOasis(3))),
+            ("r,,,y", 0, Err(RaceParseError::CamelInOasis(1))),
+        ];
+
+        for (race, position, expected) in cases {
+            let race = race.parse::<Race>().expect("to parse");
+
+            assert_eq!(
+                race.place_trap(position, Marker::Oasis),
+                expected,
+                "{} at {}",
+                race,
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn traps_can_not_be_placed_beyond_the_finish() {
+        let race = "r,y,!".parse::<Race>().expect("to parse");
+
+        assert_eq!(
+            race.place_trap(2, Marker::FataMorgana),
+            "r,y,-!".parse::<Race>()
+        );
+        assert_eq!(
+            race.place_trap(3, Marker::FataMorgana),
+            Err(RaceParseError::MarkersAfterFinish(5))
+        );
+    }
+
+    #[test]
+    fn dice_can_be_displayed() {
+        let dice = "wgr".parse::<Dice>().expect("to parse");
+
+        assert_eq!(dice.to_string(), "rgw");
+    }
+
+    #[test]
+    fn dice_iterate_in_the_order_of_the_camels() {
+        let dice = "wgr".parse::<Dice>().expect("to parse");
+
+        assert_eq!(
+            dice.into_iter().collect::<Vec<Camel>>(),
+            vec![Camel::Red, Camel::Green, Camel::White]
+        );
+    }
+
+    #[test]
+    fn removing_a_die_leaves_the_other_dice() {
+        let dice = Dice::default();
+
+        let remaining = dice.remove(Camel::Yellow);
+
+        assert_eq!(dice.len(), 5);
+        assert_eq!(remaining.len(), 4);
+        assert!(!remaining.contains(Camel::Yellow));
+        assert_eq!(remaining.to_string(), "rogw");
+    }
+
     #[test]
     fn dice_can_be_parsed() {
         let actual = "ryg".parse::<Dice>().expect("to parse");
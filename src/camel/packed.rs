@@ -0,0 +1,310 @@
+//! The markers of a race, packed in a few machine words.
+//!
+//! Every marker takes four bits, so a race fits in a fixed number of `u64`s and copying a race copies a handful of words instead of a heap allocation. A race holds at most `CAPACITY` markers, which leaves plenty of room for a board of 16 tiles with its camels, traps and finish, and for the 20 tiles of the Supercup.
+//!
+//! Longer tracks need more words. Positions and races take the number of words as a const parameter, and `words_for` tells how many words a track needs, so that even long races live on the stack.
+//!
+//! ```
+//! # use camel_up::camel::{packed::words_for, Race};
+//! const LONG: usize = words_for(48);
+//! let race = format!("r{}y", ",".repeat(80)).parse::<Race<LONG>>().expect("to parse");
+//!
+//! assert_eq!(race.positions().len(), 82);
+//! ```
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! let race = "r,+,y".parse::<Race>().expect("to parse");
+//! let positions = race.positions();
+//!
+//! assert_eq!(positions.len(), 5);
+//! assert_eq!(positions[2], Marker::Oasis);
+//! assert_eq!(positions.last(), Some(Marker::Camel(Camel::Yellow)));
+//! ```
+use super::{Camel, Marker};
+use std::{
+    fmt::{self, Debug, Formatter},
+    iter::FromIterator,
+    ops::Index,
+};
+
+/// The number of bits a marker takes.
+const BITS: usize = 4;
+
+const MARKERS_PER_WORD: usize = u64::BITS as usize / BITS;
+
+/// The number of words of positions, unless asked otherwise.
+pub const DEFAULT_WORDS: usize = 4;
+
+/// The number of markers a race can hold, unless it is given more words.
+pub const CAPACITY: usize = DEFAULT_WORDS * MARKERS_PER_WORD;
+
+/// The number of markers a leg can move camels beyond the finish, four dividers for each die.
+const BEYOND_FINISH: usize = 5 * 4;
+
+/// The number of words a race needs on a track of the given number of tiles.
+///
+/// The race spans every tile with a camel on one of the tiles, a trap on at most every other tile, the finish and the tiles camels can move beyond the finish in a leg.
+pub const fn words_for(tiles: usize) -> usize {
+    let markers = tiles + 5 + tiles.div_ceil(2) + 1 + BEYOND_FINISH;
+    markers.div_ceil(MARKERS_PER_WORD)
+}
+
+const MASK: u64 = (1 << BITS) - 1;
+
+/// Every marker, indexed by its code.
+const MARKERS: [Marker; 9] = [
+    Marker::Divider,
+    Marker::Camel(Camel::Red),
+    Marker::Camel(Camel::Orange),
+    Marker::Camel(Camel::Yellow),
+    Marker::Camel(Camel::Green),
+    Marker::Camel(Camel::White),
+    Marker::Oasis,
+    Marker::FataMorgana,
+    Marker::Finish,
+];
+
+fn code(marker: Marker) -> u64 {
+    match marker {
+        Marker::Divider => 0,
+        Marker::Camel(Camel::Red) => 1,
+        Marker::Camel(Camel::Orange) => 2,
+        Marker::Camel(Camel::Yellow) => 3,
+        Marker::Camel(Camel::Green) => 4,
+        Marker::Camel(Camel::White) => 5,
+        Marker::Oasis => 6,
+        Marker::FataMorgana => 7,
+        Marker::Finish => 8,
+    }
+}
+
+/// The markers of a race, from the rear to the front, packed in `WORDS` words.
+///
+/// The bits beyond the last marker are always zero, so that equal markers make equal positions.
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Positions<const WORDS: usize = DEFAULT_WORDS> {
+    words: [u64; WORDS],
+    len: u16,
+}
+
+impl<const WORDS: usize> Positions<WORDS> {
+    /// The number of markers these positions can hold.
+    pub const CAPACITY: usize = WORDS * MARKERS_PER_WORD;
+
+    /// The number of markers.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether there are no markers.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The marker at the index, if there is one.
+    pub fn get(&self, index: usize) -> Option<Marker> {
+        if index < self.len() {
+            Some(MARKERS[self.code(index) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// The rearmost marker.
+    pub fn first(&self) -> Option<Marker> {
+        self.get(0)
+    }
+
+    /// The frontmost marker.
+    pub fn last(&self) -> Option<Marker> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Whether the marker occurs.
+    pub fn contains(&self, marker: &Marker) -> bool {
+        let code = code(*marker);
+        (0..self.len()).any(|index| self.code(index) == code)
+    }
+
+    /// Iterates over the markers, from the rear to the front.
+    pub fn iter(&self) -> Iter<'_, WORDS> {
+        Iter {
+            positions: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// The markers in a vector.
+    pub fn to_vec(&self) -> Vec<Marker> {
+        self.iter().collect()
+    }
+
+    /// Adds the marker at the front.
+    ///
+    /// Panics when the positions already hold `CAPACITY` markers.
+    pub(crate) fn push(&mut self, marker: Marker) {
+        let index = self.len();
+        assert!(
+            index < Self::CAPACITY,
+            "a race holds at most {} markers",
+            Self::CAPACITY
+        );
+        self.words[index / MARKERS_PER_WORD] |= code(marker) << shift(index);
+        self.len += 1;
+    }
+
+    /// Removes the marker at the index, the markers in front of it move back.
+    pub(crate) fn remove(&mut self, index: usize) -> Marker {
+        let marker = self[index];
+        *self = self
+            .iter()
+            .enumerate()
+            .filter(|(candidate, _)| *candidate != index)
+            .map(|(_, marker)| marker)
+            .collect();
+        marker
+    }
+
+    fn code(&self, index: usize) -> u64 {
+        (self.words[index / MARKERS_PER_WORD] >> shift(index)) & MASK
+    }
+}
+
+fn shift(index: usize) -> usize {
+    (index % MARKERS_PER_WORD) * BITS
+}
+
+impl<const WORDS: usize> Default for Positions<WORDS> {
+    fn default() -> Self {
+        Self {
+            words: [0; WORDS],
+            len: 0,
+        }
+    }
+}
+
+impl<const WORDS: usize> Index<usize> for Positions<WORDS> {
+    type Output = Marker;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(
+            index < self.len(),
+            "index {} is out of bounds for {} markers",
+            index,
+            self.len()
+        );
+        &MARKERS[self.code(index) as usize]
+    }
+}
+
+impl<const WORDS: usize> FromIterator<Marker> for Positions<WORDS> {
+    /// Panics when there are more than `CAPACITY` markers.
+    fn from_iter<I: IntoIterator<Item = Marker>>(markers: I) -> Self {
+        let mut positions = Self::default();
+        for marker in markers {
+            positions.push(marker);
+        }
+        positions
+    }
+}
+
+impl<const WORDS: usize> Debug for Positions<WORDS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, const WORDS: usize> IntoIterator for &'a Positions<WORDS> {
+    type Item = Marker;
+    type IntoIter = Iter<'a, WORDS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the markers of positions.
+pub struct Iter<'a, const WORDS: usize = DEFAULT_WORDS> {
+    positions: &'a Positions<WORDS>,
+    front: usize,
+    back: usize,
+}
+
+impl<const WORDS: usize> Iterator for Iter<'_, WORDS> {
+    type Item = Marker;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front < self.back {
+            self.front += 1;
+            self.positions.get(self.front - 1)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const WORDS: usize> DoubleEndedIterator for Iter<'_, WORDS> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front < self.back {
+            self.back -= 1;
+            self.positions.get(self.back)
+        } else {
+            None
+        }
+    }
+}
+
+impl<const WORDS: usize> ExactSizeIterator for Iter<'_, WORDS> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn markers_are_packed_and_unpacked() {
+        let markers: Vec<Marker> = MARKERS.iter().copied().cycle().take(CAPACITY).collect();
+
+        let positions: Positions = markers.iter().copied().collect();
+
+        assert_eq!(positions.len(), CAPACITY);
+        assert_eq!(positions.to_vec(), markers);
+        assert_eq!(positions.iter().next_back(), markers.last().copied());
+    }
+
+    #[test]
+    fn longer_tracks_take_more_words() {
+        assert_eq!(words_for(16), DEFAULT_WORDS);
+        assert_eq!(words_for(20), DEFAULT_WORDS);
+        assert!(words_for(32) > DEFAULT_WORDS);
+
+        let markers: Vec<Marker> = MARKERS.iter().copied().cycle().take(100).collect();
+        let positions: Positions<7> = markers.iter().copied().collect();
+
+        assert_eq!(Positions::<7>::CAPACITY, 112);
+        assert_eq!(positions.to_vec(), markers);
+    }
+
+    #[test]
+    fn removing_a_marker_keeps_positions_equal() {
+        let mut positions: Positions = MARKERS.iter().copied().collect();
+
+        let removed = positions.remove(1);
+
+        assert_eq!(removed, Marker::Camel(Camel::Red));
+        assert_eq!(
+            positions,
+            MARKERS
+                .iter()
+                .copied()
+                .filter(|marker| *marker != removed)
+                .collect()
+        );
+    }
+}
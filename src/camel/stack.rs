@@ -0,0 +1,134 @@
+//! The camels on a single tile, stacked on top of each other.
+//!
+//! In the notation of a race the camels on a tile are written from the bottom of the stack to the top. In `"gyor"` green is at the bottom and red on top. A camel carries every camel above it when it moves, and lands on top of the stack it moves to. The camel on top of the frontmost stack is the winner, the camel below it is the runner up.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! let race = "gyor".parse::<Race>().expect("to parse");
+//! let stack = race.stack_at(0);
+//!
+//! assert_eq!(stack.bottom(), Some(Camel::Green));
+//! assert_eq!(stack.top(), Some(Camel::Red));
+//! assert_eq!(stack.unit(Camel::Yellow), Some(&[Camel::Yellow, Camel::Orange, Camel::Red][..]));
+//! assert_eq!(race.winner(), stack.top());
+//! ```
+use super::Camel;
+
+/// The camels on a tile, from the bottom of the stack to the top.
+#[derive(PartialEq, Eq, Hash, Clone, Default, Debug)]
+pub struct Stack {
+    camels: Vec<Camel>,
+}
+
+impl Stack {
+    /// The camel at the bottom of the stack, which carries every other camel.
+    pub fn bottom(&self) -> Option<Camel> {
+        self.camels.first().copied()
+    }
+
+    /// The camel on top of the stack, which is ahead of every other camel on the tile.
+    pub fn top(&self) -> Option<Camel> {
+        self.camels.last().copied()
+    }
+
+    /// The number of camels on the tile.
+    pub fn len(&self) -> usize {
+        self.camels.len()
+    }
+
+    /// Whether there are no camels on the tile.
+    pub fn is_empty(&self) -> bool {
+        self.camels.is_empty()
+    }
+
+    /// Whether the camel is on the tile.
+    pub fn contains(&self, camel: Camel) -> bool {
+        self.camels.contains(&camel)
+    }
+
+    /// The camel with every camel it carries, i.e. the camels that move when its die is rolled, from the bottom to the top.
+    pub fn unit(&self, camel: Camel) -> Option<&[Camel]> {
+        self.camels
+            .iter()
+            .position(|candidate| *candidate == camel)
+            .map(|index| &self.camels[index..])
+    }
+
+    /// Iterates over the camels, from the bottom of the stack to the top.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Camel> + ExactSizeIterator + '_ {
+        self.camels.iter().copied()
+    }
+}
+
+impl From<Vec<Camel>> for Stack {
+    /// The stack of the camels, from the bottom to the top.
+    fn from(camels: Vec<Camel>) -> Self {
+        Self { camels }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::camel::{Race, Roll, Stacking};
+
+    /// How rolls carry camels: the race, the roll and the stack the rolled camel ends up in, from the bottom to the top.
+    const CARRYING: [(&str, &str, &str); 9] = [
+        ("r,y", "r1", "yr"),
+        ("ry", "r1", "ry"),
+        ("ry", "y2", "y"),
+        ("gyor", "y1", "yor"),
+        ("gyor,w", "y1", "wyor"),
+        ("gyor,w", "r2", "r"),
+        ("r,+,y", "r1", "yr"),
+        ("r,-,y", "r1", "r"),
+        ("r,g,-", "r2", "gr"),
+    ];
+
+    fn stack(camels: &str) -> Stack {
+        let race = camels.parse::<Race>().expect("to parse");
+        Stack::from(race.ranking().into_iter().rev().collect::<Vec<Camel>>())
+    }
+
+    #[test]
+    fn rolls_carry_the_camels_above() {
+        for (race, roll, expected) in CARRYING {
+            let race = race.parse::<Race>().expect("to parse");
+            let roll = roll.parse::<Roll>().expect("to parse");
+
+            let after = race.perform(roll);
+            let tile = after.position_of(roll.camel()).expect("a camel");
+
+            assert_eq!(
+                after.stack_at(tile),
+                stack(expected),
+                "{} after {}",
+                race,
+                roll
+            );
+            assert_eq!(after.stack_of(roll.camel()), Some(stack(expected)));
+        }
+    }
+
+    #[test]
+    fn stacking_under_puts_the_unit_at_the_bottom() {
+        let race = "r,g,-"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_stacking(Stacking::Under);
+
+        let after = race.perform("r2".parse::<Roll>().expect("to parse"));
+
+        assert_eq!(after.stack_at(0), stack("rg"));
+        assert_eq!(after.stack_at(0).bottom(), Some(Camel::Red));
+    }
+
+    #[test]
+    fn tiles_without_camels_have_empty_stacks() {
+        let race = "r,+,y".parse::<Race>().expect("to parse");
+
+        assert!(race.stack_at(1).is_empty());
+        assert!(race.stack_at(7).is_empty());
+        assert_eq!(race.stack_of(Camel::White), None);
+    }
+}
@@ -0,0 +1,252 @@
+//! Table-driven cases for the rules of movement, to check `perform` and other implementations against.
+//!
+//! Every case is a race, a roll and the race it becomes, in the notation of the command line. Together the cases cover how a roll can play out: carrying the camels above, landing on an oasis with each face, a fata morgana right after the start of the track, stacked units on traps, both ways of stacking on a fata morgana, and crossing the finish.
+//!
+//! ```
+//! # use camel_up::conformance::check;
+//! let failures = check(|race, roll| race.perform(roll));
+//!
+//! assert!(failures.is_empty(), "{:?}", failures);
+//! ```
+//!
+//! A camel that lands on the tile of the finish, or beyond it, has crossed the finish and is written after it. Such a race does not parse, so the races after the rolls are compared as they are written. This module is only available with the `conformance` feature.
+use crate::camel::{Race, Roll, Stacking};
+use std::fmt::{self, Display, Formatter};
+
+/// A roll on a race, and the race it becomes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Case {
+    /// What the case shows.
+    pub name: &'static str,
+    /// The race before the roll.
+    pub race: &'static str,
+    /// Where camels end up on a fata morgana.
+    pub stacking: Stacking,
+    /// The roll.
+    pub roll: &'static str,
+    /// The race after the roll, as it is written.
+    pub expected: &'static str,
+}
+
+impl Case {
+    /// The race before the roll, following the stacking of the case.
+    pub fn race(&self) -> Race {
+        self.race
+            .parse::<Race>()
+            .expect("the races of the cases to parse")
+            .with_stacking(self.stacking)
+    }
+
+    /// The roll of the case.
+    pub fn roll(&self) -> Roll {
+        self.roll
+            .parse::<Roll>()
+            .expect("the rolls of the cases to parse")
+    }
+}
+
+const fn case(
+    name: &'static str,
+    race: &'static str,
+    roll: &'static str,
+    expected: &'static str,
+) -> Case {
+    Case {
+        name,
+        race,
+        stacking: Stacking::OnTop,
+        roll,
+        expected,
+    }
+}
+
+const fn under(
+    name: &'static str,
+    race: &'static str,
+    roll: &'static str,
+    expected: &'static str,
+) -> Case {
+    Case {
+        name,
+        race,
+        stacking: Stacking::Under,
+        roll,
+        expected,
+    }
+}
+
+/// The cases, grouped by the rule they exercise.
+pub const CASES: [Case; 30] = [
+    case("a camel moves by the face", "r,y", "y3", "r,,,,y"),
+    case("a camel passes the camels ahead", "r,y", "r3", "y,,r"),
+    case("a camel lands on top", "r,y", "r1", "yr"),
+    case("a camel carries the camel above", "ry", "r1", "ry"),
+    case("a camel leaves the camel below", "ry", "y1", "r,y"),
+    case(
+        "a camel in the middle carries the camels above",
+        "gyor",
+        "y2",
+        "g,,yor",
+    ),
+    case("a stack lands on top of a stack", "gyor,w", "y1", "g,wyor"),
+    under("landing on a tile ignores the stacking", "r,y", "r2", "y,r"),
+    case("an oasis after a one", "r,+", "r1", "+,r"),
+    case("an oasis after a two", "r,,+", "r2", "+,r"),
+    case("an oasis after a three", "r,,,+", "r3", "+,r"),
+    case(
+        "an oasis moves a camel on top of a stack",
+        "r,+,y",
+        "r1",
+        "+,yr",
+    ),
+    case(
+        "an oasis moves a camel to an empty tile",
+        "r,+,,y",
+        "r1",
+        "+,r,y",
+    ),
+    case("a fata morgana after a one", "r,,-", "r2", "r,-"),
+    case("a fata morgana after a three", "r,,,-", "r3", "r,-"),
+    case(
+        "a fata morgana at the start of the track",
+        "r,-",
+        "r1",
+        "r,-",
+    ),
+    case(
+        "a fata morgana at the start puts a camel back on top",
+        "gr,-",
+        "r1",
+        "gr,-",
+    ),
+    under(
+        "a fata morgana at the start puts a camel back under",
+        "gr,-",
+        "r1",
+        "rg,-",
+    ),
+    case(
+        "a fata morgana puts a camel on top of a stack",
+        "o,gy,-",
+        "o2",
+        "gyo,-",
+    ),
+    under(
+        "a fata morgana puts a camel under a stack",
+        "o,gy,-",
+        "o2",
+        "ogy,-",
+    ),
+    under(
+        "a fata morgana puts a camel back on its empty tile",
+        "o,r,-,g",
+        "r1",
+        "o,r,-,g",
+    ),
+    case("a stack on an oasis", "ry,+", "r1", "+,ry"),
+    case(
+        "a stack on an oasis lands on top of a stack",
+        "gry,+,w",
+        "r1",
+        "g,+,wry",
+    ),
+    under(
+        "a stack on an oasis lands on top whatever the stacking",
+        "gry,+,w",
+        "r1",
+        "g,+,wry",
+    ),
+    case("a stack on a fata morgana", "gry,,-", "r2", "g,ry,-"),
+    case("a camel crosses the finish", "r,,y!", "y1", "r,,!,y"),
+    case(
+        "a camel on the tile of the finish has crossed it",
+        "r,y!",
+        "r1",
+        "y!r",
+    ),
+    case("a stack crosses the finish", "ry,!", "r2", "!,ry"),
+    case(
+        "an oasis carries a camel across the finish",
+        "r,+,!",
+        "r1",
+        "+,!r",
+    ),
+    case(
+        "a fata morgana keeps a camel from the finish",
+        "r,y,,-,!",
+        "r3",
+        "y,r,-,!",
+    ),
+];
+
+/// A case where the race after the roll is not the expected race.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Failure {
+    /// The case.
+    pub case: Case,
+    /// The race after the roll.
+    pub actual: Race,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} after {} should be {}, not {}",
+            self.case.name, self.case.race, self.case.roll, self.case.expected, self.actual
+        )
+    }
+}
+
+/// Performs the roll of every case with `perform`, and returns the cases where the race is not the expected race.
+pub fn check<F>(perform: F) -> Vec<Failure>
+where
+    F: Fn(&Race, Roll) -> Race,
+{
+    CASES
+        .iter()
+        .filter_map(|case| {
+            let actual = perform(&case.race(), case.roll());
+            if actual.to_string() == case.expected {
+                None
+            } else {
+                Some(Failure {
+                    case: *case,
+                    actual,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn perform_follows_the_rules() {
+        let failures: Vec<String> = check(|race, roll| race.perform(roll))
+            .iter()
+            .map(Failure::to_string)
+            .collect();
+
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+
+    #[test]
+    fn rolls_that_do_nothing_fail_the_cases_that_move() {
+        let failures = check(|race, _| *race);
+
+        assert_eq!(
+            failures.len(),
+            CASES
+                .iter()
+                .filter(|case| case.race != case.expected)
+                .count()
+        );
+        assert!(!failures.is_empty());
+        assert!(failures
+            .iter()
+            .all(|failure| failure.actual == failure.case.race()));
+    }
+}
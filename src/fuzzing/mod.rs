@@ -0,0 +1,219 @@
+//! Arbitrary inputs and invariant checks for fuzzing.
+//!
+//! The `Arbitrary` implementations turn the bytes of a fuzzer into camels, faces, rolls, dice and races. Arbitrary races always follow the rules of the game, so that a fuzzer spends its time on `perform` and `project` rather than on races the parser rejects.
+//!
+//! The checks panic when an invariant of the crate is violated, which a fuzzer reports as a crash.
+//!
+//! * parsing a race and printing it gives a race that parses to the same race;
+//! * performing a roll keeps every camel in the race, and the race normalized;
+//! * the chances of a projection sum to one.
+//!
+//! The invariants themselves are public, so that property tests outside the crate can assert them without a fuzzer: see `Race::camel_multiset`, `Race::is_normalized` and `LegChances::sums_to_one`.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::fuzzing::{check_perform, check_projection};
+//! let race = "r,+,y,w".parse::<Race>().expect("to parse");
+//!
+//! check_perform(&race, "r1".parse::<Roll>().expect("to parse"));
+//! check_projection(&race, &"ry".parse::<Dice>().expect("to parse"));
+//! ```
+//!
+//! The fuzz targets live in the `fuzz` directory and run with `cargo fuzz`. This module is only available with the `fuzzing` feature.
+use crate::{
+    camel::{Camel, Dice, Edition, Face, Marker, Race, Roll},
+    oracle::project,
+};
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+use std::collections::HashSet;
+
+const CAMELS: [Camel; 5] = [
+    Camel::Red,
+    Camel::Orange,
+    Camel::Yellow,
+    Camel::Green,
+    Camel::White,
+];
+
+/// The longest arbitrary race, in tiles.
+const LONGEST: usize = 16;
+
+impl<'a> Arbitrary<'a> for Camel {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&CAMELS).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Face {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[Face::One, Face::Two, Face::Three]).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Roll {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Roll::from((Camel::arbitrary(u)?, Face::arbitrary(u)?)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Edition {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[Edition::Classic, Edition::Second]).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Dice {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut dice = HashSet::new();
+        for camel in CAMELS.iter() {
+            if u.arbitrary()? {
+                dice.insert(*camel);
+            }
+        }
+        Ok(Dice::from(dice))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Race {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let length = u.int_in_range(1..=LONGEST)?;
+        let mut camels = CAMELS.to_vec();
+        let mut tiles: Vec<String> = vec![String::new(); length];
+        let mut placed = 0;
+        while !camels.is_empty() {
+            let camel = camels.remove(u.choose_index(camels.len())?);
+            if placed == 0 || u.arbitrary()? {
+                let tile = u.choose_index(length)?;
+                tiles[tile].push_str(&Marker::Camel(camel).to_string());
+                placed += 1;
+            }
+        }
+        for index in 0..length {
+            let free = tiles[index].is_empty() && (index == 0 || !is_a_trap(&tiles[index - 1]));
+            if free && u.ratio(1, 4)? {
+                let trap = u.choose(&[Marker::Oasis, Marker::FataMorgana])?;
+                tiles[index] = trap.to_string();
+            }
+        }
+        let mut notation = tiles.join(",");
+        if u.arbitrary()? {
+            notation.push_str(&Marker::Finish.to_string());
+        }
+        let edition = Edition::arbitrary(u)?;
+        notation
+            .parse::<Race>()
+            .map(|race| race.with_edition(edition))
+            .map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+fn is_a_trap(tile: &str) -> bool {
+    tile == Marker::Oasis.to_string() || tile == Marker::FataMorgana.to_string()
+}
+
+/// Checks that a race that parses, parses the same after it is printed.
+pub fn check_race_parser(input: &str) {
+    if let Ok(race) = input.parse::<Race>() {
+        let printed = race.to_string();
+        assert_eq!(
+            printed.parse::<Race>().as_ref(),
+            Ok(&race),
+            "'{}' printed as '{}'",
+            input,
+            printed
+        );
+    }
+}
+
+/// Checks that performing the roll keeps every camel in the race, and the race normalized.
+pub fn check_perform(race: &Race, roll: Roll) {
+    let after = race.perform(roll);
+
+    assert_eq!(
+        after.camel_multiset(),
+        race.camel_multiset(),
+        "{} became {} after {}",
+        race,
+        after,
+        roll
+    );
+    assert!(
+        after.is_normalized(),
+        "{} is not normalized after {}",
+        after,
+        roll
+    );
+    assert_eq!(after.edition(), race.edition());
+    assert_eq!(after.stacking(), race.stacking());
+}
+
+/// Checks that the chances of the projection sum to one, and only concern camels in the race.
+pub fn check_projection(race: &Race, dice: &Dice) {
+    let chances = project(race, dice);
+    let present = race.camel_multiset();
+
+    assert!(
+        chances.sums_to_one(),
+        "the chances of {} with dice {} do not sum to one",
+        race,
+        dice
+    );
+    assert!(
+        chances
+            .winner
+            .values()
+            .all(|(camel, _)| present.contains_key(camel)),
+        "a camel that is not in {} can win",
+        race
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    fn inputs(seed: u64) -> Vec<u8> {
+        let mut bytes = vec![0; 256];
+        StdRng::seed_from_u64(seed).fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn arbitrary_races_follow_the_rules() {
+        for seed in 0..200 {
+            let bytes = inputs(seed);
+            let mut u = Unstructured::new(&bytes);
+
+            let race = Race::arbitrary(&mut u).expect("a race");
+
+            check_race_parser(&race.to_string());
+            assert!(!race.camel_multiset().is_empty());
+        }
+    }
+
+    #[test]
+    fn rolls_keep_every_camel() {
+        for seed in 0..200 {
+            let bytes = inputs(seed);
+            let mut u = Unstructured::new(&bytes);
+
+            let (race, roll) = <(Race, Roll)>::arbitrary(&mut u).expect("a race and a roll");
+
+            check_perform(&race, roll);
+        }
+    }
+
+    #[test]
+    fn chances_sum_to_one() {
+        for seed in 0..20 {
+            let bytes = inputs(seed);
+            let mut u = Unstructured::new(&bytes);
+
+            let race = Race::arbitrary(&mut u).expect("a race");
+            let dice: HashSet<Camel> = race.ranking().into_iter().take(3).collect();
+
+            check_projection(&race, &Dice::from(dice));
+        }
+    }
+}
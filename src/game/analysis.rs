@@ -0,0 +1,204 @@
+//! Analysis of a recorded game, to find the decisions that cost coins.
+//!
+//! Every entry of a log is a decision of a player. The analysis compares the expected coins of the action the player took with those of the best action, according to `GameState::rank_actions`. The difference is the expected value lost by the decision.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::{analysis::analyze, log::GameLog};
+//! let log = "2 r,,,,y\nticket r5\nticket y5\n".parse::<GameLog>().expect("to parse");
+//!
+//! let analysis = analyze(&log).expect("to replay");
+//!
+//! assert_eq!(analysis.lost(0), Fraction::from(4));
+//! assert_eq!(analysis.lost(1), Fraction::zero());
+//! ```
+use super::log::{replay, GameLog, ReplayError};
+use crate::{advisor::Action, fraction::Fraction};
+use std::fmt::{self, Display, Formatter};
+
+/// A decision of a player, compared with the best decision.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Annotation {
+    /// The entry of the log, counting from 1.
+    pub entry: usize,
+    /// The player that took the decision.
+    pub player: usize,
+    /// The action the player took.
+    pub chosen: Action,
+    /// The expected coins of the action the player took, when the engine values it.
+    ///
+    /// Not every legal action is ranked, e.g. a bet on the overall winner of a race without a finish. Such decisions are unvalued and lose nothing.
+    pub value: Option<Fraction>,
+    /// The best action the player could have taken.
+    pub best: Action,
+    /// The expected coins of the best action.
+    pub best_value: Fraction,
+}
+
+impl Annotation {
+    /// The expected coins lost by not taking the best action, when the action the player took is valued.
+    pub fn lost(&self) -> Option<Fraction> {
+        self.value.map(|value| self.best_value - value)
+    }
+}
+
+/// The annotated decisions of a game.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Analysis {
+    /// The number of players.
+    pub players: usize,
+    /// An annotation for every entry of the log, in order.
+    pub annotations: Vec<Annotation>,
+}
+
+impl Analysis {
+    /// The expected coins the player lost over the whole game.
+    pub fn lost(&self, player: usize) -> Fraction {
+        self.annotations
+            .iter()
+            .filter(|annotation| annotation.player == player)
+            .filter_map(Annotation::lost)
+            .sum()
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for annotation in &self.annotations {
+            write!(
+                f,
+                "{}. player {}: {}",
+                annotation.entry,
+                annotation.player + 1,
+                annotation.chosen
+            )?;
+            match annotation.value {
+                Some(value) => write!(f, " ({:.2})", value.to_f64())?,
+                None => write!(f, " (unvalued)")?,
+            }
+            if annotation.lost() > Some(Fraction::zero()) {
+                write!(
+                    f,
+                    ", best is {} ({:.2}), lost {:.2}",
+                    annotation.best,
+                    annotation.best_value.to_f64(),
+                    annotation.lost().unwrap_or_else(Fraction::zero).to_f64()
+                )?;
+            }
+            writeln!(f)?;
+        }
+        for player in 0..self.players {
+            writeln!(
+                f,
+                "player {} lost {:.2} coins",
+                player + 1,
+                self.lost(player).to_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replays the log, annotating every decision.
+///
+/// The analysis stops at the first entry the engine refuses, like `replay`.
+pub fn analyze(log: &GameLog) -> Result<Analysis, ReplayError> {
+    let mut state = replay(log, 0)?;
+    let mut annotations = Vec::new();
+    for (index, entry) in log.entries.iter().enumerate() {
+        let player = state.current;
+        let ranked = state.rank_actions(player);
+        let chosen = entry.action();
+        let value = ranked
+            .iter()
+            .find(|(action, _)| *action == chosen)
+            .map(|(_, value)| *value);
+        entry.apply_to(&mut state).map_err(|problem| ReplayError {
+            entry: index + 1,
+            problem,
+        })?;
+        let (best, best_value) = ranked[0];
+        annotations.push(Annotation {
+            entry: index + 1,
+            player,
+            chosen,
+            value,
+            best,
+            best_value,
+        });
+    }
+    Ok(Analysis {
+        players: log.players,
+        annotations,
+    })
+}
+
+/// The entries of the log where the player lost more than the threshold, the worst first.
+pub fn blunders(analysis: &Analysis, threshold: Fraction) -> Vec<&Annotation> {
+    let mut blunders: Vec<&Annotation> = analysis
+        .annotations
+        .iter()
+        .filter(|annotation| annotation.lost() > Some(threshold))
+        .collect();
+    blunders.sort_by_key(|annotation| std::cmp::Reverse(annotation.lost()));
+    blunders
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bets::Ticket, camel::Camel};
+
+    #[test]
+    fn the_best_decisions_lose_nothing() {
+        let log = "2 r,,,,y\nticket y5\nticket y3\nticket y2\n"
+            .parse::<GameLog>()
+            .expect("to parse");
+
+        let analysis = analyze(&log).expect("to replay");
+
+        assert_eq!(analysis.annotations.len(), 3);
+        assert_eq!(
+            analysis.annotations[1].chosen,
+            Action::TakeTicket(Ticket::from((Camel::Yellow, 3)))
+        );
+        assert_eq!(analysis.lost(0), Fraction::zero());
+        assert_eq!(analysis.lost(1), Fraction::zero());
+    }
+
+    #[test]
+    fn blunders_are_the_worst_decisions() {
+        let log = "2 r,,,,y\nticket r5\nticket y5\nroll r1\n"
+            .parse::<GameLog>()
+            .expect("to parse");
+
+        let analysis = analyze(&log).expect("to replay");
+        let blunders = blunders(&analysis, Fraction::one());
+
+        assert_eq!(blunders.len(), 2);
+        assert_eq!(blunders[0].entry, 1);
+        assert_eq!(blunders[0].lost(), Some(Fraction::from(4)));
+        assert_eq!(blunders[1].entry, 3);
+        assert_eq!(blunders[1].lost(), Some(Fraction::from(2)));
+    }
+
+    #[test]
+    fn unranked_decisions_are_unvalued() {
+        let log = "2 r,,,,y\nwinner r\nticket y5\n"
+            .parse::<GameLog>()
+            .expect("to parse");
+
+        let analysis = analyze(&log).expect("to replay");
+
+        assert_eq!(
+            analysis.annotations[0].chosen,
+            Action::BetOnWinner(Camel::Red)
+        );
+        assert_eq!(analysis.annotations[0].value, None);
+        assert_eq!(analysis.annotations[0].lost(), None);
+        assert_eq!(analysis.lost(0), Fraction::zero());
+        assert!(analysis
+            .to_string()
+            .starts_with("1. player 1: bet on Red winning the race (unvalued)\n"));
+    }
+}
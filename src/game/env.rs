@@ -0,0 +1,269 @@
+//! The game as an environment to train agents with reinforcement learning.
+//!
+//! An `Environment` seats an agent among opponents that follow a `Strategy`. The agent takes its turns with `step`, after which the opponents take theirs until it is the turn of the agent again. The reward of a step is the change in the coins of the agent.
+//!
+//! ```
+//! # use camel_up::camel::Edition;
+//! # use camel_up::game::env::Environment;
+//! # use camel_up::game::strategy::{AlwaysRoll, Strategy};
+//! let opponents: Vec<Box<dyn Strategy>> = vec![Box::new(AlwaysRoll)];
+//! let mut environment = Environment::new(0, opponents, Edition::Classic, 42).expect("to set up");
+//!
+//! let mut observation = environment.reset();
+//! let mut done = false;
+//! while !done {
+//!     let action = environment.legal_actions()[0];
+//!     let (next, _reward, over) = environment.step(action).expect("to be legal");
+//!     observation = next;
+//!     done = over;
+//! }
+//! assert_eq!(observation.len(), environment.observation_size());
+//! ```
+//!
+//! This module is only available with the `env` feature.
+use super::{
+    setup::{setup, SetupError, TRACK_LENGTH},
+    strategy::Strategy,
+    GameState, IllegalAction,
+};
+use crate::{
+    advisor::Action,
+    camel::{Edition, Marker, CAMELS},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The number of values the observation holds for every camel.
+const CAMEL_FEATURES: usize = 4;
+/// The number of values the observation holds for every player.
+const PLAYER_FEATURES: usize = 3;
+
+/// A game against opponents, seen from the seat of the agent.
+pub struct Environment {
+    agent: usize,
+    opponents: Vec<Box<dyn Strategy>>,
+    edition: Edition,
+    rng: StdRng,
+    state: GameState,
+}
+
+impl Environment {
+    /// An environment with the agent in the given seat and an opponent in every other seat, in seating order.
+    ///
+    /// The seed determines the setups and the dice, so that episodes can be reproduced. Editions whose games can not be set up are refused, see `setup`.
+    pub fn new(
+        agent: usize,
+        opponents: Vec<Box<dyn Strategy>>,
+        edition: Edition,
+        seed: u64,
+    ) -> Result<Self, SetupError> {
+        let players = opponents.len() + 1;
+        assert!(agent < players, "the agent should have a seat");
+        let mut rng = StdRng::seed_from_u64(seed);
+        let state = setup(players, edition, &mut rng)?;
+        Ok(Self {
+            agent,
+            opponents,
+            edition,
+            rng,
+            state,
+        })
+    }
+
+    /// Starts a new game, returning the first observation of the agent.
+    pub fn reset(&mut self) -> Vec<f32> {
+        self.state = setup(self.opponents.len() + 1, self.edition, &mut self.rng)
+            .expect("the edition to be set up like it was in `new`");
+        self.play_opponents();
+        self.observe()
+    }
+
+    /// The agent takes its turn with the action, then the opponents take theirs.
+    ///
+    /// Returns the observation of the agent, its reward and whether the game is over. Illegal actions are refused and leave the game as it was.
+    pub fn step(&mut self, action: Action) -> Result<(Vec<f32>, f64, bool), IllegalAction> {
+        if self.state.is_over() {
+            return Err(IllegalAction::GameOver);
+        }
+        let before = self.state.players[self.agent].coins;
+        self.state.apply(action, &mut self.rng)?;
+        self.play_opponents();
+        let reward = (self.state.players[self.agent].coins - before) as f64;
+        Ok((self.observe(), reward, self.state.is_over()))
+    }
+
+    /// The actions the agent can take.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        self.state.legal_actions()
+    }
+
+    /// The complete state of the game.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// The number of values in an observation.
+    pub fn observation_size(&self) -> usize {
+        observation_size(self.state.players.len())
+    }
+
+    fn play_opponents(&mut self) {
+        while !self.state.is_over() && self.state.current != self.agent {
+            let player = self.state.current;
+            let seat = if player < self.agent {
+                player
+            } else {
+                player - 1
+            };
+            let action = self.opponents[seat].choose(&self.state, player);
+            self.state
+                .apply(action, &mut self.rng)
+                .expect("strategies to choose legal actions");
+        }
+    }
+
+    fn observe(&self) -> Vec<f32> {
+        encode(&self.state, self.agent)
+    }
+}
+
+/// The number of values in the observation of a game with the number of players.
+pub fn observation_size(players: usize) -> usize {
+    CAMELS.len() * CAMEL_FEATURES + players * PLAYER_FEATURES + CAMELS.len() + TRACK_LENGTH
+}
+
+/// Encodes the state as seen by the player into a fixed number of values, see `observation_size`.
+///
+/// In order, the observation holds
+///
+/// 1. for every camel: its distance to the finish, its height in its stack, whether its die is in the pyramid and the value of its top leg betting ticket, or 0 when there is none,
+/// 2. for every player, starting with the player and continuing in seating order: their coins, their leg betting tickets and their pyramid tickets,
+/// 3. for every camel: whether the player still holds its card for a bet on the outcome of the race,
+/// 4. for every tile, counting back from the finish: 1 for an oasis, -1 for a fata morgana and 0 otherwise.
+///
+/// Camels follow the order of their dice. Distances are counted in tiles, with 0 for a camel across the finish.
+pub fn encode(state: &GameState, player: usize) -> Vec<f32> {
+    let players = state.players.len();
+    let mut observation = Vec::with_capacity(observation_size(players));
+    let positions = state.race.positions.to_vec();
+    let finish = positions
+        .iter()
+        .position(|marker| *marker == Marker::Finish)
+        .unwrap_or(positions.len());
+    let distance = |index: usize| {
+        if index > finish {
+            return 0;
+        }
+        positions[index..finish]
+            .iter()
+            .filter(|marker| marker.is_a_divider())
+            .count()
+            + 1
+    };
+    let tickets = state.available_tickets();
+    for camel in CAMELS.iter() {
+        let index = positions
+            .iter()
+            .position(|marker| *marker == Marker::Camel(*camel));
+        let (distance, height) = match index {
+            Some(index) => {
+                let height = positions[0..index]
+                    .iter()
+                    .rev()
+                    .take_while(|marker| marker.to_camel().is_some())
+                    .count();
+                (distance(index), height)
+            }
+            None => (0, 0),
+        };
+        observation.push(distance as f32);
+        observation.push(height as f32);
+        observation.push(if state.pyramid.contains(*camel) {
+            1.0
+        } else {
+            0.0
+        });
+        observation.push(
+            tickets
+                .iter()
+                .find(|ticket| ticket.camel == *camel)
+                .map_or(0.0, |ticket| ticket.value as f32),
+        );
+    }
+    for seat in 0..players {
+        let holdings = &state.players[(player + seat) % players];
+        observation.push(holdings.coins as f32);
+        observation.push(holdings.tickets.len() as f32);
+        observation.push(holdings.pyramid_tickets as f32);
+    }
+    for camel in CAMELS.iter() {
+        observation.push(if state.players[player].cards.contains(camel) {
+            1.0
+        } else {
+            0.0
+        });
+    }
+    let mut tiles = vec![0.0; TRACK_LENGTH];
+    for (index, marker) in positions.iter().enumerate() {
+        let value = match marker {
+            Marker::Oasis => 1.0,
+            Marker::FataMorgana => -1.0,
+            _ => continue,
+        };
+        if let Some(tile) = distance(index)
+            .checked_sub(1)
+            .and_then(|tile| tiles.get_mut(tile))
+        {
+            *tile = value;
+        }
+    }
+    observation.extend(tiles);
+    observation
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        camel::Race,
+        game::{strategy::AlwaysRoll, START_COINS},
+    };
+
+    #[test]
+    fn observations_describe_the_race_from_the_finish() {
+        let race = "r,yg,+,,!".parse::<Race>().expect("to parse");
+        let state = GameState::new(race, 2);
+
+        let observation = encode(&state, 1);
+
+        assert_eq!(observation.len(), observation_size(2));
+        assert_eq!(&observation[0..4], &[5.0, 0.0, 1.0, 5.0]);
+        assert_eq!(&observation[12..16], &[4.0, 1.0, 1.0, 5.0]);
+        assert_eq!(observation[observation.len() - TRACK_LENGTH + 2], 1.0);
+    }
+
+    #[test]
+    fn rewards_are_the_change_in_coins() {
+        let opponents: Vec<Box<dyn Strategy>> = vec![Box::new(AlwaysRoll), Box::new(AlwaysRoll)];
+        let mut environment =
+            Environment::new(1, opponents, Edition::Classic, 7).expect("to set up");
+        environment.reset();
+        let mut total = 0.0;
+        let mut done = false;
+
+        while !done {
+            let action = match environment.state().available_tickets().first() {
+                Some(ticket) => Action::TakeTicket(*ticket),
+                None => Action::Roll,
+            };
+            let (_, reward, over) = environment.step(action).expect("to be legal");
+            total += reward;
+            done = over;
+        }
+
+        assert_eq!(
+            total,
+            (environment.state().players[1].coins - START_COINS) as f64
+        );
+        assert!(environment.step(Action::Roll).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! Undo and redo for a game in progress.
+//!
+//! When a roll is entered wrongly, the game should not have to be rebuilt from the start. A `History` remembers the states before each turn, so that turns can be undone and redone.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::{history::History, GameState};
+//! let race = "r,y,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+//! let mut history = History::new(GameState::new(race, 2));
+//!
+//! history.apply_roll((Camel::Red, Face::Three)).expect("to be legal");
+//! history.undo();
+//! history.apply_roll((Camel::Red, Face::Two)).expect("to be legal");
+//!
+//! assert_eq!(history.state().race.to_string(), "y,r,,,,,,,,,,,,,!");
+//! assert!(!history.redo());
+//! ```
+use super::{GameState, IllegalAction};
+use crate::{advisor::Action, camel::Roll};
+use rand::Rng;
+use std::mem;
+
+/// A game state, together with the states before it and the states that were undone.
+#[derive(Clone, Debug)]
+pub struct History {
+    current: GameState,
+    done: Vec<GameState>,
+    undone: Vec<GameState>,
+}
+
+impl History {
+    /// Starts the history of a game with the given state.
+    pub fn new(state: GameState) -> Self {
+        Self {
+            current: state,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// The current state of the game.
+    pub fn state(&self) -> &GameState {
+        &self.current
+    }
+
+    /// Applies the action, see `GameState::apply`. Turns that were undone can no longer be redone.
+    pub fn apply<R>(&mut self, action: Action, rng: &mut R) -> Result<(), IllegalAction>
+    where
+        R: Rng + ?Sized,
+    {
+        self.record(|state| state.apply(action, rng))
+    }
+
+    /// Applies the roll, see `GameState::apply_roll`. Turns that were undone can no longer be redone.
+    pub fn apply_roll<R>(&mut self, roll: R) -> Result<(), IllegalAction>
+    where
+        R: Into<Roll>,
+    {
+        self.record(|state| state.apply_roll(roll))
+    }
+
+    fn record<F>(&mut self, turn: F) -> Result<(), IllegalAction>
+    where
+        F: FnOnce(&mut GameState) -> Result<(), IllegalAction>,
+    {
+        let before = self.current.clone();
+        turn(&mut self.current)?;
+        self.done.push(before);
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Undoes the last turn, returning whether there was a turn to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.done.pop() {
+            Some(state) => {
+                self.undone.push(mem::replace(&mut self.current, state));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redoes the last undone turn, returning whether there was a turn to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(state) => {
+                self.done.push(mem::replace(&mut self.current, state));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bets::Ticket,
+        camel::{Camel, Face, Race},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn undone_turns_can_be_redone() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+        let start = GameState::new(race, 2);
+        let mut history = History::new(start.clone());
+        let mut rng = StdRng::seed_from_u64(7);
+
+        history
+            .apply(Action::TakeTicket(Ticket::from((Camel::Red, 5))), &mut rng)
+            .expect("to be legal");
+        history
+            .apply_roll((Camel::Yellow, Face::One))
+            .expect("to be legal");
+        let end = history.state().clone();
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(!history.undo());
+        assert_eq!(history.state(), &start);
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.state(), &end);
+    }
+
+    #[test]
+    fn refused_turns_are_not_recorded() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+        let mut history = History::new(GameState::new(race, 2));
+
+        history
+            .apply_roll((Camel::Red, Face::One))
+            .expect("to be legal");
+        assert!(history.apply_roll((Camel::Red, Face::Two)).is_err());
+
+        assert!(history.undo());
+        assert!(!history.undo());
+    }
+}
@@ -0,0 +1,252 @@
+//! Beliefs about the hidden bets on the overall winner and loser.
+//!
+//! Bets on the outcome of the race are placed face down. Which camel a card is for only shows at the end of the game, but the moment a player places it tells something. A player is more likely to bet on a camel that has a good chance. `Beliefs` keep a posterior over the camel of every hidden card, and turn it into the expected payout of a bet of your own.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::advisor::OVERALL_REWARD;
+//! # use camel_up::game::inference::{Beliefs, Bet};
+//! # use camel_up::oracle::sample_race;
+//! # use rand::{rngs::StdRng, SeedableRng};
+//! let race = "r,,,,y!".parse::<Race>().expect("to parse");
+//! let dice = "ry".parse::<Dice>().expect("to parse");
+//! let chances = sample_race(&race, &dice, 100, &mut StdRng::seed_from_u64(0));
+//!
+//! let mut beliefs = Beliefs::new();
+//! beliefs.observe(1, Bet::Winner, &chances);
+//!
+//! let rewards = beliefs.rewards(&[Camel::Red, Camel::Yellow]);
+//! assert!(rewards.winner[&Camel::Yellow] < Fraction::from(OVERALL_REWARD));
+//! assert!(rewards.winner[&Camel::Yellow] < rewards.winner[&Camel::Red]);
+//! ```
+//!
+//! The posterior assumes players bet on a camel in proportion to its chance at the time, with some room for bluffs and mistakes. A player holds a single card for every camel, so their cards are all for different camels.
+use crate::{
+    advisor::Rewards,
+    camel::Camel,
+    fraction::Fraction,
+    oracle::{Distribution, RaceChances},
+    rules::RuleSet,
+};
+use std::collections::HashMap;
+
+/// The part of the likelihood that does not depend on the chances, so that unlikely camels are not ruled out.
+const BLUFF: f64 = 0.1;
+/// The precision of the expected payouts, a hundredth of a coin.
+const PRECISION: u64 = 100;
+
+/// The kind of bet on the outcome of the race.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Bet {
+    /// A bet on the overall winner.
+    Winner,
+    /// A bet on the overall loser.
+    Loser,
+}
+
+#[derive(Clone, Debug)]
+struct Card {
+    player: usize,
+    bet: Bet,
+    likelihood: HashMap<Camel, f64>,
+}
+
+/// The bets placed so far, with what is believed about their camels.
+#[derive(Clone, Default, Debug)]
+pub struct Beliefs {
+    cards: Vec<Card>,
+}
+
+impl Beliefs {
+    /// Beliefs before any bet is placed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a hidden bet of the player, placed when the race had the given chances.
+    pub fn observe(&mut self, player: usize, bet: Bet, race: &RaceChances) {
+        let distribution: &Distribution = match bet {
+            Bet::Winner => &race.winner,
+            Bet::Loser => &race.loser,
+        };
+        let mut camels: Vec<Camel> = race
+            .places
+            .iter()
+            .flat_map(Distribution::values)
+            .map(|(camel, _)| *camel)
+            .collect();
+        camels.sort();
+        camels.dedup();
+        let uniform = BLUFF / camels.len().max(1) as f64;
+        let likelihood = camels
+            .into_iter()
+            .map(|camel| {
+                (
+                    camel,
+                    uniform + (1.0 - BLUFF) * distribution[&camel].to_f64(),
+                )
+            })
+            .collect();
+        self.cards.push(Card {
+            player,
+            bet,
+            likelihood,
+        });
+    }
+
+    /// Records a bet of the player of which the camel is known, e.g. a bet of your own.
+    pub fn reveal(&mut self, player: usize, bet: Bet, camel: Camel) {
+        self.cards.push(Card {
+            player,
+            bet,
+            likelihood: vec![(camel, 1.0)].into_iter().collect(),
+        });
+    }
+
+    /// The chance that each camel is on the card, for every bet in the order they were placed.
+    pub fn posterior(&self) -> Vec<HashMap<Camel, f64>> {
+        let mut posterior: Vec<HashMap<Camel, f64>> = vec![HashMap::new(); self.cards.len()];
+        let mut players: Vec<usize> = self.cards.iter().map(|card| card.player).collect();
+        players.sort();
+        players.dedup();
+        for player in players {
+            let indices: Vec<usize> = (0..self.cards.len())
+                .filter(|index| self.cards[*index].player == player)
+                .collect();
+            let mut weights: Vec<HashMap<Camel, f64>> = vec![HashMap::new(); indices.len()];
+            let mut assignment = Vec::new();
+            let total = self.enumerate(&indices, &mut assignment, 1.0, &mut weights);
+            for (index, weights) in indices.iter().zip(weights) {
+                posterior[*index] = weights
+                    .into_iter()
+                    .map(|(camel, weight)| (camel, if total > 0.0 { weight / total } else { 0.0 }))
+                    .collect();
+            }
+        }
+        posterior
+    }
+
+    /// Visits every way to assign different camels to the cards, accumulating the weight of each camel on each card.
+    fn enumerate(
+        &self,
+        indices: &[usize],
+        assignment: &mut Vec<Camel>,
+        weight: f64,
+        weights: &mut [HashMap<Camel, f64>],
+    ) -> f64 {
+        if assignment.len() == indices.len() {
+            for (card, camel) in assignment.iter().enumerate() {
+                *weights[card].entry(*camel).or_insert(0.0) += weight;
+            }
+            return weight;
+        }
+        let card = &self.cards[indices[assignment.len()]];
+        let mut total = 0.0;
+        for (camel, likelihood) in &card.likelihood {
+            if assignment.contains(camel) || *likelihood == 0.0 {
+                continue;
+            }
+            assignment.push(*camel);
+            total += self.enumerate(indices, assignment, weight * likelihood, weights);
+            assignment.pop();
+        }
+        total
+    }
+
+    /// The expected payout of a new correct bet on each of the camels, given the bets placed before it.
+    ///
+    /// The payouts are rounded to a hundredth of a coin.
+    pub fn rewards(&self, camels: &[Camel]) -> Rewards {
+        self.rewards_with(camels, &RuleSet::default())
+    }
+
+    /// The expected payouts like `rewards`, following the payouts and the penalty of the rules.
+    pub fn rewards_with(&self, camels: &[Camel], rules: &RuleSet) -> Rewards {
+        let posterior = self.posterior();
+        let mut rewards = Rewards::from(rules);
+        for camel in camels {
+            rewards
+                .winner
+                .insert(*camel, self.reward(&posterior, Bet::Winner, *camel, rules));
+            rewards
+                .loser
+                .insert(*camel, self.reward(&posterior, Bet::Loser, *camel, rules));
+        }
+        rewards
+    }
+
+    fn reward(
+        &self,
+        posterior: &[HashMap<Camel, f64>],
+        bet: Bet,
+        camel: Camel,
+        rules: &RuleSet,
+    ) -> Fraction {
+        let mut chances: HashMap<usize, f64> = HashMap::new();
+        for (card, beliefs) in self.cards.iter().zip(posterior) {
+            if card.bet == bet {
+                *chances.entry(card.player).or_insert(0.0) += beliefs.get(&camel).unwrap_or(&0.0);
+            }
+        }
+        // the number of earlier bets on the camel follows from one bet at most for every player
+        let mut earlier = vec![1.0];
+        for chance in chances.values() {
+            let mut next = vec![0.0; earlier.len() + 1];
+            for (count, probability) in earlier.iter().enumerate() {
+                next[count] += probability * (1.0 - chance);
+                next[count + 1] += probability * chance;
+            }
+            earlier = next;
+        }
+        let expected: f64 = earlier
+            .iter()
+            .enumerate()
+            .map(|(count, probability)| probability * rules.overall_payout(count) as f64)
+            .sum();
+        Fraction::new((expected * PRECISION as f64).round() as i64, PRECISION)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        advisor::OVERALL_REWARD,
+        camel::{Dice, Race},
+        oracle::sample_race,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn known_bets_lower_the_payout() {
+        let mut beliefs = Beliefs::new();
+        beliefs.reveal(0, Bet::Winner, Camel::Red);
+        beliefs.reveal(1, Bet::Winner, Camel::Red);
+
+        let rewards = beliefs.rewards(&[Camel::Red, Camel::Yellow]);
+
+        assert_eq!(rewards.winner[&Camel::Red], Fraction::from(3));
+        assert_eq!(
+            rewards.winner[&Camel::Yellow],
+            Fraction::from(OVERALL_REWARD)
+        );
+        assert_eq!(rewards.loser[&Camel::Red], Fraction::from(OVERALL_REWARD));
+    }
+
+    #[test]
+    fn a_player_has_a_single_card_for_every_camel() {
+        let race = "r,,,,y!".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let chances = sample_race(&race, &dice, 100, &mut StdRng::seed_from_u64(0));
+        let mut beliefs = Beliefs::new();
+        beliefs.reveal(0, Bet::Loser, Camel::Yellow);
+        beliefs.observe(0, Bet::Winner, &chances);
+
+        let posterior = beliefs.posterior();
+
+        assert!(posterior[1]
+            .get(&Camel::Yellow)
+            .is_none_or(|chance| *chance == 0.0));
+        assert_eq!(posterior[1][&Camel::Red], 1.0);
+    }
+}
@@ -0,0 +1,322 @@
+//! A textual record of a game, to replay it afterwards.
+//!
+//! A log starts with a setup line, holding the number of players and the race at the start of the game. Every following line is the turn of the next player:
+//!
+//! | Entry       | Turn                                                      |
+//! |-------------|-----------------------------------------------------------|
+//! | `roll r2`   | take a pyramid ticket, the red die shows a two            |
+//! | `ticket y5` | take the yellow leg betting ticket worth five             |
+//! | `winner g`  | bet on green winning the race                             |
+//! | `loser w`   | bet on white losing the race                              |
+//! | `trap +4`   | place an oasis, or a fata morgana with `-`, on position 4 |
+//! | `partner 2` | form a partnership with player 2                          |
+//!
+//! Blank lines and lines starting with `#` are skipped.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::log::{replay, GameLog};
+//! let log = "2 r,y,,,,,,,,,,,,,,!\nticket y5\nroll r1\n".parse::<GameLog>().expect("to parse");
+//!
+//! let state = replay(&log, 1).expect("to replay");
+//! assert_eq!(state.available_tickets()[1].value, 3);
+//!
+//! let state = replay(&log, log.entries.len()).expect("to replay");
+//! assert_eq!(state.race.to_string(), "yr,,,,,,,,,,,,,,!");
+//! ```
+use super::{GameState, IllegalAction};
+use crate::{
+    advisor::Action,
+    bets::Ticket,
+    camel::{Camel, Marker, Race, RaceParseError, Roll},
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A single turn in the log.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Entry {
+    /// A pyramid ticket was taken and the die showed the roll.
+    Roll(Roll),
+    /// A leg betting ticket was taken.
+    Ticket(Ticket),
+    /// A bet on the camel winning the race.
+    Winner(Camel),
+    /// A bet on the camel losing the race.
+    Loser(Camel),
+    /// A spectator tile was placed on the position.
+    Trap(usize, Marker),
+    /// A partnership was formed with the player, counted from 0.
+    Partner(usize),
+}
+
+impl Entry {
+    /// The action the player took, a roll is taking a pyramid ticket.
+    pub fn action(&self) -> Action {
+        match *self {
+            Entry::Roll(_) => Action::Roll,
+            Entry::Ticket(ticket) => Action::TakeTicket(ticket),
+            Entry::Winner(camel) => Action::BetOnWinner(camel),
+            Entry::Loser(camel) => Action::BetOnLoser(camel),
+            Entry::Trap(position, trap) => Action::PlaceTrap(position, trap),
+            Entry::Partner(player) => Action::Partner(player),
+        }
+    }
+
+    /// Applies the entry to the state, see `GameState::apply_roll` and `GameState::apply`.
+    pub(crate) fn apply_to(&self, state: &mut GameState) -> Result<(), IllegalAction> {
+        match *self {
+            Entry::Roll(roll) => state.apply_roll(roll),
+            _ => state.decide(self.action()),
+        }
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Entry::Roll(roll) => write!(f, "roll {}", roll),
+            Entry::Ticket(ticket) => {
+                write!(f, "ticket {}{}", Marker::Camel(ticket.camel), ticket.value)
+            }
+            Entry::Winner(camel) => write!(f, "winner {}", Marker::Camel(*camel)),
+            Entry::Loser(camel) => write!(f, "loser {}", Marker::Camel(*camel)),
+            Entry::Trap(position, trap) => write!(f, "trap {}{}", trap, position),
+            Entry::Partner(player) => write!(f, "partner {}", player + 1),
+        }
+    }
+}
+
+impl FromStr for Entry {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let (keyword, argument) = match words.as_slice() {
+            [keyword, argument] => (*keyword, *argument),
+            _ => return Err(()),
+        };
+        let camel = || {
+            argument
+                .parse::<Marker>()
+                .ok()
+                .and_then(|marker| marker.to_camel())
+                .ok_or(())
+        };
+        match keyword {
+            "roll" => argument.parse::<Roll>().map(Entry::Roll).map_err(|_| ()),
+            "ticket" => argument
+                .parse::<Ticket>()
+                .map(Entry::Ticket)
+                .map_err(|_| ()),
+            "winner" => camel().map(Entry::Winner),
+            "loser" => camel().map(Entry::Loser),
+            "trap" => {
+                let trap = argument
+                    .get(0..1)
+                    .and_then(|symbol| symbol.parse::<Marker>().ok())
+                    .filter(|marker| *marker == Marker::Oasis || *marker == Marker::FataMorgana)
+                    .ok_or(())?;
+                let position = argument[1..].parse::<usize>().map_err(|_| ())?;
+                Ok(Entry::Trap(position, trap))
+            }
+            "partner" => argument
+                .parse::<usize>()
+                .ok()
+                .and_then(|player| player.checked_sub(1))
+                .map(Entry::Partner)
+                .ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A recorded game, the setup and every turn that was taken.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameLog {
+    /// The number of players.
+    pub players: usize,
+    /// The race at the start of the game.
+    pub race: Race,
+    /// The turns, in the order they were taken.
+    pub entries: Vec<Entry>,
+}
+
+impl Display for GameLog {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{} {}", self.players, self.race)?;
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameLog {
+    type Err = LogParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (number, setup) = lines.next().ok_or(LogParseError::MissingSetup)?;
+        let words: Vec<&str> = setup.split_whitespace().collect();
+        let (players, race) = match words.as_slice() {
+            [players, race] => (players, race),
+            _ => return Err(LogParseError::NotASetup(number)),
+        };
+        let players = players
+            .parse::<usize>()
+            .ok()
+            .filter(|players| *players > 0)
+            .ok_or(LogParseError::NotASetup(number))?;
+        let race = race
+            .parse::<Race>()
+            .map_err(|problem| LogParseError::Race(number, problem))?;
+        let entries = lines
+            .map(|(number, line)| {
+                line.parse::<Entry>()
+                    .map_err(|_| LogParseError::NotAnEntry(number, line.to_owned()))
+            })
+            .collect::<Result<Vec<Entry>, LogParseError>>()?;
+        Ok(Self {
+            players,
+            race,
+            entries,
+        })
+    }
+}
+
+/// When parsing of a GameLog goes wrong, this enumeration tells you precisely what went down.
+#[derive(PartialEq, Debug)]
+pub enum LogParseError {
+    /// There is no setup line.
+    MissingSetup,
+    /// The setup line on the given line is not the number of players followed by a race.
+    NotASetup(usize),
+    /// The race of the setup on the given line does not parse.
+    Race(usize, RaceParseError),
+    /// The line is not one of the entries.
+    NotAnEntry(usize, String),
+}
+
+impl LogParseError {
+    /// The line the problem is on, counting from 1.
+    pub fn line(&self) -> usize {
+        match self {
+            LogParseError::MissingSetup => 1,
+            LogParseError::NotASetup(line) => *line,
+            LogParseError::Race(line, _) => *line,
+            LogParseError::NotAnEntry(line, _) => *line,
+        }
+    }
+}
+
+impl Display for LogParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LogParseError::MissingSetup => write!(f, "the log is empty"),
+            LogParseError::NotASetup(line) => write!(
+                f,
+                "line {}: expected the number of players followed by a race",
+                line
+            ),
+            LogParseError::Race(line, problem) => write!(f, "line {}: {}", line, problem),
+            LogParseError::NotAnEntry(line, entry) => {
+                write!(f, "line {}: '{}' is not an entry", line, entry)
+            }
+        }
+    }
+}
+
+impl Error for LogParseError {}
+
+/// Reconstructs the state of the game after the given number of entries.
+///
+/// Replaying stops at the first entry the engine refuses, reporting which entry it was.
+pub fn replay(log: &GameLog, entries: usize) -> Result<GameState, ReplayError> {
+    let mut state = GameState::new(log.race, log.players);
+    for (index, entry) in log.entries.iter().take(entries).enumerate() {
+        entry.apply_to(&mut state).map_err(|problem| ReplayError {
+            entry: index + 1,
+            problem,
+        })?;
+    }
+    Ok(state)
+}
+
+/// An entry of the log that could not be replayed.
+#[derive(PartialEq, Debug)]
+pub struct ReplayError {
+    /// The entry that was refused, counting from 1.
+    pub entry: usize,
+    /// Why the engine refused it.
+    pub problem: IllegalAction,
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "entry {}: {}", self.entry, self.problem)
+    }
+}
+
+impl Error for ReplayError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::camel::Face;
+
+    #[test]
+    fn logs_can_be_written_and_read_back() {
+        let log = GameLog {
+            players: 3,
+            race: "r,y,,,!".parse::<Race>().expect("to parse"),
+            entries: vec![
+                Entry::Roll(Roll::from((Camel::Red, Face::Two))),
+                Entry::Ticket(Ticket::from((Camel::Yellow, 5))),
+                Entry::Loser(Camel::Red),
+                Entry::Trap(3, Marker::FataMorgana),
+                Entry::Partner(0),
+            ],
+        };
+
+        let text = log.to_string();
+
+        assert_eq!(
+            text,
+            "3 r,y,,,!\nroll r2\nticket y5\nloser r\ntrap -3\npartner 1\n"
+        );
+        assert_eq!(text.parse::<GameLog>(), Ok(log));
+    }
+
+    #[test]
+    fn parse_errors_carry_their_line() {
+        let problem = "# a game\n2 r,y\n\nroll r4\n".parse::<GameLog>();
+
+        assert_eq!(
+            problem,
+            Err(LogParseError::NotAnEntry(4, "roll r4".to_owned()))
+        );
+    }
+
+    #[test]
+    fn replaying_stops_at_an_illegal_entry() {
+        let log = "2 r,y\nroll r1\nroll r2\n"
+            .parse::<GameLog>()
+            .expect("to parse");
+
+        assert_eq!(
+            replay(&log, 2),
+            Err(ReplayError {
+                entry: 2,
+                problem: IllegalAction::DieNotInPyramid(Camel::Red)
+            })
+        );
+    }
+}
@@ -0,0 +1,1545 @@
+//! The complete state of a game of Camel Up.
+//!
+//! The oracle tells which camel is likely to come out ahead, but whether an action pays off depends on more than the race. It depends on the tickets that are left, the bets that are placed and the coins of the players. A `GameState` keeps track of all of it.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! let race = "r,y,,g,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+//! let state = GameState::new(race, 4);
+//!
+//! assert_eq!(state.players.len(), 4);
+//! assert_eq!(state.pyramid.len(), 3);
+//! assert_eq!(state.available_tickets().len(), 3);
+//! ```
+//!
+//! Players take their turn by applying an action to the state. Rolling draws a die from the pyramid, and when the last die is drawn the leg ends: leg betting tickets and pyramid tickets are paid out and a new leg starts.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::advisor::Action;
+//! # use rand::{rngs::StdRng, SeedableRng};
+//! let race = "r,,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+//! let mut state = GameState::new(race, 2);
+//! let mut rng = StdRng::seed_from_u64(42);
+//!
+//! state.apply(Action::TakeTicket(Ticket::from((Camel::Red, 5))), &mut rng).expect("to be legal");
+//! state.apply(Action::Roll, &mut rng).expect("to be legal");
+//!
+//! assert_eq!(state.players[0].coins, 3 + 5);
+//! assert_eq!(state.players[1].coins, 3 + 1);
+//! ```
+//!
+//! The second edition lets a player form a partnership with a player that has no partner yet. At the end of the leg, each partner also receives the winnings of the leg betting tickets of the other, their losses are not shared. Partnerships last a single leg.
+pub mod analysis;
+#[cfg(feature = "env")]
+pub mod env;
+pub mod history;
+pub mod inference;
+pub mod log;
+pub mod observer;
+pub mod setup;
+pub mod strategy;
+
+use crate::{
+    advisor::{
+        next_ticket, partnership, rank_with_rewards, Action, Rewards, OVERALL_REWARD, ROLL_REWARD,
+    },
+    bets::Ticket,
+    camel::{Camel, Dice, Edition, Face, Marker, Race, RaceParseError, Roll},
+    fraction::Fraction,
+    oracle::{expected_arrivals, expected_landings, project, sample_race, LegChances, RaceChances},
+    rules::RuleSet,
+};
+use inference::{Beliefs, Bet};
+use observer::{Observer, Unobserved};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+/// The values of the leg betting tickets of a camel, from the bottom of the stack to the top.
+pub(crate) const LEG_TICKETS: [u8; 3] = [2, 3, 5];
+
+/// The values of the leg betting tickets of a camel in the second edition, from the bottom of the stack to the top.
+pub(crate) const SECOND_EDITION_LEG_TICKETS: [u8; 4] = [2, 2, 3, 5];
+
+/// The stack of leg betting tickets of every camel at the start of a leg, see `RuleSet::leg_tickets`.
+pub(crate) fn leg_tickets(edition: Edition) -> &'static [u8] {
+    match edition {
+        Edition::Classic => &LEG_TICKETS,
+        Edition::Second => &SECOND_EDITION_LEG_TICKETS,
+    }
+}
+
+/// The payouts for correct bets on the overall winner or loser, in the order the bets were placed, see `RuleSet::overall_payouts`. Later correct bets are paid the last payout.
+pub(crate) const OVERALL_PAYOUTS: [i64; 5] = [OVERALL_REWARD, 5, 3, 2, 1];
+
+/// The coins each player starts the game with.
+pub const START_COINS: i64 = 3;
+
+/// The number of complete races `GameState::rank_actions` samples to estimate the outcome of the race.
+pub const RACE_SAMPLES: usize = 1000;
+
+/// A player and their possessions.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Player {
+    /// The coins of the player.
+    pub coins: i64,
+    /// The leg betting tickets taken during this leg.
+    pub tickets: Vec<Ticket>,
+    /// The number of pyramid tickets taken during this leg, each one is worth a coin at the end of the leg.
+    pub pyramid_tickets: usize,
+    /// The overall bet cards still in hand, one for each camel.
+    pub cards: Vec<Camel>,
+    /// The player this player formed a partnership with during this leg.
+    pub partner: Option<usize>,
+    /// The number of times a camel landed on the spectator tile or the photographer of the player during this leg, each landing paid a coin.
+    pub landings: usize,
+}
+
+impl Player {
+    fn new(cards: Vec<Camel>, coins: i64) -> Self {
+        Self {
+            coins,
+            tickets: Vec::new(),
+            pyramid_tickets: 0,
+            cards,
+            partner: None,
+            landings: 0,
+        }
+    }
+}
+
+/// A bet on the overall winner or loser of the race.
+///
+/// The card is face down, so other players do not know which camel it is for.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct OverallBet {
+    /// The player that placed the bet.
+    pub player: usize,
+    /// The camel the bet is on.
+    pub camel: Camel,
+}
+
+/// The betting photographer of the Supercup expansion, on the track during this leg.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Photographer {
+    /// The player that placed the photographer.
+    pub owner: usize,
+    /// The tile of the photographer, counted back from the finish. The tile right before the finish is at distance 1.
+    pub distance: usize,
+}
+
+/// A coin a player earns during a roll, because the camel landed on a tile of the player.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Payout {
+    /// The camel landed on a spectator tile of the player.
+    Spectator(usize),
+    /// The camel landed on the tile of the betting photographer of the player.
+    Photographer(usize),
+}
+
+impl Payout {
+    /// The player that earns the coin.
+    pub fn player(&self) -> usize {
+        match self {
+            Payout::Spectator(player) | Payout::Photographer(player) => *player,
+        }
+    }
+
+    /// The number of coins the player earns.
+    pub fn coins(&self) -> i64 {
+        1
+    }
+}
+
+/// Everything there is to know about a game in progress.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameState {
+    /// The positions of the camels and the spectator tiles.
+    pub race: Race,
+    /// The dice that are still in the pyramid during this leg.
+    pub pyramid: Dice,
+    /// The owner of each spectator tile in the race, in the order the tiles appear from the rear of the race.
+    pub spectator_tiles: Vec<usize>,
+    /// The players, in the order they take their turn.
+    pub players: Vec<Player>,
+    /// The player whose turn it is.
+    pub current: usize,
+    /// The stacks of leg betting tickets that are left for each camel, the top of a stack is last.
+    pub leg_tickets: HashMap<Camel, Vec<u8>>,
+    /// The bets on the overall winner, in the order they were placed.
+    pub winner_bets: Vec<OverallBet>,
+    /// The bets on the overall loser, in the order they were placed.
+    pub loser_bets: Vec<OverallBet>,
+    /// How the coins of the players changed in each leg that was scored.
+    pub legs: Vec<LegSummary>,
+    /// The rules the game follows.
+    pub rules: RuleSet,
+    /// The betting photographer, when it was placed during this leg.
+    pub photographer: Option<Photographer>,
+}
+
+impl GameState {
+    /// Starts a game on the given race for a number of players, following the rules of the edition of the race.
+    ///
+    /// The leg starts with the dice of all camels in the pyramid and full stacks of leg betting tickets, see `leg_tickets`. Each player starts with their coins and a card for every camel. The track is long enough for the race.
+    pub fn new(race: Race, players: usize) -> Self {
+        let edition = RuleSet::from(race.edition());
+        let rules = RuleSet {
+            fata_morgana: race.stacking(),
+            board_length: edition.board_length.max(race.tiles()),
+            ..edition
+        };
+        Self::with_rules(race, players, rules).expect("the track to fit the race")
+    }
+
+    /// Starts a game like `new`, following the given rules. The race follows the rules as well.
+    ///
+    /// Races that span more tiles than the `board_length` of the rules are refused.
+    pub fn with_rules(race: Race, players: usize, rules: RuleSet) -> Result<Self, RaceParseError> {
+        race.check_length(rules.board_length)?;
+        let race = race.with_rules(&rules);
+        let camels = camels_of(&race);
+        let pyramid = Dice::from(&race);
+        let leg_tickets = fresh_tickets(&camels, &rules.leg_tickets);
+        let coins = rules.seating(players).start_coins;
+        Ok(Self {
+            race,
+            pyramid,
+            spectator_tiles: Vec::new(),
+            players: (0..players)
+                .map(|_| Player::new(camels.clone(), coins))
+                .collect(),
+            current: 0,
+            leg_tickets,
+            winner_bets: Vec::new(),
+            loser_bets: Vec::new(),
+            legs: Vec::new(),
+            rules,
+            photographer: None,
+        })
+    }
+
+    /// The chances of every camel at the end of the current leg, see `oracle::project`.
+    ///
+    /// The race and the dice in the pyramid are those of the state, and the race follows the rules of the game.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// let race = "r,,,,y".parse::<Race>().expect("to parse");
+    /// let state = GameState::new(race, 2);
+    ///
+    /// let chances = state.chances();
+    ///
+    /// assert_eq!(chances.winner[&Camel::Yellow], Fraction::one());
+    /// ```
+    pub fn chances(&self) -> LegChances {
+        project(&self.race, &self.pyramid)
+    }
+
+    /// The chances of every camel at the end of the race, estimated from the number of sampled races, see `oracle::sample_race`.
+    ///
+    /// Without a finish a race does not end, so there are no chances.
+    pub fn race_chances<R>(&self, samples: usize, rng: &mut R) -> Option<RaceChances>
+    where
+        R: Rng + ?Sized,
+    {
+        if self.race.positions.contains(&Marker::Finish) {
+            Some(sample_race(&self.race, &self.pyramid, samples, rng))
+        } else {
+            None
+        }
+    }
+
+    /// The leg betting tickets that can be taken, i.e. the top of each stack.
+    pub fn available_tickets(&self) -> Vec<Ticket> {
+        let mut tickets: Vec<Ticket> = self
+            .leg_tickets
+            .iter()
+            .filter_map(|(camel, stack)| stack.last().map(|value| Ticket::from((*camel, *value))))
+            .collect();
+        tickets.sort_by_key(|ticket| ticket.camel);
+        tickets
+    }
+
+    /// Whether the game is over, i.e. a camel crossed the finish.
+    pub fn is_over(&self) -> bool {
+        self.race.is_over()
+    }
+
+    /// The actions the current player can take.
+    ///
+    /// Spectator tiles can be placed on any position between the rear of the race and the finish that the rules allow. Without a finish, only positions within the race are considered.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        let mut actions = vec![Action::Roll];
+        actions.extend(self.available_tickets().into_iter().map(Action::TakeTicket));
+        for camel in &self.players[self.current].cards {
+            actions.push(Action::BetOnWinner(*camel));
+            actions.push(Action::BetOnLoser(*camel));
+        }
+        let tiles = self
+            .race
+            .positions
+            .iter()
+            .take_while(|marker| !marker.is_a_finish())
+            .filter(|marker| marker.is_a_divider())
+            .count();
+        for position in 1..=tiles {
+            for trap in [Marker::Oasis, Marker::FataMorgana] {
+                if self.with_trap(position, trap).is_ok() {
+                    actions.push(Action::PlaceTrap(position, trap));
+                }
+            }
+        }
+        actions.extend(
+            (0..self.players.len())
+                .filter(|partner| self.can_partner(*partner))
+                .map(Action::Partner),
+        );
+        if self.rules.has_photographer() && self.photographer.is_none() {
+            if let Some(finish) = self.finish_tile() {
+                actions.extend((1..finish).map(Action::Photograph));
+            }
+        }
+        actions
+    }
+
+    /// Ranks the actions the player can take on their turn by the number of coins they are expected to earn, best first.
+    ///
+    /// Leg betting tickets and rolling are valued like `advisor::rank`. Bets on the outcome of the race use chances estimated from `RACE_SAMPLES` sampled races, with a fixed seed so that the ranking does not change between calls, and a payout lowered by the bets that were placed before, see the `inference` module. They are only considered when the race has a finish. A spectator tile is worth the landings expected during the leg, a partnership the expected winnings of the partner.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// # use camel_up::advisor::Action;
+    /// let race = "r,,,,y".parse::<Race>().expect("to parse");
+    /// let state = GameState::new(race, 2);
+    ///
+    /// let ranked = state.rank_actions(1);
+    ///
+    /// assert_eq!(ranked[0], (Action::TakeTicket(Ticket::from((Camel::Yellow, 5))), Fraction::from(5)));
+    /// ```
+    pub fn rank_actions(&self, player: usize) -> Vec<(Action, Fraction)> {
+        self.rank_actions_with(player, &mut StdRng::seed_from_u64(0))
+    }
+
+    /// Ranks the actions like `rank_actions`, sampling the races with the given random number generator.
+    pub fn rank_actions_with<R>(&self, player: usize, rng: &mut R) -> Vec<(Action, Fraction)>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut turn = self.clone();
+        turn.current = player;
+        let legal = turn.legal_actions();
+        let leg = self.chances();
+        let race = self.race_chances(RACE_SAMPLES, rng);
+        let rewards = match &race {
+            Some(race) => {
+                let mut beliefs = Beliefs::new();
+                for (kind, bets) in [
+                    (Bet::Winner, &self.winner_bets),
+                    (Bet::Loser, &self.loser_bets),
+                ] {
+                    for bet in bets {
+                        if bet.player == player {
+                            beliefs.reveal(bet.player, kind, bet.camel);
+                        } else {
+                            beliefs.observe(bet.player, kind, race);
+                        }
+                    }
+                }
+                beliefs.rewards_with(&camels_of(&self.race), &self.rules)
+            }
+            None => Rewards::from(&self.rules),
+        };
+        let mut ranked: Vec<(Action, Fraction)> =
+            rank_with_rewards(&leg, &self.available_tickets(), race.as_ref(), &rewards)
+                .into_iter()
+                .filter(|(action, _)| legal.contains(action))
+                .collect();
+        for action in legal {
+            match action {
+                Action::PlaceTrap(position, trap) => {
+                    let (race, _, placed) = turn
+                        .with_trap(position, trap)
+                        .expect("legal spectator tiles to be placeable");
+                    let landings = expected_landings(&race, &self.pyramid, placed)
+                        .expect("a placed trap to be in its position");
+                    ranked.push((action, landings));
+                }
+                Action::Partner(partner) => {
+                    let value = partnership(
+                        &leg,
+                        &self.players[player].tickets,
+                        &self.players[partner].tickets,
+                    );
+                    ranked.push((action, value.gained));
+                }
+                Action::Photograph(position) => {
+                    let finish = self
+                        .finish_tile()
+                        .expect("a finish for the photographer to be placed");
+                    ranked.push((
+                        action,
+                        expected_arrivals(&self.race, &self.pyramid, finish - position),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        ranked.sort_by(|(_, left), (_, right)| right.cmp(left));
+        ranked
+    }
+
+    /// Ranks the actions like `rank_actions`, looking ahead to the next turn of the player.
+    ///
+    /// Every other player acts before that turn, so with more players at the table the best leg betting tickets are more likely to be gone. The value of an action adds what the player can expect on their next turn: the best ticket the others leave, see `advisor::next_ticket`, or a roll when that is worth more.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// # use camel_up::advisor::Action;
+    /// let race = "r,,,,y".parse::<Race>().expect("to parse");
+    /// let state = GameState::new(race, 2);
+    ///
+    /// let ranked = state.rank_actions_ahead(0);
+    ///
+    /// assert_eq!(ranked[0], (Action::TakeTicket(Ticket::from((Camel::Yellow, 5))), Fraction::from(7)));
+    /// ```
+    pub fn rank_actions_ahead(&self, player: usize) -> Vec<(Action, Fraction)> {
+        let leg = self.chances();
+        let opponents = self.players.len().saturating_sub(1);
+        let mut ranked: Vec<(Action, Fraction)> = self
+            .rank_actions(player)
+            .into_iter()
+            .map(|(action, value)| {
+                let mut stacks = self.leg_tickets.clone();
+                let mut dice = self.pyramid.len();
+                match action {
+                    Action::TakeTicket(ticket) => {
+                        if let Some(stack) = stacks.get_mut(&ticket.camel) {
+                            stack.pop();
+                        }
+                    }
+                    Action::Roll => dice = dice.saturating_sub(1),
+                    _ => {}
+                }
+                let roll = Fraction::from(ROLL_REWARD);
+                let next = next_ticket(&leg, &stacks, opponents, dice)
+                    .map(|(_, value)| value)
+                    .filter(|value| *value > roll)
+                    .unwrap_or(roll);
+                (action, value + next)
+            })
+            .collect();
+        ranked.sort_by(|(_, left), (_, right)| right.cmp(left));
+        ranked
+    }
+
+    /// The current player takes their turn with the action, after which it is the turn of the next player.
+    ///
+    /// Rolling draws a random die from the pyramid with a random face, see `apply_roll`. Placing a spectator tile moves a tile of the player when all of their tiles are already in the race. Illegal actions are refused and leave the state as it was.
+    pub fn apply<R>(&mut self, action: Action, rng: &mut R) -> Result<(), IllegalAction>
+    where
+        R: Rng + ?Sized,
+    {
+        self.apply_observed(action, rng, &mut Unobserved)
+    }
+
+    /// Applies the action like `apply`, telling the observer what happens.
+    pub fn apply_observed<R>(
+        &mut self,
+        action: Action,
+        rng: &mut R,
+        observer: &mut dyn Observer,
+    ) -> Result<(), IllegalAction>
+    where
+        R: Rng + ?Sized,
+    {
+        match action {
+            Action::Roll if !self.is_over() => {
+                let roll = self.draw(rng);
+                self.apply_roll_observed(roll, observer)
+            }
+            action => self.decide_observed(action, observer),
+        }
+    }
+
+    /// Applies an action that does not involve dice, i.e. anything but rolling.
+    fn decide(&mut self, action: Action) -> Result<(), IllegalAction> {
+        self.decide_observed(action, &mut Unobserved)
+    }
+
+    fn decide_observed(
+        &mut self,
+        action: Action,
+        observer: &mut dyn Observer,
+    ) -> Result<(), IllegalAction> {
+        if self.is_over() {
+            return Err(IllegalAction::GameOver);
+        }
+        match action {
+            Action::Roll => unreachable!(/* rolls are drawn by apply */),
+            Action::TakeTicket(ticket) => {
+                let stack = self
+                    .leg_tickets
+                    .get_mut(&ticket.camel)
+                    .filter(|stack| stack.last() == Some(&ticket.value))
+                    .ok_or(IllegalAction::TicketUnavailable(ticket))?;
+                stack.pop();
+                self.players[self.current].tickets.push(ticket);
+            }
+            Action::BetOnWinner(camel) => {
+                let player = self.play_card(camel)?;
+                self.winner_bets.push(OverallBet { player, camel });
+            }
+            Action::BetOnLoser(camel) => {
+                let player = self.play_card(camel)?;
+                self.loser_bets.push(OverallBet { player, camel });
+            }
+            Action::PlaceTrap(position, trap) => self.place_trap(position, trap)?,
+            Action::Partner(partner) => {
+                if !self.rules.partnerships {
+                    return Err(IllegalAction::NoPartnerships);
+                }
+                if !self.can_partner(partner) {
+                    return Err(IllegalAction::NotAPartner(partner));
+                }
+                self.players[self.current].partner = Some(partner);
+                self.players[partner].partner = Some(self.current);
+            }
+            Action::Photograph(position) => {
+                if !self.rules.has_photographer() {
+                    return Err(IllegalAction::NoPhotographer);
+                }
+                if self.photographer.is_some() {
+                    return Err(IllegalAction::PhotographerPlaced);
+                }
+                let distance = self
+                    .finish_tile()
+                    .filter(|finish| (1..*finish).contains(&position))
+                    .map(|finish| finish - position)
+                    .ok_or(IllegalAction::NotOnTheTrack(position))?;
+                self.photographer = Some(Photographer {
+                    owner: self.current,
+                    distance,
+                });
+            }
+        }
+        if let Action::TakeTicket(_) | Action::BetOnWinner(_) | Action::BetOnLoser(_) = action {
+            observer.on_bet(self, self.current, action);
+        }
+        self.next_turn();
+        Ok(())
+    }
+
+    /// The current player takes a pyramid ticket, and the die shows the given roll.
+    ///
+    /// This allows one to follow a game played with real dice. When the camel lands on a spectator tile, the owner of the tile receives a coin. When the last die of the pyramid is drawn, the leg ends.
+    pub fn apply_roll<R>(&mut self, roll: R) -> Result<(), IllegalAction>
+    where
+        R: Into<Roll>,
+    {
+        self.apply_roll_observed(roll, &mut Unobserved)
+    }
+
+    /// Applies the roll like `apply_roll`, telling the observer what happens.
+    pub fn apply_roll_observed<R>(
+        &mut self,
+        roll: R,
+        observer: &mut dyn Observer,
+    ) -> Result<(), IllegalAction>
+    where
+        R: Into<Roll>,
+    {
+        let roll: Roll = roll.into();
+        if self.is_over() {
+            return Err(IllegalAction::GameOver);
+        }
+        if !self.pyramid.contains(roll.camel()) {
+            return Err(IllegalAction::DieNotInPyramid(roll.camel()));
+        }
+        let payouts = self.payouts(roll);
+        self.race = self.race.perform(roll);
+        self.pyramid = self.pyramid.remove(roll.camel());
+        self.players[self.current].pyramid_tickets += 1;
+        observer.on_roll(self, self.current, roll);
+        for payout in payouts {
+            let player = &mut self.players[payout.player()];
+            player.coins += payout.coins();
+            player.landings += 1;
+            match payout {
+                Payout::Spectator(owner) => observer.on_trap_triggered(self, owner, roll.camel()),
+                Payout::Photographer(owner) => observer.on_photographed(self, owner, roll.camel()),
+            }
+        }
+        if self.is_over() || self.pyramid.is_empty() {
+            self.score_leg();
+            if self.is_over() {
+                self.score_race();
+            }
+            observer.on_leg_end(self, self.legs.last().unwrap(/* the leg was just scored */));
+            if !self.is_over() {
+                self.reset_leg();
+            }
+        }
+        self.next_turn();
+        Ok(())
+    }
+
+    /// The coins the roll would pay out to the owners of the tiles the camel lands on, without performing it.
+    ///
+    /// The spectator tile is paid before the photographer. A roll of a camel that is not in the race pays nothing.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// # use camel_up::{advisor::Action, game::Payout};
+    /// # use rand::{rngs::StdRng, SeedableRng};
+    /// let race = "r,,,,,,,,,!".parse::<Race>().expect("to parse");
+    /// let mut state = GameState::new(race, 2);
+    /// state.apply(Action::PlaceTrap(2, Marker::Oasis), &mut StdRng::seed_from_u64(0)).expect("to be legal");
+    ///
+    /// assert_eq!(state.payouts((Camel::Red, Face::Two)), vec![Payout::Spectator(0)]);
+    /// assert_eq!(state.payouts((Camel::Red, Face::One)), vec![]);
+    /// ```
+    pub fn payouts<R>(&self, roll: R) -> Vec<Payout>
+    where
+        R: Into<Roll>,
+    {
+        let roll: Roll = roll.into();
+        let mut payouts = Vec::new();
+        let outcome = match self.race.perform_detailed(roll) {
+            Ok(outcome) => outcome,
+            Err(_) => return payouts,
+        };
+        if let Some(owner) = outcome
+            .trap
+            .and_then(|ordinal| self.spectator_tiles.get(ordinal).copied())
+        {
+            payouts.push(Payout::Spectator(owner));
+        }
+        let destination = self
+            .race
+            .position_of(roll.camel())
+            .map(|tile| tile + usize::from(roll.face()));
+        if let Some(photographer) = self.photographer.filter(|photographer| {
+            destination.is_some()
+                && self
+                    .finish_tile()
+                    .and_then(|finish| finish.checked_sub(photographer.distance))
+                    == destination
+        }) {
+            payouts.push(Payout::Photographer(photographer.owner));
+        }
+        payouts
+    }
+
+    fn draw<R>(&self, rng: &mut R) -> Roll
+    where
+        R: Rng + ?Sized,
+    {
+        let mut dice: Vec<Camel> = self.pyramid.iter().collect();
+        dice.sort();
+        let camel = *dice.choose(rng).unwrap(/* the pyramid is refilled at the end of every leg */);
+        let face = *[Face::One, Face::Two, Face::Three]
+            .choose(rng)
+            .unwrap(/* faces is not empty */);
+        Roll::from((camel, face))
+    }
+
+    fn play_card(&mut self, camel: Camel) -> Result<usize, IllegalAction> {
+        let player = self.current;
+        let cards = &mut self.players[player].cards;
+        let index = cards
+            .iter()
+            .position(|card| *card == camel)
+            .ok_or(IllegalAction::NoCard(camel))?;
+        cards.remove(index);
+        Ok(player)
+    }
+
+    /// Whether the current player can form a partnership with the other player, when the edition allows it.
+    fn can_partner(&self, partner: usize) -> bool {
+        self.rules.partnerships
+            && partner != self.current
+            && partner < self.players.len()
+            && self.players[self.current].partner.is_none()
+            && self.players[partner].partner.is_none()
+    }
+
+    fn place_trap(&mut self, position: usize, trap: Marker) -> Result<(), IllegalAction> {
+        let (race, owners, _) = self.with_trap(position, trap)?;
+        self.race = race;
+        self.spectator_tiles = owners;
+        Ok(())
+    }
+
+    /// The race and the owners of the spectator tiles after the current player places their tile, together with the position of the placed tile in that race.
+    ///
+    /// When all tiles of the player are on the track, the rearmost one is moved. Moving a tile from the rear of the race shifts the start of the race, so the placed tile can end up in a different position than the one it was placed on.
+    fn with_trap(
+        &self,
+        position: usize,
+        trap: Marker,
+    ) -> Result<(Race, Vec<usize>, usize), IllegalAction> {
+        if !trap.is_an_adjustment() {
+            return Err(IllegalAction::NotATrap(trap));
+        }
+        let allowed = self.rules.seating(self.players.len()).spectator_tiles;
+        if allowed == 0 {
+            return Err(IllegalAction::NoSpectatorTile);
+        }
+        let mut race = self.race;
+        let mut owners = self.spectator_tiles.clone();
+        let placed = owners
+            .iter()
+            .filter(|owner| **owner == self.current)
+            .count();
+        if placed >= allowed {
+            let ordinal = owners
+                .iter()
+                .position(|owner| *owner == self.current)
+                .unwrap(/* the player has a tile on the track */);
+            remove_trap(&mut race, ordinal);
+            owners.remove(ordinal);
+        }
+        let ordinal = trap_tiles(&race)
+            .iter()
+            .filter(|tile| **tile < position)
+            .count();
+        let race = race.place_trap(position, trap)?;
+        owners.insert(ordinal, self.current);
+        let placed = trap_tiles(&race)[ordinal];
+        Ok((race, owners, placed))
+    }
+
+    fn score_leg(&mut self) {
+        let winner = self.race.winner();
+        let runner_up = self.race.runner_up();
+        let payout = |ticket: &Ticket| {
+            if Some(ticket.camel) == winner {
+                ticket.value as i64
+            } else if Some(ticket.camel) == runner_up {
+                1
+            } else {
+                -1
+            }
+        };
+        let winnings: Vec<i64> = self
+            .players
+            .iter()
+            .map(|player| {
+                player
+                    .tickets
+                    .iter()
+                    .map(payout)
+                    .filter(|coins| *coins > 0)
+                    .sum()
+            })
+            .collect();
+        let mut scores = Vec::new();
+        for (index, player) in self.players.iter_mut().enumerate() {
+            let tickets: i64 = player.tickets.drain(..).map(|ticket| payout(&ticket)).sum();
+            let partner = player.partner.take().map_or(0, |partner| winnings[partner]);
+            let pyramid = player.pyramid_tickets as i64 * ROLL_REWARD;
+            player.coins = (player.coins + tickets + partner + pyramid).max(0);
+            scores.push(Score {
+                player: index,
+                pyramid,
+                spectator: player.landings as i64,
+                tickets,
+                partner,
+                overall: 0,
+                coins: player.coins,
+            });
+            player.pyramid_tickets = 0;
+            player.landings = 0;
+        }
+        self.legs.push(LegSummary {
+            leg: self.legs.len() + 1,
+            scores,
+        });
+    }
+
+    /// Pays out the bets on the overall winner and loser, adding them to the summary of the last leg.
+    fn score_race(&mut self) {
+        let mut overall = vec![0; self.players.len()];
+        let outcomes = [
+            (self.race.winner(), &self.winner_bets),
+            (self.race.loser(), &self.loser_bets),
+        ];
+        for (outcome, bets) in outcomes {
+            let mut correct = 0;
+            for bet in bets {
+                overall[bet.player] += if Some(bet.camel) == outcome {
+                    correct += 1;
+                    self.rules.overall_payout(correct - 1)
+                } else {
+                    -self.rules.overall_penalty
+                };
+            }
+        }
+        for (player, coins) in self.players.iter_mut().zip(&overall) {
+            player.coins = (player.coins + coins).max(0);
+        }
+        if let Some(summary) = self.legs.last_mut() {
+            for score in summary.scores.iter_mut() {
+                score.overall = overall[score.player];
+                score.coins = self.players[score.player].coins;
+            }
+        }
+    }
+
+    /// The final standings, once the game is over.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// # use camel_up::advisor::Action;
+    /// # use rand::{rngs::StdRng, SeedableRng};
+    /// let race = "r,y!".parse::<Race>().expect("to parse");
+    /// let mut state = GameState::new(race, 2);
+    /// let mut rng = StdRng::seed_from_u64(42);
+    ///
+    /// state.apply(Action::BetOnWinner(Camel::Yellow), &mut rng).expect("to be legal");
+    /// assert!(state.standings().is_none());
+    /// state.apply_roll((Camel::Yellow, Face::One)).expect("to be legal");
+    ///
+    /// let standings = state.standings().expect("game to be over");
+    /// assert_eq!(standings.places[0].player, 0);
+    /// assert_eq!(standings.places[0].coins, 3 + 8);
+    /// ```
+    pub fn standings(&self) -> Option<Standings> {
+        if !self.is_over() {
+            return None;
+        }
+        let mut places: Vec<Standing> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(player, state)| Standing {
+                place: 1 + self
+                    .players
+                    .iter()
+                    .filter(|other| other.coins > state.coins)
+                    .count(),
+                player,
+                coins: state.coins,
+            })
+            .collect();
+        places.sort_by_key(|standing| (standing.place, standing.player));
+        Some(Standings { places })
+    }
+
+    fn reset_leg(&mut self) {
+        let camels = camels_of(&self.race);
+        let positions: Vec<Marker> = self
+            .race
+            .positions
+            .iter()
+            .filter(|marker| !marker.is_an_adjustment())
+            .collect();
+        self.race = Race::from(positions).following(&self.race);
+        self.spectator_tiles.clear();
+        self.photographer = None;
+        self.pyramid = Dice::from(&self.race);
+        self.leg_tickets = fresh_tickets(&camels, &self.rules.leg_tickets);
+    }
+
+    /// The tile of the finish, counted from the start of the race, when the race has a finish.
+    fn finish_tile(&self) -> Option<usize> {
+        let index = self
+            .race
+            .positions
+            .iter()
+            .position(|marker| marker.is_a_finish())?;
+        Some(
+            self.race
+                .positions
+                .iter()
+                .take(index)
+                .filter(|marker| marker.is_a_divider())
+                .count(),
+        )
+    }
+
+    fn next_turn(&mut self) {
+        self.current = (self.current + 1) % self.players.len();
+    }
+}
+
+/// How the players finished the game, the richest player first.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Standings {
+    /// The place of every player. Players with the same number of coins share their place.
+    pub places: Vec<Standing>,
+}
+
+/// The place of a player at the end of the game.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Standing {
+    /// The place, starting at 1 for the winner.
+    pub place: usize,
+    /// The player.
+    pub player: usize,
+    /// The coins of the player at the end of the game.
+    pub coins: i64,
+}
+
+impl Display for Standings {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for standing in &self.places {
+            writeln!(
+                f,
+                "{}. player {}: {} coins",
+                standing.place,
+                standing.player + 1,
+                standing.coins
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How the coins of the players changed during a leg.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// let race = "r,,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+/// let mut state = GameState::new(race, 2);
+///
+/// state.apply_roll((Camel::Red, Face::One)).expect("to be legal");
+///
+/// assert_eq!(state.legs[0].scores[0].pyramid, 1);
+/// assert_eq!(
+///     state.legs[0].to_string(),
+///     "leg 1\n\
+///     player 1: 4 coins (pyramid +1, spectator +0, tickets +0, partner +0, overall +0)\n\
+///     player 2: 3 coins (pyramid +0, spectator +0, tickets +0, partner +0, overall +0)\n"
+/// );
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LegSummary {
+    /// The number of the leg, starting at 1.
+    pub leg: usize,
+    /// The score of every player, in the order they take their turn.
+    pub scores: Vec<Score>,
+}
+
+/// The coins a player earned during a leg, by source.
+///
+/// Spectator tiles pay out during the leg, everything else at the end of it. The bets on the overall winner and loser are only paid out in the last leg.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Score {
+    /// The player.
+    pub player: usize,
+    /// The coins from pyramid tickets.
+    pub pyramid: i64,
+    /// The coins from camels landing on the spectator tile of the player.
+    pub spectator: i64,
+    /// The coins won or lost with leg betting tickets.
+    pub tickets: i64,
+    /// The coins from the winning tickets of the partner of the player.
+    pub partner: i64,
+    /// The coins won or lost with bets on the overall winner and loser.
+    pub overall: i64,
+    /// The coins of the player after the leg. A player can not go below zero coins.
+    pub coins: i64,
+}
+
+impl Display for LegSummary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "leg {}", self.leg)?;
+        for score in &self.scores {
+            writeln!(
+                f,
+                "player {}: {} coins (pyramid {:+}, spectator {:+}, tickets {:+}, partner {:+}, overall {:+})",
+                score.player + 1,
+                score.coins,
+                score.pyramid,
+                score.spectator,
+                score.tickets,
+                score.partner,
+                score.overall
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The reasons an action can be refused.
+#[derive(PartialEq, Debug)]
+pub enum IllegalAction {
+    /// A camel crossed the finish, so no more actions can be taken.
+    GameOver,
+    /// The ticket is not on top of the stack of its camel.
+    TicketUnavailable(Ticket),
+    /// The die of the camel was already drawn this leg.
+    DieNotInPyramid(Camel),
+    /// The player already played the card of the camel.
+    NoCard(Camel),
+    /// Only an oasis or a fata morgana can be placed.
+    NotATrap(Marker),
+    /// The spectator tile can not be placed there, see `RaceParseError` for the reason.
+    Trap(RaceParseError),
+    /// The edition of the race has no partnerships.
+    NoPartnerships,
+    /// The player can not become a partner, because one of the two already has a partner or it is the player themselves.
+    NotAPartner(usize),
+    /// The rules give the players no spectator tiles.
+    NoSpectatorTile,
+    /// The rules do not include the betting photographer of the Supercup expansion.
+    NoPhotographer,
+    /// The photographer was already placed during this leg.
+    PhotographerPlaced,
+    /// The position does not lie between the rear of the race and the finish.
+    NotOnTheTrack(usize),
+}
+
+impl From<RaceParseError> for IllegalAction {
+    fn from(problem: RaceParseError) -> Self {
+        Self::Trap(problem)
+    }
+}
+
+impl Display for IllegalAction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            IllegalAction::GameOver => write!(f, "the game is over"),
+            IllegalAction::TicketUnavailable(ticket) => write!(
+                f,
+                "the {:?} ticket worth {} is not available",
+                ticket.camel, ticket.value
+            ),
+            IllegalAction::DieNotInPyramid(camel) => {
+                write!(f, "the {:?} die is not in the pyramid", camel)
+            }
+            IllegalAction::NoCard(camel) => write!(f, "the {:?} card was already played", camel),
+            IllegalAction::NotATrap(marker) => write!(f, "'{}' is not a spectator tile", marker),
+            IllegalAction::Trap(problem) => write!(f, "{}", problem),
+            IllegalAction::NoPartnerships => write!(f, "partnerships are not part of this edition"),
+            IllegalAction::NotAPartner(partner) => {
+                write!(f, "player {} can not become a partner", partner + 1)
+            }
+            IllegalAction::NoSpectatorTile => write!(f, "there are no spectator tiles to place"),
+            IllegalAction::NoPhotographer => write!(f, "the rules have no photographer"),
+            IllegalAction::PhotographerPlaced => {
+                write!(f, "the photographer was already placed this leg")
+            }
+            IllegalAction::NotOnTheTrack(position) => {
+                write!(f, "position {} is not on the track", position)
+            }
+        }
+    }
+}
+
+impl Error for IllegalAction {}
+
+/// The position of every trap in the race, from the rear of the race to the front.
+fn trap_tiles(race: &Race) -> Vec<usize> {
+    race.positions
+        .iter()
+        .enumerate()
+        .filter(|(_, marker)| marker.is_an_adjustment())
+        .map(|(index, _)| {
+            race.positions
+                .iter()
+                .take(index)
+                .filter(|marker| marker.is_a_divider())
+                .count()
+        })
+        .collect()
+}
+
+/// Removes a trap without normalizing the race, so that positions keep counting from the same start.
+fn remove_trap(race: &mut Race, ordinal: usize) {
+    let index = race
+        .positions
+        .iter()
+        .enumerate()
+        .filter(|(_, marker)| marker.is_an_adjustment())
+        .map(|(index, _)| index)
+        .nth(ordinal)
+        .unwrap(/* every spectator tile has an owner */);
+    race.positions.remove(index);
+}
+
+fn camels_of(race: &Race) -> Vec<Camel> {
+    let mut camels = race.ranking();
+    camels.sort();
+    camels
+}
+
+fn fresh_tickets(camels: &[Camel], stack: &[u8]) -> HashMap<Camel, Vec<u8>> {
+    camels
+        .iter()
+        .map(|camel| (*camel, stack.to_vec()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        camel::Stacking,
+        rules::{Seating, Supercup},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn players_start_with_coins_and_a_card_for_every_camel() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+
+        let state = GameState::new(race, 2);
+
+        assert_eq!(state.players[1].coins, START_COINS);
+        assert_eq!(state.players[1].cards, vec![Camel::Red, Camel::Yellow]);
+    }
+
+    #[test]
+    fn more_players_leave_fewer_tickets_for_the_next_turn() {
+        let race = "r,,,,y".parse::<Race>().expect("to parse");
+        let ranked = |players| GameState::new(race, players).rank_actions_ahead(0);
+        let value = |ranked: &[(Action, Fraction)], action| {
+            ranked
+                .iter()
+                .find(|(candidate, _)| *candidate == action)
+                .map(|(_, value)| *value)
+        };
+        let yellow = Action::TakeTicket(Ticket::from((Camel::Yellow, 5)));
+
+        assert_eq!(value(&ranked(2), yellow), Some(Fraction::from(7)));
+        assert_eq!(value(&ranked(2), Action::Roll), Some(Fraction::from(4)));
+        assert_eq!(value(&ranked(4), yellow), Some(Fraction::from(6)));
+    }
+
+    #[test]
+    fn players_can_have_more_spectator_tiles_when_the_rules_say_so() {
+        let race = "r,,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let rules = RuleSet {
+            seating: vec![Seating {
+                start_coins: 5,
+                spectator_tiles: 2,
+            }],
+            ..RuleSet::default()
+        };
+        let mut state = GameState::with_rules(race, 1, rules).expect("to fit the track");
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for position in [2, 4, 6] {
+            state
+                .apply(Action::PlaceTrap(position, Marker::Oasis), &mut rng)
+                .expect("to be legal");
+        }
+
+        assert_eq!(state.players[0].coins, 5);
+        assert_eq!(state.race.to_string(), "r,,,,+,,+,,,,,,,,,!");
+        assert_eq!(state.spectator_tiles, vec![0, 0]);
+    }
+
+    #[test]
+    fn the_photographer_earns_a_coin_for_every_landing() {
+        let race = "r,y,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let rules = RuleSet::default().with_supercup(Supercup::default());
+        let mut state = GameState::with_rules(race, 2, rules).expect("to fit the track");
+
+        assert!(state.legal_actions().contains(&Action::Photograph(3)));
+        state.decide(Action::Photograph(3)).expect("to be legal");
+        assert_eq!(
+            state.decide(Action::Photograph(4)),
+            Err(IllegalAction::PhotographerPlaced)
+        );
+        state
+            .apply_roll((Camel::Yellow, Face::Two))
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Red, Face::Three))
+            .expect("to be legal");
+
+        assert_eq!(state.players[0].coins, START_COINS + 2 + 1);
+        assert_eq!(state.photographer, None);
+    }
+
+    #[test]
+    fn only_the_supercup_has_a_photographer() {
+        let race = "r,y,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+
+        assert!(!state
+            .legal_actions()
+            .iter()
+            .any(|action| matches!(action, Action::Photograph(_))));
+        assert_eq!(
+            state.decide(Action::Photograph(3)),
+            Err(IllegalAction::NoPhotographer)
+        );
+    }
+
+    #[test]
+    fn bets_on_the_race_are_valued_by_the_rules_of_the_game() {
+        let race = "r,,y,,,,!".parse::<Race>().expect("to parse");
+        let rules = RuleSet {
+            overall_payouts: vec![4],
+            overall_penalty: 3,
+            ..RuleSet::default()
+        };
+        let state = GameState::with_rules(race, 2, rules).expect("to fit the track");
+
+        let ranked = state.rank_actions(0);
+
+        let chances = state
+            .race_chances(RACE_SAMPLES, &mut StdRng::seed_from_u64(0))
+            .expect("a finish");
+        let chance = chances.winner[&Camel::Yellow];
+        assert!(ranked.contains(&(
+            Action::BetOnWinner(Camel::Yellow),
+            chance * 4 - (Fraction::one() - chance) * 3
+        )));
+    }
+
+    #[test]
+    fn games_refuse_races_longer_than_the_track() {
+        let race = "r,,,,,,,,y,!".parse::<Race>().expect("to parse");
+        let rules = RuleSet {
+            board_length: 8,
+            ..RuleSet::default()
+        };
+
+        assert_eq!(
+            GameState::with_rules(race, 2, rules),
+            Err(RaceParseError::TooLong(9))
+        );
+        assert_eq!(
+            GameState::new(race, 2).rules.board_length,
+            setup::TRACK_LENGTH
+        );
+    }
+
+    #[test]
+    fn chances_follow_the_rules_of_the_game() {
+        let race = "yr,-".parse::<Race>().expect("to parse");
+        let rules = RuleSet {
+            fata_morgana: Stacking::Under,
+            ..RuleSet::default()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let classic = GameState::new(race, 2);
+        let under = GameState::with_rules(race, 2, rules).expect("to fit the track");
+
+        assert_ne!(
+            classic.chances().winner[&Camel::Red],
+            under.chances().winner[&Camel::Red]
+        );
+        assert!(under.race_chances(10, &mut rng).is_none());
+    }
+
+    #[test]
+    fn games_with_the_same_seed_are_the_same() {
+        let play = |seed| {
+            let race = "r,y,,g,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+            let mut state = GameState::new(race, 3);
+            let mut rng = StdRng::seed_from_u64(seed);
+            while !state.is_over() {
+                state.apply(Action::Roll, &mut rng).expect("to be legal");
+            }
+            state
+        };
+
+        assert_eq!(play(3), play(3));
+        assert_eq!(
+            GameState::new("r,y,,!".parse::<Race>().expect("to parse"), 2)
+                .rank_actions_with(0, &mut StdRng::seed_from_u64(5)),
+            GameState::new("r,y,,!".parse::<Race>().expect("to parse"), 2)
+                .rank_actions_with(0, &mut StdRng::seed_from_u64(5))
+        );
+    }
+
+    #[test]
+    fn the_top_tickets_are_available() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+
+        let state = GameState::new(race, 2);
+
+        assert_eq!(
+            state.available_tickets(),
+            vec![
+                Ticket::from((Camel::Red, 5)),
+                Ticket::from((Camel::Yellow, 5))
+            ]
+        );
+    }
+
+    #[test]
+    fn the_leg_ends_when_the_last_die_is_drawn() {
+        let race = "y,r,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        state
+            .apply(
+                Action::TakeTicket(Ticket::from((Camel::Yellow, 5))),
+                &mut rng,
+            )
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Red, Face::One))
+            .expect("to be legal");
+        assert_eq!(state.pyramid.len(), 1);
+        state
+            .apply_roll((Camel::Yellow, Face::Two))
+            .expect("to be legal");
+
+        assert_eq!(state.players[0].coins, START_COINS + 5 + 1);
+        assert_eq!(state.players[1].coins, START_COINS + 1);
+        assert_eq!(state.pyramid.len(), 2);
+        assert_eq!(state.available_tickets().len(), 2);
+        assert_eq!(state.current, 1);
+    }
+
+    #[test]
+    fn taken_tickets_are_no_longer_available() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+        let mut rng = StdRng::seed_from_u64(7);
+        let ticket = Ticket::from((Camel::Red, 5));
+
+        state
+            .apply(Action::TakeTicket(ticket), &mut rng)
+            .expect("to be legal");
+
+        assert_eq!(
+            state.apply(Action::TakeTicket(ticket), &mut rng),
+            Err(IllegalAction::TicketUnavailable(ticket))
+        );
+        assert_eq!(state.current, 1);
+    }
+
+    #[test]
+    fn spectator_tiles_move_and_are_returned_at_the_end_of_the_leg() {
+        let race = "r,,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 1);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        state
+            .apply(Action::PlaceTrap(4, Marker::Oasis), &mut rng)
+            .expect("to be legal");
+        state
+            .apply(Action::PlaceTrap(2, Marker::FataMorgana), &mut rng)
+            .expect("to be legal");
+        assert_eq!(state.race.to_string(), "r,,-,,,,,,,,,,,,,!");
+        assert_eq!(state.spectator_tiles, vec![0]);
+
+        state
+            .apply_roll((Camel::Red, Face::Three))
+            .expect("to be legal");
+
+        assert_eq!(state.race.to_string(), "r,,,,,,,,,,,,!");
+        assert!(state.spectator_tiles.is_empty());
+    }
+
+    #[test]
+    fn overall_bets_are_paid_in_the_order_they_were_placed() {
+        let race = "r,y,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 3);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for action in [
+            Action::BetOnWinner(Camel::Yellow),
+            Action::BetOnWinner(Camel::Red),
+            Action::BetOnWinner(Camel::Yellow),
+        ] {
+            state.apply(action, &mut rng).expect("to be legal");
+        }
+        state
+            .apply_roll((Camel::Yellow, Face::Three))
+            .expect("to be legal");
+
+        assert!(state.is_over());
+        let coins: Vec<i64> = state.players.iter().map(|player| player.coins).collect();
+        assert_eq!(
+            coins,
+            vec![START_COINS + 1 + 8, START_COINS - 1, START_COINS + 5]
+        );
+    }
+
+    #[test]
+    fn players_with_the_same_coins_share_their_place() {
+        let race = "r!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 3);
+        state.players[2].coins = 7;
+
+        state
+            .apply_roll((Camel::Red, Face::One))
+            .expect("to be legal");
+
+        let standings = state.standings().expect("game to be over");
+        assert_eq!(
+            standings.to_string(),
+            "1. player 3: 7 coins\n2. player 1: 4 coins\n3. player 2: 3 coins\n"
+        );
+    }
+
+    #[test]
+    fn partners_share_their_winnings_but_not_their_losses() {
+        let race = "y,r,,,,,,,,,,,,,,!"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_edition(Edition::Second);
+        let mut state = GameState::new(race, 3);
+
+        state.decide(Action::Partner(1)).expect("to be legal");
+        assert_eq!(
+            state.decide(Action::Partner(0)),
+            Err(IllegalAction::NotAPartner(0))
+        );
+        state
+            .decide(Action::TakeTicket(Ticket::from((Camel::Yellow, 5))))
+            .expect("to be legal");
+        state
+            .decide(Action::TakeTicket(Ticket::from((Camel::Red, 5))))
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Yellow, Face::Three))
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Red, Face::One))
+            .expect("to be legal");
+
+        assert_eq!(state.players[0].coins, START_COINS + 5 + 1);
+        assert_eq!(state.players[1].coins, START_COINS + 5 + 1);
+        assert_eq!(state.players[2].coins, START_COINS + 1);
+        assert!(state.players.iter().all(|player| player.partner.is_none()));
+    }
+
+    #[test]
+    fn the_classic_edition_has_no_partnerships() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+
+        assert!(!state.legal_actions().contains(&Action::Partner(1)));
+        assert_eq!(
+            state.decide(Action::Partner(1)),
+            Err(IllegalAction::NoPartnerships)
+        );
+    }
+
+    #[test]
+    fn the_second_edition_has_an_extra_ticket_of_two() {
+        let race = "r,y"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_edition(Edition::Second);
+        let mut state = GameState::new(race, 2);
+
+        for value in [5, 3, 2, 2] {
+            state
+                .decide(Action::TakeTicket(Ticket::from((Camel::Red, value))))
+                .expect("to be legal");
+        }
+
+        assert_eq!(
+            state.decide(Action::TakeTicket(Ticket::from((Camel::Red, 2)))),
+            Err(IllegalAction::TicketUnavailable(Ticket::from((
+                Camel::Red,
+                2
+            ))))
+        );
+        assert_eq!(
+            state.available_tickets(),
+            vec![Ticket::from((Camel::Yellow, 5))]
+        );
+    }
+
+    #[test]
+    fn landing_on_a_spectator_tile_pays_its_owner() {
+        let race = "r,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+
+        state
+            .decide(Action::PlaceTrap(2, Marker::Oasis))
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Red, Face::Two))
+            .expect("to be legal");
+
+        assert_eq!(state.players[0].coins, START_COINS + 1);
+        assert_eq!(state.players[1].coins, START_COINS + 1);
+        assert_eq!(state.legs[0].scores[0].spectator, 1);
+        assert_eq!(state.legs[0].scores[1].pyramid, 1);
+    }
+
+    #[test]
+    fn payouts_follow_the_spectator_tiles_as_the_race_moves() {
+        let race = "r,,,,,,,,y,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+        state
+            .decide(Action::PlaceTrap(2, Marker::Oasis))
+            .expect("to be legal");
+        state
+            .decide(Action::PlaceTrap(4, Marker::FataMorgana))
+            .expect("to be legal");
+
+        assert_eq!(
+            state.payouts((Camel::Red, Face::Two)),
+            vec![Payout::Spectator(0)]
+        );
+        assert_eq!(state.payouts((Camel::Red, Face::Three)), vec![]);
+        assert_eq!(state.payouts((Camel::White, Face::Two)), vec![]);
+
+        state
+            .apply_roll((Camel::Red, Face::Two))
+            .expect("to be legal");
+
+        assert_eq!(state.race.to_string(), "+,r,-,,,,y,!");
+        assert_eq!(
+            state.payouts((Camel::Red, Face::One)),
+            vec![Payout::Spectator(1)]
+        );
+    }
+
+    #[test]
+    fn moving_a_spectator_tile_from_the_rear_is_ranked() {
+        let race = "r,,y,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 1);
+        state
+            .decide(Action::PlaceTrap(1, Marker::Oasis))
+            .expect("to be legal");
+        state
+            .apply_roll((Camel::Red, Face::One))
+            .expect("to be legal");
+
+        let ranked = state.rank_actions(0);
+
+        let (race, _, placed) = state.with_trap(4, Marker::Oasis).expect("to be placeable");
+        assert_eq!(race.to_string(), "yr,,,+,,!");
+        assert_eq!(placed, 3);
+        assert!(ranked.contains(&(
+            Action::PlaceTrap(4, Marker::Oasis),
+            expected_landings(&race, &state.pyramid, placed).expect("a trap")
+        )));
+    }
+
+    #[test]
+    fn every_legal_action_is_ranked() {
+        let race = "r,y,,,,!"
+            .parse::<Race>()
+            .expect("to parse")
+            .with_edition(Edition::Second);
+        let mut state = GameState::new(race, 3);
+        state
+            .decide(Action::BetOnWinner(Camel::Yellow))
+            .expect("to be legal");
+
+        let ranked = state.rank_actions(1);
+
+        let legal = state.legal_actions();
+        assert_eq!(ranked.len(), legal.len());
+        assert!(ranked.iter().all(|(action, _)| legal.contains(action)));
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(ranked.contains(&(Action::Partner(2), Fraction::zero())));
+    }
+}
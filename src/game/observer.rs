@@ -0,0 +1,113 @@
+//! Hooks into the engine, to follow a game as it is played.
+//!
+//! Loggers, user interfaces and statistics can implement `Observer` and pass it to `GameState::apply_observed` or `GameState::apply_roll_observed`. Every callback has an empty default, so an observer only implements what it is interested in.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::{observer::Observer, GameState};
+//! struct Rolls(Vec<Roll>);
+//!
+//! impl Observer for Rolls {
+//!     fn on_roll(&mut self, _state: &GameState, _player: usize, roll: Roll) {
+//!         self.0.push(roll);
+//!     }
+//! }
+//!
+//! let race = "r,y,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+//! let mut state = GameState::new(race, 2);
+//! let mut rolls = Rolls(Vec::new());
+//!
+//! state.apply_roll_observed((Camel::Red, Face::Two), &mut rolls).expect("to be legal");
+//!
+//! assert_eq!(rolls.0, vec![Roll::from((Camel::Red, Face::Two))]);
+//! ```
+use super::{GameState, LegSummary};
+use crate::{
+    advisor::Action,
+    camel::{Camel, Roll},
+};
+
+/// Callbacks the engine invokes while a game is played.
+///
+/// The state is passed as it is right after the event, before the turn passes to the next player.
+pub trait Observer {
+    /// The player rolled a die, the camels have moved.
+    fn on_roll(&mut self, _state: &GameState, _player: usize, _roll: Roll) {}
+
+    /// The leg ended and was scored. When the game is over, the summary includes the bets on the outcome of the race.
+    fn on_leg_end(&mut self, _state: &GameState, _summary: &LegSummary) {}
+
+    /// The player took a leg betting ticket or placed a bet on the overall winner or loser.
+    fn on_bet(&mut self, _state: &GameState, _player: usize, _action: Action) {}
+
+    /// The camel landed on the spectator tile of the owner, who received a coin.
+    fn on_trap_triggered(&mut self, _state: &GameState, _owner: usize, _camel: Camel) {}
+
+    /// The camel landed on the tile of the betting photographer, the owner received a coin.
+    fn on_photographed(&mut self, _state: &GameState, _owner: usize, _camel: Camel) {}
+}
+
+/// Observes nothing, for when nobody is watching.
+pub(crate) struct Unobserved;
+
+impl Observer for Unobserved {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bets::Ticket,
+        camel::{Face, Marker, Race},
+    };
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl Observer for Events {
+        fn on_roll(&mut self, _state: &GameState, player: usize, roll: Roll) {
+            self.0.push(format!("roll {} {}", player, roll));
+        }
+
+        fn on_leg_end(&mut self, _state: &GameState, summary: &LegSummary) {
+            self.0.push(format!("leg {}", summary.leg));
+        }
+
+        fn on_bet(&mut self, _state: &GameState, player: usize, action: Action) {
+            self.0.push(format!("bet {} {}", player, action));
+        }
+
+        fn on_trap_triggered(&mut self, _state: &GameState, owner: usize, camel: Camel) {
+            self.0.push(format!("trap {} {:?}", owner, camel));
+        }
+    }
+
+    #[test]
+    fn observers_hear_about_every_event() {
+        let race = "r,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+        let mut events = Events::default();
+
+        state
+            .decide_observed(
+                Action::TakeTicket(Ticket::from((Camel::Red, 5))),
+                &mut events,
+            )
+            .expect("to be legal");
+        state
+            .decide_observed(Action::PlaceTrap(2, Marker::FataMorgana), &mut events)
+            .expect("to be legal");
+        state
+            .apply_roll_observed((Camel::Red, Face::Two), &mut events)
+            .expect("to be legal");
+
+        assert_eq!(
+            events.0,
+            vec![
+                "bet 0 take Red ticket worth 5",
+                "roll 0 r2",
+                "trap 1 Red",
+                "leg 1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! The start of a game, following the official rules.
+//!
+//! Each camel has its die rolled, in the order the dice leave the pyramid, and is placed on the tile the die shows. Camels that land on the same tile stack in the order they are placed, so the last one is on top.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::setup::seeded;
+//! let state = seeded(4, Edition::Classic, 42).expect("to set up");
+//!
+//! assert_eq!(state.players.len(), 4);
+//! assert_eq!(state.pyramid.len(), 5);
+//! assert_eq!(state, seeded(4, Edition::Classic, 42).expect("to set up"));
+//! ```
+//!
+//! The second edition starts the crazy camels from the other end of the track. Races do not model crazy camels, see `Edition::has_crazy_camels`, so games with crazy camels can not be set up.
+use super::GameState;
+use crate::{
+    camel::{Camel, Marker, Race, RaceParseError, CAMELS},
+    rules::RuleSet,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+/// The number of tiles on the track, the finish lies beyond the last one.
+pub const TRACK_LENGTH: usize = 16;
+
+/// The problems with setting up a game.
+#[derive(PartialEq, Debug)]
+pub enum SetupError {
+    /// The edition of the rules has crazy camels, which races do not model.
+    CrazyCamels,
+    /// The camels start on the first three tiles, which do not fit a shorter track.
+    Track(RaceParseError),
+}
+
+impl Display for SetupError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SetupError::CrazyCamels => write!(f, "games with crazy camels can not be set up"),
+            SetupError::Track(problem) => write!(f, "the camels do not fit the track: {}", problem),
+        }
+    }
+}
+
+impl Error for SetupError {}
+
+/// Sets up a game for the number of players, rolling every camel onto one of the first three tiles.
+///
+/// The game follows the rules, or the rules of an edition. The track is as long as the rules say, with the finish beyond its last tile. Rules with crazy camels, and tracks too short to start on, are refused.
+pub fn setup<R, S>(players: usize, rules: S, rng: &mut R) -> Result<GameState, SetupError>
+where
+    R: Rng + ?Sized,
+    S: Into<RuleSet>,
+{
+    let rules = rules.into();
+    if rules.edition.has_crazy_camels() {
+        return Err(SetupError::CrazyCamels);
+    }
+    let mut order = CAMELS.to_vec();
+    order.shuffle(rng);
+    let mut tiles: Vec<Vec<Camel>> = vec![Vec::new(); rules.board_length.max(3)];
+    for camel in order {
+        tiles[rng.gen_range(0..3)].push(camel);
+    }
+    let mut positions: Vec<Marker> = Vec::new();
+    for (index, tile) in tiles.iter().enumerate() {
+        if index > 0 {
+            positions.push(Marker::Divider);
+        }
+        positions.extend(tile.iter().map(|camel| Marker::Camel(*camel)));
+    }
+    positions.push(Marker::Divider);
+    positions.push(Marker::Finish);
+
+    GameState::with_rules(Race::from(positions), players, rules).map_err(SetupError::Track)
+}
+
+/// Sets up a game like `setup`, with a random number generator seeded with the given seed.
+///
+/// The same seed always results in the same game.
+pub fn seeded<S>(players: usize, rules: S, seed: u64) -> Result<GameState, SetupError>
+where
+    S: Into<RuleSet>,
+{
+    setup(players, rules, &mut StdRng::seed_from_u64(seed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::camel::Edition;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn games_start_with_every_camel_on_the_first_tiles() {
+        for seed in 0..20 {
+            let state = seeded(2, Edition::Classic, seed).expect("to set up");
+
+            assert_eq!(state.race.edition(), Edition::Classic);
+            assert!(CAMELS
+                .iter()
+                .all(|camel| state.race.position_of(*camel).is_some_and(|tile| tile < 3)));
+        }
+    }
+
+    #[test]
+    fn the_finish_lies_beyond_the_track() {
+        let state = setup(2, Edition::Classic, &mut StepRng::new(0, 0)).expect("to set up");
+        let dividers = state
+            .race
+            .positions
+            .iter()
+            .filter(|marker| *marker == Marker::Divider)
+            .count();
+
+        assert!(state
+            .race
+            .positions
+            .first()
+            .is_some_and(|marker| marker.to_camel().is_some()));
+        assert_eq!(state.race.positions.last(), Some(Marker::Finish));
+        assert_eq!(dividers, TRACK_LENGTH);
+        assert_eq!(state.race.tiles(), TRACK_LENGTH);
+    }
+
+    #[test]
+    fn the_track_is_as_long_as_the_rules_say() {
+        let rules = RuleSet {
+            board_length: 8,
+            ..RuleSet::default()
+        };
+        let state = setup(2, rules.clone(), &mut StepRng::new(0, 0)).expect("to set up");
+        let dividers = state
+            .race
+            .positions
+            .iter()
+            .filter(|marker| *marker == Marker::Divider)
+            .count();
+
+        assert_eq!(state.rules, rules);
+        assert_eq!(dividers, 8);
+    }
+
+    #[test]
+    fn crazy_camels_can_not_be_set_up() {
+        assert_eq!(seeded(2, Edition::Second, 7), Err(SetupError::CrazyCamels));
+    }
+
+    #[test]
+    fn camels_need_room_to_start() {
+        let rules = RuleSet {
+            board_length: 2,
+            ..RuleSet::default()
+        };
+
+        assert_eq!(
+            setup(2, rules, &mut StepRng::new(0, 0)),
+            Err(SetupError::Track(RaceParseError::TooLong(3)))
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Strategies decide which action a player takes on their turn.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::game::strategy::{AlwaysRoll, GreedyLegEv, Strategy};
+//! # use rand::{rngs::StdRng, SeedableRng};
+//! let race = "r,y,,g,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+//! let mut state = GameState::new(race, 2);
+//! let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(GreedyLegEv), Box::new(AlwaysRoll)];
+//! let mut rng = StdRng::seed_from_u64(42);
+//!
+//! while !state.is_over() {
+//!     let player = state.current;
+//!     let action = strategies[player].choose(&state, player);
+//!     state.apply(action, &mut rng).expect("to be legal");
+//! }
+//! ```
+use super::GameState;
+use crate::{
+    advisor::{rank, Action},
+    camel::{Camel, Face, Roll},
+    fraction::Fraction,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// Decides on the action of a player.
+pub trait Strategy {
+    /// Chooses one of the legal actions for the player, whose turn it is.
+    fn choose(&mut self, state: &GameState, player: usize) -> Action;
+}
+
+/// Picks any of the legal actions.
+pub struct Random<R> {
+    rng: R,
+}
+
+impl<R> Random<R>
+where
+    R: Rng,
+{
+    /// A strategy that picks its actions with the random number generator.
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+impl<R> Strategy for Random<R>
+where
+    R: Rng,
+{
+    fn choose(&mut self, state: &GameState, _player: usize) -> Action {
+        *state
+            .legal_actions()
+            .choose(&mut self.rng)
+            .unwrap_or(&Action::Roll)
+    }
+}
+
+/// Takes the leg betting ticket with the best expected value, or rolls when that is better.
+///
+/// The oracle projects the leg for every decision.
+pub struct GreedyLegEv;
+
+impl Strategy for GreedyLegEv {
+    fn choose(&mut self, state: &GameState, _player: usize) -> Action {
+        let leg = state.chances();
+        rank(&leg, &state.available_tickets(), None)[0].0
+    }
+}
+
+/// Like `GreedyLegEv`, but considers bets on the overall winner and loser as well.
+///
+/// The chances for the race are estimated by sampling complete races, so the race needs a finish.
+pub struct GreedyRaceEv<R> {
+    rng: R,
+    samples: usize,
+}
+
+impl<R> GreedyRaceEv<R>
+where
+    R: Rng,
+{
+    /// A strategy that samples the given number of races for every decision.
+    pub fn new(rng: R, samples: usize) -> Self {
+        Self { rng, samples }
+    }
+}
+
+impl<R> Strategy for GreedyRaceEv<R>
+where
+    R: Rng,
+{
+    fn choose(&mut self, state: &GameState, _player: usize) -> Action {
+        let leg = state.chances();
+        let race = state.race_chances(self.samples, &mut self.rng);
+        let legal = state.legal_actions();
+        rank(&leg, &state.available_tickets(), race.as_ref())
+            .into_iter()
+            .map(|(action, _)| action)
+            .find(|action| legal.contains(action))
+            .unwrap_or(Action::Roll)
+    }
+}
+
+/// Always rolls a die.
+pub struct AlwaysRoll;
+
+impl Strategy for AlwaysRoll {
+    fn choose(&mut self, _state: &GameState, _player: usize) -> Action {
+        Action::Roll
+    }
+}
+
+/// Looks ahead over its own actions and the dice, taking the action with the best expected outcome.
+///
+/// The search alternates between decisions of the player, where the best action is taken, and rolls of the dice, which are averaged. The other players are assumed to roll on their turn. After `depth` turns, the state is valued by the coins of the player, their pyramid tickets and the expected value of their leg betting tickets according to the oracle.
+///
+/// Only rolling and taking leg betting tickets are considered. Overall bets and spectator tiles pay off over several legs, beyond what the search sees.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::advisor::Action;
+/// # use camel_up::game::strategy::{Expectimax, Strategy};
+/// let race = "r,,,,y".parse::<Race>().expect("to parse");
+/// let state = GameState::new(race, 2);
+///
+/// let action = Expectimax::new(2).choose(&state, 0);
+///
+/// assert!(matches!(action, Action::TakeTicket(ticket) if ticket.camel == Camel::Yellow));
+/// ```
+pub struct Expectimax {
+    depth: usize,
+}
+
+impl Expectimax {
+    /// A strategy that looks ahead the given number of turns, at least one.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth: depth.max(1),
+        }
+    }
+}
+
+impl Strategy for Expectimax {
+    fn choose(&mut self, state: &GameState, player: usize) -> Action {
+        let mut best = (Action::Roll, None);
+        for action in candidates(state) {
+            let value = after(state, action, player, self.depth);
+            if best.1.is_none_or(|best| value > best) {
+                best = (action, Some(value));
+            }
+        }
+        best.0
+    }
+}
+
+fn candidates(state: &GameState) -> Vec<Action> {
+    let mut actions = vec![Action::Roll];
+    actions.extend(
+        state
+            .available_tickets()
+            .into_iter()
+            .map(Action::TakeTicket),
+    );
+    actions
+}
+
+/// The value of the state for the player, looking ahead the given number of turns.
+fn value(state: &GameState, player: usize, turns: usize) -> Fraction {
+    if turns == 0 || state.is_over() {
+        return worth(state, player);
+    }
+    if state.current == player {
+        candidates(state)
+            .into_iter()
+            .map(|action| after(state, action, player, turns))
+            .max()
+            .unwrap_or_else(|| worth(state, player))
+    } else {
+        after(state, Action::Roll, player, turns)
+    }
+}
+
+/// The value for the player after the current player takes the action, as the first of the given number of turns.
+fn after(state: &GameState, action: Action, player: usize, turns: usize) -> Fraction {
+    if action != Action::Roll {
+        let mut next = state.clone();
+        next.decide(action).expect("candidates to be legal");
+        return value(&next, player, turns - 1);
+    }
+    let mut dice: Vec<Camel> = state.pyramid.iter().collect();
+    dice.sort();
+    let mut values = Vec::new();
+    for camel in dice {
+        for face in [Face::One, Face::Two, Face::Three] {
+            let mut next = state.clone();
+            next.apply_roll(Roll::from((camel, face)))
+                .expect("dice in the pyramid to be rollable");
+            values.push(value(&next, player, turns - 1));
+        }
+    }
+    let outcomes = values.len() as i64;
+    (values.into_iter().sum::<Fraction>() / Fraction::from(outcomes))
+        .unwrap(/* the pyramid is refilled at the end of every leg */)
+}
+
+/// The coins of the player, including what they can expect from the current leg.
+fn worth(state: &GameState, player: usize) -> Fraction {
+    let holdings = &state.players[player];
+    let coins = Fraction::from(holdings.coins + holdings.pyramid_tickets as i64);
+    if holdings.tickets.is_empty() || state.is_over() {
+        return coins;
+    }
+    let leg = state.chances();
+    holdings
+        .tickets
+        .iter()
+        .fold(coins, |coins, ticket| coins + ticket.expected_value(&leg))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bets::Ticket, camel::Race};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn greedy_takes_the_ticket_of_the_certain_winner() {
+        let race = "r,,,,y".parse::<Race>().expect("to parse");
+        let state = GameState::new(race, 2);
+
+        let action = GreedyLegEv.choose(&state, 0);
+
+        assert_eq!(action, Action::TakeTicket(Ticket::from((Camel::Yellow, 5))));
+    }
+
+    #[test]
+    fn random_picks_legal_actions() {
+        let race = "r,y,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+        let mut state = GameState::new(race, 2);
+        let mut strategy = Random::new(StdRng::seed_from_u64(7));
+        let mut rng = StdRng::seed_from_u64(7);
+
+        while !state.is_over() {
+            let player = state.current;
+            let action = strategy.choose(&state, player);
+            state.apply(action, &mut rng).expect("to be legal");
+        }
+    }
+
+    #[test]
+    fn searching_a_single_turn_agrees_with_greedy() {
+        let race = "r,y,,g".parse::<Race>().expect("to parse");
+        let state = GameState::new(race, 3);
+
+        let action = Expectimax::new(1).choose(&state, 0);
+
+        assert_eq!(action, GreedyLegEv.choose(&state, 0));
+    }
+}
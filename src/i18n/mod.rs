@@ -0,0 +1,167 @@
+//! Names of camels and labels of chances in the languages of the players.
+//!
+//! The game is played in many languages, so every text that is shown to players can be asked for in a `Language`. Parsing accepts the language code or the English name of the language.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::i18n::{Label, Language};
+//! let language = "nl".parse::<Language>().expect("to parse");
+//!
+//! assert_eq!(language.camel(Camel::Yellow), "Geel");
+//! assert_eq!(language.label(Label::RunnerUp), "tweede");
+//! ```
+use crate::camel::Camel;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// The languages texts can be shown in.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum Language {
+    /// English, the language of the crate.
+    #[default]
+    English,
+    /// Dutch.
+    Dutch,
+    /// German.
+    German,
+}
+
+/// The labels that accompany chances.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Label {
+    /// The heading of a column of camels.
+    Camel,
+    /// The heading of the winning chances.
+    Winner,
+    /// The heading of the runner up chances.
+    RunnerUp,
+    /// The heading of the losing chances.
+    Loser,
+    /// The winning chance of a single camel.
+    Win,
+    /// The chance of a single camel to end last.
+    Last,
+}
+
+impl Language {
+    /// The name of the camel, by its color.
+    pub fn camel(&self, camel: Camel) -> &'static str {
+        match (self, camel) {
+            (Language::English, Camel::Red) => "Red",
+            (Language::English, Camel::Orange) => "Orange",
+            (Language::English, Camel::Yellow) => "Yellow",
+            (Language::English, Camel::Green) => "Green",
+            (Language::English, Camel::White) => "White",
+            (Language::Dutch, Camel::Red) => "Rood",
+            (Language::Dutch, Camel::Orange) => "Oranje",
+            (Language::Dutch, Camel::Yellow) => "Geel",
+            (Language::Dutch, Camel::Green) => "Groen",
+            (Language::Dutch, Camel::White) => "Wit",
+            (Language::German, Camel::Red) => "Rot",
+            (Language::German, Camel::Orange) => "Orange",
+            (Language::German, Camel::Yellow) => "Gelb",
+            (Language::German, Camel::Green) => "Grün",
+            (Language::German, Camel::White) => "Weiß",
+        }
+    }
+
+    /// The text of the label.
+    pub fn label(&self, label: Label) -> &'static str {
+        match (self, label) {
+            (Language::English, Label::Camel) => "camel",
+            (Language::English, Label::Winner) => "winner",
+            (Language::English, Label::RunnerUp) => "runner up",
+            (Language::English, Label::Loser) => "loser",
+            (Language::English, Label::Win) => "win",
+            (Language::English, Label::Last) => "last",
+            (Language::Dutch, Label::Camel) => "kameel",
+            (Language::Dutch, Label::Winner) => "winnaar",
+            (Language::Dutch, Label::RunnerUp) => "tweede",
+            (Language::Dutch, Label::Loser) => "verliezer",
+            (Language::Dutch, Label::Win) => "winst",
+            (Language::Dutch, Label::Last) => "laatste",
+            (Language::German, Label::Camel) => "Kamel",
+            (Language::German, Label::Winner) => "Sieger",
+            (Language::German, Label::RunnerUp) => "Zweiter",
+            (Language::German, Label::Loser) => "Verlierer",
+            (Language::German, Label::Win) => "Sieg",
+            (Language::German, Label::Last) => "Letzter",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = NoLanguage;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "en" | "english" => Ok(Language::English),
+            "nl" | "dutch" => Ok(Language::Dutch),
+            "de" | "german" => Ok(Language::German),
+            _ => Err(NoLanguage::But(input.to_owned())),
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let code = match self {
+            Language::English => "en",
+            Language::Dutch => "nl",
+            Language::German => "de",
+        };
+        write!(f, "{}", code)
+    }
+}
+
+/// Reasons why a language could not be parsed.
+#[derive(PartialEq, Eq, Debug)]
+pub enum NoLanguage {
+    /// The input is not one of the known languages.
+    But(String),
+}
+
+impl Display for NoLanguage {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NoLanguage::But(input) => {
+                write!(f, "'{}' is not a language, use en, nl or de", input)
+            }
+        }
+    }
+}
+
+impl Error for NoLanguage {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn english_names_are_the_names_of_the_colors() {
+        for camel in [
+            Camel::Red,
+            Camel::Orange,
+            Camel::Yellow,
+            Camel::Green,
+            Camel::White,
+        ] {
+            assert_eq!(Language::English.camel(camel), format!("{:?}", camel));
+        }
+    }
+
+    #[test]
+    fn languages_are_parsed_from_their_code_or_name() {
+        for language in [Language::English, Language::Dutch, Language::German] {
+            assert_eq!(language.to_string().parse::<Language>(), Ok(language));
+        }
+        assert_eq!("german".parse::<Language>(), Ok(Language::German));
+        assert_eq!(
+            "fr".parse::<Language>(),
+            Err(NoLanguage::But("fr".to_owned()))
+        );
+    }
+}
@@ -33,30 +33,85 @@
 //! | -      | FataMorgana |
 //! | !      | Finish      |
 //!
-//! As per the rules of the game, camels can not be in a oasis or a fata morgana, nor can either of those be next to each other. So the following strings all fail to parse.
+//! Camels between the same dividers share a tile and form a stack. They are written from the bottom of the stack to the top, so in `"gyor"` green is at the bottom and red is on top. The camel on top is ahead of the camels below it, see `camel::Stack`.
+//!
+//! ```
+//! use camel_up::prelude::*;
+//! let race = "gyor".parse::<Race>().expect("to parse");
+//! assert_eq!(race.winner(), Some(Camel::Red));
+//! assert_eq!(race.stack_at(0).bottom(), Some(Camel::Green));
+//! ```
+//!
+//! As per the rules of the game, camels can not be in a oasis or a fata morgana, nor can either of those be next to each other. Neither can be on the first tile of the track, which a race never shows, because it starts at its rearmost camel or trap. So the following strings all fail to parse.
 //!
 //! ```
 //! use camel_up::camel::*;
-//! assert_eq!("r|y".parse::<Race>(), Err(RaceParseError::NotAMarker(NotAMarker::But("|".to_owned()))));
-//! assert_eq!("+r,y".parse::<Race>(), Err(RaceParseError::CamelInOasis));
-//! assert_eq!("-r,y".parse::<Race>(), Err(RaceParseError::CamelInFataMorgana));
-//! assert_eq!("r,-+,y".parse::<Race>(), Err(RaceParseError::ToManyAdjustmentsInOnePosition));
-//! assert_eq!("r,-,+,y".parse::<Race>(), Err(RaceParseError::ConsecutiveAdjustments));
+//! assert_eq!("r|y".parse::<Race>(), Err(RaceParseError::NotAMarker(1, NotAMarker::But("|".to_owned()))));
+//! assert_eq!("+r,y".parse::<Race>(), Err(RaceParseError::CamelInOasis(0)));
+//! assert_eq!("-r,y".parse::<Race>(), Err(RaceParseError::CamelInFataMorgana(0)));
+//! assert_eq!("r,-+,y".parse::<Race>(), Err(RaceParseError::ToManyAdjustmentsInOnePosition(3)));
+//! assert_eq!("r,-,+,y".parse::<Race>(), Err(RaceParseError::ConsecutiveAdjustments(4)));
+//! ```
+//!
+//! The errors carry the position, counted in characters, of the offending symbol. They can be displayed as well.
+//!
+//! ```
+//! use camel_up::camel::*;
+//! let error = "r,-+,y".parse::<Race>().unwrap_err();
+//! assert_eq!(error.position(), 3);
+//! assert_eq!(error.to_string(), "adjustments can't be in the same position");
 //! ```
 //!
 //! ## Parsing of Dice
 //! Dice can be similarly parsed. The only allowed symbols are the ones for the camels.
 
+pub mod advisor;
+pub mod bets;
 pub mod camel;
+#[cfg(feature = "conformance")]
+pub mod conformance;
 pub mod fraction;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+pub mod game;
+pub mod i18n;
+pub mod notation;
 pub mod oracle;
+pub mod query;
+pub mod rules;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "spreadsheet")]
+pub mod spreadsheet;
+pub mod test_vectors;
 mod tree;
+#[cfg(feature = "vis")]
 pub mod vis;
+pub mod wire;
+
+pub use wire::oracle_json;
 
 pub mod prelude {
     //! Easy access to a good combination of camel up related concepts.
+    //!
+    //! The prelude holds what is needed to describe a race, ask the oracle about it and play a game.
+    //!
+    //! ```
+    //! use camel_up::prelude::*;
+    //! let race = "r,,w".parse::<Race>().expect("to parse");
+    //! let dice = "rw".parse::<Dice>().expect("to parse");
+    //!
+    //! let chances: LegChances = project(&race, &dice);
+    //! let ticket = Ticket::from((Camel::White, 5));
+    //!
+    //! assert!(ticket.expected_value(&chances) > Fraction::zero());
+    //! ```
 
-    pub use crate::camel::{Camel, Dice, Race};
+    pub use crate::bets::Ticket;
+    pub use crate::camel::{Camel, Dice, Edition, Face, Marker, Race, Roll};
     pub use crate::fraction::Fraction;
-    pub use crate::oracle::project;
+    pub use crate::game::GameState;
+    pub use crate::i18n::Language;
+    pub use crate::oracle::{project, sample, Distribution, LegChances, RaceChances};
+    pub use crate::rules::RuleSet;
 }
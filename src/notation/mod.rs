@@ -0,0 +1,217 @@
+//! Letters for the camels other than the standard ones.
+//!
+//! The race notation uses `r`, `o`, `y`, `g` and `w` for the camels. Editions of the game in other languages, or with other colors, are easier to follow with different letters. A `Notation` maps between the letters of the user and the standard ones, so that everything else can keep using the standard notation.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::notation::Notation;
+//! let notation = "r=b,w=s".parse::<Notation>().expect("to parse");
+//!
+//! let race = notation.parse_race("b,,s").expect("to parse");
+//!
+//! assert_eq!(race, "r,,w".parse::<Race>().expect("to parse"));
+//! assert_eq!(notation.localize(&race.to_string()), "b,,s");
+//! ```
+use crate::camel::{Camel, Dice, NoDice, NotAMarker, Race, RaceParseError};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+const CAMELS: [Camel; 5] = [
+    Camel::Red,
+    Camel::Orange,
+    Camel::Yellow,
+    Camel::Green,
+    Camel::White,
+];
+const STANDARD: [char; 5] = ['r', 'o', 'y', 'g', 'w'];
+const RESERVED: [char; 4] = [',', '+', '-', '!'];
+
+/// The letter for each camel.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Notation {
+    letters: [char; 5],
+}
+
+impl Default for Notation {
+    fn default() -> Self {
+        Self { letters: STANDARD }
+    }
+}
+
+impl Notation {
+    /// The letter used for the camel.
+    pub fn letter(&self, camel: Camel) -> char {
+        self.letters[index(camel)]
+    }
+
+    /// The camel the letter stands for, if any.
+    pub fn camel(&self, letter: char) -> Option<Camel> {
+        self.letters
+            .iter()
+            .position(|candidate| *candidate == letter)
+            .map(|index| CAMELS[index])
+    }
+
+    /// Translates the input to the standard notation.
+    ///
+    /// Standard letters that are replaced by other letters are rejected, with their position, so that a mix of notations is noticed.
+    pub fn standardize(&self, input: &str) -> Result<String, (usize, char)> {
+        input
+            .chars()
+            .enumerate()
+            .map(|(position, symbol)| match self.camel(symbol) {
+                Some(camel) => Ok(STANDARD[index(camel)]),
+                None if STANDARD.contains(&symbol) => Err((position, symbol)),
+                None => Ok(symbol),
+            })
+            .collect()
+    }
+
+    /// Translates output in the standard notation to the letters of this notation.
+    pub fn localize(&self, output: &str) -> String {
+        output
+            .chars()
+            .map(
+                |symbol| match STANDARD.iter().position(|letter| *letter == symbol) {
+                    Some(index) => self.letters[index],
+                    None => symbol,
+                },
+            )
+            .collect()
+    }
+
+    /// Parses a race written in this notation.
+    pub fn parse_race(&self, input: &str) -> Result<Race, RaceParseError> {
+        self.standardize(input)
+            .map_err(|(position, symbol)| {
+                RaceParseError::NotAMarker(position, NotAMarker::But(symbol.to_string()))
+            })?
+            .parse::<Race>()
+    }
+
+    /// Parses dice written in this notation.
+    pub fn parse_dice(&self, input: &str) -> Result<Dice, NoDice> {
+        self.standardize(input)
+            .map_err(|(position, symbol)| {
+                NoDice::NotAMarker(position, NotAMarker::But(symbol.to_string()))
+            })?
+            .parse::<Dice>()
+    }
+}
+
+fn index(camel: Camel) -> usize {
+    CAMELS
+        .iter()
+        .position(|candidate| *candidate == camel)
+        .unwrap(/* every camel is in CAMELS */)
+}
+
+impl FromStr for Notation {
+    type Err = NotationParseError;
+
+    /// Parses comma separated pairs of a standard letter and its replacement, e.g. `r=b,w=s`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut letters = STANDARD;
+        for pair in input.split(',').filter(|pair| !pair.is_empty()) {
+            let symbols: Vec<char> = pair.chars().collect();
+            let (camel, letter) = match symbols.as_slice() {
+                [camel, '=', letter] => (*camel, *letter),
+                _ => return Err(NotationParseError::NotAPair(pair.to_owned())),
+            };
+            let index = STANDARD
+                .iter()
+                .position(|standard| *standard == camel)
+                .ok_or(NotationParseError::NotACamel(camel))?;
+            if RESERVED.contains(&letter) {
+                return Err(NotationParseError::Reserved(letter));
+            }
+            letters[index] = letter;
+        }
+        for (index, letter) in letters.iter().enumerate() {
+            if letters[index + 1..].contains(letter) {
+                return Err(NotationParseError::Duplicate(*letter));
+            }
+        }
+        Ok(Self { letters })
+    }
+}
+
+/// When parsing of a Notation goes wrong, this enumeration tells you precisely what went down.
+#[derive(PartialEq, Debug)]
+pub enum NotationParseError {
+    /// The argument is not of the form `r=b`.
+    NotAPair(String),
+    /// The letter before the `=` is not one of the standard camel letters.
+    NotACamel(char),
+    /// The letter is already used for something other than a camel.
+    Reserved(char),
+    /// The letter would be used for more than one camel.
+    Duplicate(char),
+}
+
+impl Display for NotationParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NotationParseError::NotAPair(pair) => write!(f, "'{}' is not of the form r=b", pair),
+            NotationParseError::NotACamel(letter) => {
+                write!(f, "'{}' is not one of the camels r, o, y, g or w", letter)
+            }
+            NotationParseError::Reserved(letter) => {
+                write!(f, "'{}' is already used by the race notation", letter)
+            }
+            NotationParseError::Duplicate(letter) => {
+                write!(f, "'{}' would be used for more than one camel", letter)
+            }
+        }
+    }
+}
+
+impl Error for NotationParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn letters_can_be_swapped() {
+        let notation = "r=y,y=r".parse::<Notation>().expect("to parse");
+
+        assert_eq!(notation.letter(Camel::Red), 'y');
+        assert_eq!(notation.standardize("y,r"), Ok("r,y".to_owned()));
+    }
+
+    #[test]
+    fn replaced_letters_are_rejected_with_their_position() {
+        let notation = "r=b".parse::<Notation>().expect("to parse");
+
+        assert_eq!(
+            notation.parse_race("b,,r"),
+            Err(RaceParseError::NotAMarker(
+                3,
+                NotAMarker::But("r".to_owned())
+            ))
+        );
+    }
+
+    #[test]
+    fn letters_should_be_unique() {
+        assert_eq!(
+            "r=y".parse::<Notation>(),
+            Err(NotationParseError::Duplicate('y'))
+        );
+        assert_eq!(
+            "r=+".parse::<Notation>(),
+            Err(NotationParseError::Reserved('+'))
+        );
+    }
+
+    #[test]
+    fn dice_can_be_parsed() {
+        let notation = "g=d".parse::<Notation>().expect("to parse");
+
+        assert_eq!(notation.parse_dice("rd"), "rg".parse::<Dice>());
+    }
+}
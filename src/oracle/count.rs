@@ -0,0 +1,162 @@
+//! The integers the oracle counts leaves in.
+//!
+//! A leg with `n` dice has `n! * 3^n` leaves. The oracle counts how many of them end in each place for each camel, and only turns the counts into fractions at the end. A `Count` is wide enough when the number of leaves fits, so that the chances stay exact. Counting in `u64` is plenty for the dice of a single leg, `u128` and, with the `bigint` feature, `BigUint` leave room for larger variants of the game.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::oracle::project_count_only_in;
+//! let race = "r,,w".parse::<Race>().expect("to parse");
+//! let dice = "rw".parse::<Dice>().expect("to parse");
+//!
+//! let chances = project_count_only_in::<u128>(&race, &dice).expect("the chances to fit");
+//!
+//! assert_eq!(chances.winner[&Camel::White], project(&race, &dice).winner[&Camel::White]);
+//! ```
+use crate::fraction::Fraction;
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    ops::{AddAssign, Div, Mul, Rem},
+};
+
+/// An unsigned integer to count leaves in.
+pub trait Count:
+    Clone
+    + Default
+    + Eq
+    + AddAssign
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Send
+    + Sync
+{
+    /// The count of this many leaves.
+    fn of(leaves: usize) -> Self;
+
+    /// The count, when it fits in 64 bits.
+    fn to_u64(&self) -> Option<u64>;
+
+    /// Whether nothing is counted.
+    fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The count as a fraction of the total, when the fraction in lowest terms fits a `Fraction`.
+    ///
+    /// Panics when the total is zero.
+    fn ratio(&self, total: &Self) -> Result<Fraction, Overflow> {
+        let gcd = gcd(self.clone(), total.clone());
+        let numerator = (self.clone() / gcd.clone())
+            .to_u64()
+            .and_then(|numerator| i64::try_from(numerator).ok())
+            .ok_or(Overflow)?;
+        let denominator = (total.clone() / gcd).to_u64().ok_or(Overflow)?;
+        Ok(Fraction::new(numerator, denominator))
+    }
+}
+
+/// A chance that does not fit a `Fraction`, even in lowest terms.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "the chance does not fit a fraction")
+    }
+}
+
+impl Error for Overflow {}
+
+macro_rules! primitive_count {
+    ($($count:ty),*) => {
+        $(
+            impl Count for $count {
+                fn of(leaves: usize) -> Self {
+                    leaves as $count
+                }
+
+                fn to_u64(&self) -> Option<u64> {
+                    u64::try_from(*self).ok()
+                }
+            }
+        )*
+    };
+}
+
+primitive_count!(usize, u64, u128);
+
+#[cfg(feature = "bigint")]
+impl Count for num_bigint::BigUint {
+    fn of(leaves: usize) -> Self {
+        Self::from(leaves)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self).ok()
+    }
+}
+
+/// The number of leaves below a node with the given number of dice left in the pyramid.
+///
+/// Every die can be rolled next, with one of three faces.
+pub(super) fn leaves<C>(dice: usize) -> C
+where
+    C: Count,
+{
+    (1..=dice).fold(C::of(1), |leaves, remaining| leaves * C::of(remaining * 3))
+}
+
+fn gcd<C>(mut a: C, mut b: C) -> C
+where
+    C: Count,
+{
+    while !b.is_zero() {
+        let remainder = a % b.clone();
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_of_any_width_agree() {
+        assert_eq!(leaves::<u64>(5), 29_160);
+        assert_eq!(leaves::<u128>(5), 29_160);
+        assert_eq!(6u64.ratio(&leaves(2)), u128::of(6).ratio(&leaves(2)));
+    }
+
+    #[test]
+    fn chances_that_do_not_fit_a_fraction_overflow() {
+        let total = u128::from(u64::MAX) * 3;
+
+        assert_eq!(2u128.ratio(&total), Err(Overflow));
+        assert_eq!(total.ratio(&(total * 2)), Ok(Fraction::new(1, 2)));
+    }
+
+    #[test]
+    fn wide_counts_are_exact_when_their_ratio_fits() {
+        let total = leaves::<u128>(20);
+        let count = total / 3;
+
+        assert!(total > u128::from(u64::MAX));
+        assert_eq!(count.ratio(&total), Ok(Fraction::new(1, 3)));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_counts_go_beyond_any_primitive() {
+        use num_bigint::BigUint;
+
+        let total = leaves::<BigUint>(30);
+        let count = total.clone() * BigUint::of(2) / BigUint::of(9);
+
+        assert_eq!(total.to_u64(), None);
+        assert_eq!(count.ratio(&total), Ok(Fraction::new(2, 9)));
+    }
+}
@@ -3,55 +3,1171 @@
 //! > a person or agency considered to provide wise and insightful counsel or prophetic predictions or precognition of the future, inspired by the gods. As such it is a form of divination.
 //!
 //! We divine by way of mathematics.
+//!
+//! With the `tracing` feature the oracle is instrumented with [tracing](https://docs.rs/tracing) spans, so that integrators can see where the time goes with the subscriber of their choice.
+//!
+//! | Span           | Level | Fields                       |
+//! |----------------|-------|------------------------------|
+//! | `project`      | info  | race, dice, nodes and leaves |
+//! | `expand`       | debug | dice, nodes                  |
+//! | `count_leaves` | debug | leaves                       |
+//! | `sample`       | info  | race, dice and samples       |
+//!
+//! The dice the oracle is asked about should be of camels in the race. A die of a camel that is not in the race would roll without moving anything and skew every chance, so the oracle panics on it instead. So it does on a race without room for the markers its camels move into during the leg. Check races and dice that come from elsewhere with `Race::check_leg`, or project them with `project_checked`.
+//!
+//! While the tree of rolls is expanded, a trace event reports the number of nodes every so often. Positions that occur more than once are expanded each time. To reuse the outcome of rolls between projections, see `Transitions`.
+mod count;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod permutations;
+mod transitions;
+
+pub use count::{Count, Overflow};
+#[cfg(feature = "parallel")]
+pub use parallel::project_parallel;
+pub use permutations::Permutations;
+pub use transitions::Transitions;
+
 use crate::{
-    camel::{Camel, Dice, Race},
+    camel::{Camel, Dice, Face, Marker, MoveError, Race, Roll, CAMELS, CAPACITY, DISTINCT_PLACES},
     fraction::Fraction,
-    tree::{LeafVisitor, Tree},
+    tree::{LeafVisitor, ReducibleVisitor, Tree, NODE_BYTES},
+};
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    iter::{once, repeat_n, Iterator},
+    ops::Index,
+    time::{Duration, Instant},
 };
-use std::{collections::HashMap, iter::Iterator, ops::Index};
 
 /// Determines the win chances for each camel.
 ///
 /// The `Distribution` returns for each camel present in the race, the chance of winning.
-pub fn project(race: &Race, dice: &Dice) -> Chances {
-    let mut tree = Tree::singleton(race.clone());
-    tree.expand(dice);
+pub fn project(race: &Race, dice: &Dice) -> LegChances {
+    project_with_progress(race, dice, |_, _| {})
+}
+
+/// Determines the win chances for each camel, like `project`, when the dice can be rolled on the race for a whole leg.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::camel::MoveError;
+/// # use camel_up::oracle::project_checked;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "rw".parse::<Dice>().expect("to parse");
+///
+/// assert_eq!(project_checked(&race, &dice).err(), Some(MoveError::CamelNotInRace(Camel::White)));
+/// ```
+pub fn project_checked(race: &Race, dice: &Dice) -> Result<LegChances, MoveError> {
+    race.check_leg(dice)?;
+    Ok(project(race, dice))
+}
+
+/// Determines the win chances for each camel, like `project`, while reporting progress.
+///
+/// Every so often `progress` is called with the number of nodes built so far and the total number of nodes, see `tree_size`. This allows one to show that a long computation is still going.
+pub fn project_with_progress<P>(race: &Race, dice: &Dice, progress: P) -> LegChances
+where
+    P: FnMut(u64, u64),
+{
+    project_with_statistics(race, dice, progress).0
+}
+
+/// What a projection did, to find out where the time goes.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct Statistics {
+    /// The number of nodes expanded in the tree of rolls.
+    pub nodes: u64,
+    /// The number of leaves counted, i.e. the number of ways the leg can play out.
+    pub leaves: u64,
+    /// The time spent expanding the tree.
+    pub expansion: Duration,
+    /// The time spent counting the leaves.
+    pub counting: Duration,
+}
+
+/// Determines the win chances for each camel, like `project_with_progress`, together with `Statistics` of the projection.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::project_with_statistics;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "ry".parse::<Dice>().expect("to parse");
+///
+/// let (_, statistics) = project_with_statistics(&race, &dice, |_, _| {});
+///
+/// assert_eq!(statistics.nodes, 1 + 2 * 3 + 2 * 3 * 3);
+/// assert_eq!(statistics.leaves, 2 * 3 * 3);
+/// ```
+pub fn project_with_statistics<P>(
+    race: &Race,
+    dice: &Dice,
+    mut progress: P,
+) -> (LegChances, Statistics)
+where
+    P: FnMut(u64, u64),
+{
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "project",
+        race = %race,
+        dice = %dice,
+        nodes = tracing::field::Empty,
+        leaves = tracing::field::Empty
+    )
+    .entered();
+    let start = Instant::now();
+    let total = tree_size(dice);
+    let mut tree = Tree::singleton(*race);
+    {
+        #[cfg(feature = "tracing")]
+        let span =
+            tracing::debug_span!("expand", dice = %dice, nodes = tracing::field::Empty).entered();
+        tree.expand_with_progress(dice, &mut |nodes| progress(nodes as u64, total));
+        #[cfg(feature = "tracing")]
+        span.record("nodes", tree.size());
+    }
+    let expanded = Instant::now();
+
+    let mut counter: LeafCounter = Default::default();
+    {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("count_leaves", leaves = tracing::field::Empty).entered();
+        tree.visit_leaves(&mut counter);
+        #[cfg(feature = "tracing")]
+        span.record("leaves", counter.total);
+    }
+    let chances = counter.chances();
+    #[cfg(feature = "tracing")]
+    {
+        span.record("nodes", tree.size());
+        span.record("leaves", counter.total);
+    }
+
+    let statistics = Statistics {
+        nodes: tree.size() as u64,
+        leaves: counter.total as u64,
+        expansion: expanded - start,
+        counting: expanded.elapsed(),
+    };
+    (chances, statistics)
+}
+
+/// Determines the win chances for each camel, like `project`, by counting the places of the camels while the rolls are performed.
+///
+/// No tree is built and no race is kept after its places are counted, so the memory it takes only grows with the number of dice.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::project_count_only;
+/// let race = "r,,w".parse::<Race>().expect("to parse");
+/// let dice = "rw".parse::<Dice>().expect("to parse");
+///
+/// let chances = project_count_only(&race, &dice);
+///
+/// assert_eq!(chances.winner[&Camel::White], project(&race, &dice).winner[&Camel::White]);
+/// ```
+pub fn project_count_only(race: &Race, dice: &Dice) -> LegChances {
+    project_count_only_in::<u64>(race, dice).expect("the leaves of a leg to fit 64 bits")
+}
+
+/// Determines the win chances for each camel, like `project_count_only`, counting the leaves in `C`.
+///
+/// The leaves of a leg with many dice overflow a narrow count, see the `Count` trait for the counts to choose from. Errors when a chance in lowest terms does not fit a `Fraction`.
+pub fn project_count_only_in<C>(race: &Race, dice: &Dice) -> Result<LegChances, Overflow>
+where
+    C: Count,
+{
+    let mut tally = Tally::<C>::default();
+    tally.count(race, dice);
+    tally.chances()
+}
+
+/// The number of times each camel ends up in each place, indexed by the place and then the camel.
+#[derive(Default)]
+struct Tally<C> {
+    total: C,
+    places: Vec<[C; CAMELS.len()]>,
+}
+
+impl<C> Tally<C>
+where
+    C: Count,
+{
+    /// Counts the leaves below the race, all at once when the ranking can no longer change.
+    fn count(&mut self, race: &Race, dice: &Dice) {
+        if dice.is_empty() || is_decided(race, dice) {
+            let leaves = count::leaves::<C>(dice.len());
+            let ranking = race
+                .positions
+                .iter()
+                .rev()
+                .filter_map(|marker| marker.to_camel());
+            for (place, camel) in ranking.enumerate() {
+                if self.places.len() <= place {
+                    self.places.push(Default::default());
+                }
+                self.places[place][camel as usize] += leaves.clone();
+            }
+            self.total += leaves;
+            return;
+        }
+        for camel in dice {
+            let remaining_dice = dice.remove(camel);
+            for face in Face::values() {
+                self.count(&perform(race, (camel, face)), &remaining_dice);
+            }
+        }
+    }
+
+    fn chances(&self) -> Result<LegChances, Overflow> {
+        let distribution = |counts: &[C; CAMELS.len()]| {
+            let chances = CAMELS
+                .iter()
+                .filter(|camel| !counts[**camel as usize].is_zero())
+                .map(|camel| Ok((*camel, counts[*camel as usize].ratio(&self.total)?)))
+                .collect::<Result<HashMap<Camel, Fraction>, Overflow>>()?;
+            Ok(Distribution::from(chances))
+        };
+        let place = |counts: Option<&[C; CAMELS.len()]>| {
+            counts
+                .map(distribution)
+                .unwrap_or_else(|| Ok(Distribution::from(HashMap::new())))
+        };
+        Ok(LegChances {
+            winner: place(self.places.first())?,
+            runner_up: place(self.places.get(1))?,
+            loser: place(
+                self.places
+                    .last()
+                    .filter(|_| self.places.len() >= DISTINCT_PLACES),
+            )?,
+            places: self
+                .places
+                .iter()
+                .map(distribution)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The furthest a camel moves in a roll, three tiles and one more for an oasis.
+const REACH: usize = 4;
+
+/// Whether no roll of the dice can change the ranking of the camels.
+///
+/// That is the case when every camel is alone on its tile, and no camel with a die in the pyramid can reach the tile of the camel in front of it. Camels that can not reach each other are never carried, and a fata morgana never moves a camel behind the tile it started from.
+fn is_decided(race: &Race, dice: &Dice) -> bool {
+    let mut tile = 0;
+    let mut behind: Option<(usize, Camel)> = None;
+    for marker in &race.positions {
+        match marker {
+            Marker::Divider => tile += 1,
+            Marker::Camel(camel) => {
+                if let Some((behind_tile, behind_camel)) = behind {
+                    let reach = if dice.contains(behind_camel) {
+                        REACH
+                    } else {
+                        0
+                    };
+                    if behind_tile + reach >= tile {
+                        return false;
+                    }
+                }
+                behind = Some((tile, camel));
+            }
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Performs the roll of a die in the pyramid on the race.
+///
+/// Panics when the roll can not be performed, see the module documentation.
+pub(crate) fn perform<R>(race: &Race, roll: R) -> Race
+where
+    R: Into<Roll>,
+{
+    race.perform_checked(roll)
+        .unwrap_or_else(|problem| refuse(problem))
+}
+
+/// Panics with the problem of a roll the oracle should not have been asked about.
+fn refuse(problem: MoveError) -> ! {
+    match problem {
+        MoveError::CamelNotInRace(camel) => {
+            panic!(
+                "the die of the {:?} camel to be of a camel in the race",
+                camel
+            )
+        }
+        MoveError::TooManyMarkers => {
+            panic!("the race to keep room for the markers of a leg")
+        }
+    }
+}
+
+/// Visits every leaf of the leg with a visitor like `prototype`.
+///
+/// With the `parallel` feature the leaves are split over the threads of the global thread pool, and the visitors of the threads are merged.
+fn visit_leaves<V>(race: &Race, dice: &Dice, prototype: V) -> V
+where
+    V: ReducibleVisitor,
+{
+    #[cfg(feature = "parallel")]
+    {
+        parallel::visit(race, dice, &prototype)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let mut tree = Tree::singleton(*race);
+        tree.expand(dice);
+        let mut visitor = prototype;
+        tree.visit_leaves(&mut visitor);
+        visitor
+    }
+}
+
+/// Determines the win chances for each camel, like `project`, looking up the outcome of rolls in the transitions.
+///
+/// Rolls that are not in the transitions yet are performed and added, so that the next projection of the same board, or of a board later in the leg, performs fewer rolls.
+pub fn project_with_transitions(
+    race: &Race,
+    dice: &Dice,
+    transitions: &mut Transitions,
+) -> LegChances {
+    let mut tree = Tree::singleton(*race);
+    tree.expand_with_transitions(dice, transitions, &mut |_| {});
 
     let mut counter: LeafCounter = Default::default();
     tree.visit_leaves(&mut counter);
+    counter.chances()
+}
+
+/// The chance of every race the leg can end in.
+///
+/// Many orders of the rolls lead to the same race, every race occurs once with the chance that the leg ends in it.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::final_races;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// let races = final_races(&race, &dice);
+///
+/// assert_eq!(races.len(), 3);
+/// assert_eq!(races[&"y,r".parse::<Race>().expect("to parse")], Fraction::new(1, 3));
+/// ```
+pub fn final_races(race: &Race, dice: &Dice) -> HashMap<Race, Fraction> {
+    let mut tree = Tree::singleton(*race);
+    tree.expand(dice);
+    let leaves = tree.leaves();
+    let total: usize = leaves.values().sum();
+    leaves
+        .into_iter()
+        .map(|(race, count)| (race, Fraction::new(count as i64, total as u64)))
+        .collect()
+}
+
+/// The number of nodes in the tree that `project` builds for the given dice.
+///
+/// This allows one to decide beforehand if an exact projection is feasible.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::tree_size;
+/// let dice = "rw".parse::<Dice>().expect("to parse");
+///
+/// assert_eq!(tree_size(&dice), 1 + 2 * 3 + 2 * 3 * 3);
+/// ```
+pub fn tree_size(dice: &Dice) -> u64 {
+    let mut size = 1;
+    let mut level = 1;
+    for remaining in (1..=dice.len() as u64).rev() {
+        level *= remaining * 3;
+        size += level;
+    }
+    size
+}
+
+/// An estimate of the number of bytes the tree that `project` builds for the given dice takes.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::{tree_memory, tree_size};
+/// let dice = "rw".parse::<Dice>().expect("to parse");
+///
+/// assert!(tree_memory(&dice) > tree_size(&dice));
+/// ```
+pub fn tree_memory(dice: &Dice) -> u64 {
+    tree_size(dice) * NODE_BYTES as u64
+}
+
+/// How a projection went over the ways the leg can play out.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Enumeration {
+    /// The tree of rolls was built, and its leaves counted.
+    Tree,
+    /// The leaves were counted as the rolls were performed, without building the tree.
+    Streaming,
+}
+
+impl Display for Enumeration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Enumeration::Tree => write!(f, "tree"),
+            Enumeration::Streaming => write!(f, "streaming"),
+        }
+    }
+}
+
+/// Determines the win chances for each camel, like `project`, without taking more than `budget` bytes for the tree of rolls.
+///
+/// When the tree would not fit the budget, see `tree_memory`, the leaves are streamed instead. The chances are the same either way, the `Enumeration` tells which was used.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::{project_within, Enumeration};
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "ry".parse::<Dice>().expect("to parse");
+///
+/// let (_, enumeration) = project_within(&race, &dice, 1024);
+///
+/// assert_eq!(enumeration, Enumeration::Streaming);
+/// ```
+pub fn project_within(race: &Race, dice: &Dice, budget: u64) -> (LegChances, Enumeration) {
+    let enumeration = if tree_memory(dice) <= budget {
+        Enumeration::Tree
+    } else {
+        Enumeration::Streaming
+    };
+    #[cfg(feature = "tracing")]
+    tracing::debug!(%enumeration, budget, "enumerating");
+    let chances = match enumeration {
+        Enumeration::Tree => project(race, dice),
+        Enumeration::Streaming => project_count_only(race, dice),
+    };
+    (chances, enumeration)
+}
+
+/// Estimates the win chances for each camel by sampling legs.
+///
+/// Each sample draws the remaining dice in a random order, with a random face. The resulting `LegChances` approximate the ones `project` determines exactly, which is useful when the exact computation is too large.
+pub fn sample<R>(race: &Race, dice: &Dice, samples: usize, rng: &mut R) -> LegChances
+where
+    R: Rng + ?Sized,
+{
+    sample_with_progress(race, dice, samples, rng, |_, _| {})
+}
+
+/// Estimates the win chances for each camel, like `sample`, while reporting progress.
+///
+/// Every so often `progress` is called with the number of samples taken so far and the total number of samples.
+pub fn sample_with_progress<R, P>(
+    race: &Race,
+    dice: &Dice,
+    samples: usize,
+    rng: &mut R,
+    mut progress: P,
+) -> LegChances
+where
+    R: Rng + ?Sized,
+    P: FnMut(u64, u64),
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("sample", race = %race, dice = %dice, samples).entered();
+    let faces = [Face::One, Face::Two, Face::Three];
+    let mut order: Vec<Camel> = dice.iter().collect();
+    order.sort();
+    let mut counter: LeafCounter = Default::default();
+    for taken in 0..samples {
+        if taken.is_multiple_of(SAMPLE_PROGRESS_INTERVAL) {
+            progress(taken as u64, samples as u64);
+        }
+        let mut camels = order.clone();
+        camels.shuffle(rng);
+        let mut result = *race;
+        for camel in camels {
+            let face = *faces.choose(rng).unwrap(/* faces is not empty */);
+            result = perform(&result, (camel, face));
+        }
+        counter.visit(&result, 1);
+    }
+    progress(samples as u64, samples as u64);
 
     counter.chances()
 }
 
-/// All the relevant chances for each camel.
+/// The number of samples between two progress reports.
+const SAMPLE_PROGRESS_INTERVAL: usize = 1 << 12;
+
+/// Estimates the chances for each camel to be the overall winner and loser of the race by sampling complete races.
+///
+/// The current leg continues with the given dice, the following legs use the dice of all camels in the race. A race ends when a camel crosses the finish.
+///
+/// A race without camels never ends, its chances are empty. Panics when the race does not have a finish.
+pub fn sample_race<R>(race: &Race, dice: &Dice, samples: usize, rng: &mut R) -> RaceChances
+where
+    R: Rng + ?Sized,
+{
+    assert!(
+        race.positions.contains(&Marker::Finish),
+        "a race needs a finish to end"
+    );
+    let faces = [Face::One, Face::Two, Face::Three];
+    let all = Dice::from(race);
+    let mut counter: LeafCounter = Default::default();
+    if all.is_empty() {
+        return counter.race_chances();
+    }
+    for _ in 0..samples {
+        let mut result = *race;
+        let mut pyramid = *dice;
+        while !result.is_over() {
+            if pyramid.is_empty() {
+                pyramid = all;
+            }
+            let mut camels: Vec<Camel> = pyramid.iter().collect();
+            camels.sort();
+            let camel = *camels.choose(rng).unwrap(/* pyramid is not empty */);
+            let face = *faces.choose(rng).unwrap(/* faces is not empty */);
+            result = perform(&result, (camel, face));
+            pyramid = pyramid.remove(camel);
+        }
+        counter.visit(&result, 1);
+    }
+
+    counter.race_chances()
+}
+
+/// Determines the expected position of each camel at the end of the leg.
+///
+/// Positions are tiles counted from the rear of the given race, i.e. the first position of `race` is tile 0.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::expected_positions;
+/// let race = "r,,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// let expected = expected_positions(&race, &dice);
+///
+/// assert_eq!(expected[&Camel::Red], Fraction::from(2));
+/// assert_eq!(expected[&Camel::Yellow], Fraction::from(2));
+/// ```
+pub fn expected_positions(race: &Race, dice: &Dice) -> HashMap<Camel, Fraction> {
+    let anchored = anchor(race);
+    let accumulator = visit_leaves(
+        &anchored,
+        dice,
+        PositionAccumulator::new(tiles_to_finish(&anchored, 0)),
+    );
+
+    accumulator.expected()
+}
+
+/// The outcome counts behind the chances of a leg, together with examples of roll sequences.
+pub struct Explanation {
+    /// The number of roll sequences, i.e. orderings of the dice with their faces.
+    pub total: usize,
+    /// For each camel the number of sequences in which it wins.
+    pub winner: HashMap<Camel, usize>,
+    /// For each camel the number of sequences in which it is runner up.
+    pub runner_up: HashMap<Camel, usize>,
+    /// For each camel the number of sequences in which it loses.
+    pub loser: HashMap<Camel, usize>,
+    /// For each camel a few sequences in which it wins.
+    pub examples: HashMap<Camel, Vec<Vec<Roll>>>,
+}
+
+/// Explains the chances `project` determines, by counting the roll sequences for each result.
+///
+/// At most `examples` roll sequences are kept for each winning camel. Sequences are enumerated in a fixed order, so the examples are the same every time.
 ///
-/// I.e. which camel is winning, which is losing, which is the runner up.
-pub struct Chances {
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::explain;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let explanation = explain(&race, &dice, 2);
+///
+/// assert_eq!(explanation.total, 3);
+/// assert_eq!(explanation.winner[&Camel::Red], 3);
+/// assert_eq!(explanation.examples[&Camel::Red].len(), 2);
+/// ```
+pub fn explain(race: &Race, dice: &Dice, examples: usize) -> Explanation {
+    let mut explainer = Explainer {
+        counter: Default::default(),
+        examples: HashMap::new(),
+        limit: examples,
+    };
+    explainer.expand(race, dice, &mut Vec::new());
+
+    Explanation {
+        total: explainer.counter.total,
+        winner: explainer.counter.winner,
+        runner_up: explainer.counter.runner_up,
+        loser: explainer.counter.loser,
+        examples: explainer.examples,
+    }
+}
+
+struct Explainer {
+    counter: LeafCounter,
+    examples: HashMap<Camel, Vec<Vec<Roll>>>,
+    limit: usize,
+}
+
+impl Explainer {
+    fn expand(&mut self, race: &Race, dice: &Dice, rolls: &mut Vec<Roll>) {
+        if dice.is_empty() {
+            self.counter.visit(race, 1);
+            if let Some(winner) = race.winner() {
+                let examples = self.examples.entry(winner).or_default();
+                if examples.len() < self.limit {
+                    examples.push(rolls.clone());
+                }
+            }
+            return;
+        }
+        let mut camels: Vec<Camel> = dice.iter().collect();
+        camels.sort();
+        for camel in camels {
+            let remaining = dice.remove(camel);
+            for face in [Face::One, Face::Two, Face::Three] {
+                let roll = Roll::from((camel, face));
+                rolls.push(roll);
+                self.expand(&perform(race, roll), &remaining, rolls);
+                rolls.pop();
+            }
+        }
+    }
+}
+
+/// The expected number of times a camel unit lands on the trap in the given position during the leg.
+///
+/// The owner of a trap earns a coin for every landing. Positions are counted in tiles from the start of the race.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::expected_landings;
+/// let race = "r,+".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// assert_eq!(expected_landings(&race, &dice, 1), Some(Fraction::new(1, 3)));
+/// assert_eq!(expected_landings(&race, &dice, 2), None);
+/// ```
+///
+/// There is nothing to expect when there is no trap in the given position.
+pub fn expected_landings(race: &Race, dice: &Dice, position: usize) -> Option<Fraction> {
+    let ordinal = adjustments(race).position(|(tile, _)| tile == position)?;
+    let (landings, leaves) = count_landings(race, dice, ordinal);
+
+    Some(Fraction::new(landings as i64, leaves as u64))
+}
+
+/// Counts the landings on the trap with the given ordinal over all roll sequences, together with the number of sequences.
+///
+/// Traps are identified by their ordinal, because the tiles of a race shift when the rear camel moves.
+fn count_landings(race: &Race, dice: &Dice, ordinal: usize) -> (usize, usize) {
+    if dice.is_empty() {
+        return (0, 1);
+    }
+    let mut landings = 0;
+    let mut leaves = 0;
+    for camel in dice {
+        let remaining = dice.remove(camel);
+        for face in Face::values() {
+            let outcome = race
+                .perform_detailed((camel, face))
+                .unwrap_or_else(|problem| refuse(problem));
+            if outcome.trap == Some(ordinal) {
+                landings += leaves_of(&remaining);
+            }
+            let (sub_landings, sub_leaves) = count_landings(&outcome.race, &remaining, ordinal);
+            landings += sub_landings;
+            leaves += sub_leaves;
+        }
+    }
+    (landings, leaves)
+}
+
+/// The expected number of times a camel unit lands on the tile at the given distance from the finish during the leg.
+///
+/// Unlike a trap the tile has no effect on the camels, like the betting photographer of the Supercup expansion. The tile right before the finish is at distance 1.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::expected_arrivals;
+/// let race = "r,,,!".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// assert_eq!(expected_arrivals(&race, &dice, 2), Fraction::new(1, 3));
+/// ```
+///
+/// Panics when the race does not have a finish.
+pub fn expected_arrivals(race: &Race, dice: &Dice, distance: usize) -> Fraction {
+    let (arrivals, leaves) = count_arrivals(race, dice, distance);
+
+    Fraction::new(arrivals as i64, leaves as u64)
+}
+
+/// Counts the landings on the tile at the given distance from the finish over all roll sequences, together with the number of sequences.
+///
+/// The tile is identified by its distance, because the tiles of a race shift when the rear camel moves.
+fn count_arrivals(race: &Race, dice: &Dice, distance: usize) -> (usize, usize) {
+    if dice.is_empty() {
+        return (0, 1);
+    }
+    let finish = race
+        .positions
+        .iter()
+        .position(|marker| marker == Marker::Finish)
+        .map(|index| divisions_before(race, index))
+        .expect("a race with a finish");
+    let target = finish.checked_sub(distance);
+    let mut arrivals = 0;
+    let mut leaves = 0;
+    for camel in dice {
+        let remaining = dice.remove(camel);
+        for face in Face::values() {
+            let tile = race.position_of(camel);
+            if tile.is_some() && tile.map(|tile| tile + usize::from(face)) == target {
+                arrivals += leaves_of(&remaining);
+            }
+            let (sub_arrivals, sub_leaves) =
+                count_arrivals(&perform(race, (camel, face)), &remaining, distance);
+            arrivals += sub_arrivals;
+            leaves += sub_leaves;
+        }
+    }
+    (arrivals, leaves)
+}
+
+/// The number of roll sequences the given dice can produce.
+fn leaves_of(dice: &Dice) -> usize {
+    (1..=dice.len()).map(|remaining| remaining * 3).product()
+}
+
+/// The tiles of the traps in the race, together with their marker.
+fn adjustments(race: &Race) -> impl Iterator<Item = (usize, Marker)> + '_ {
+    race.positions
+        .iter()
+        .enumerate()
+        .filter(|(_, marker)| marker.is_an_adjustment())
+        .map(move |(index, marker)| (divisions_before(race, index), marker))
+}
+
+fn divisions_before(race: &Race, index: usize) -> usize {
+    race.positions
+        .iter()
+        .take(index)
+        .filter(|marker| marker.is_a_divider())
+        .count()
+}
+
+/// The chance that `camel` is ahead of `rival` at the end of the leg.
+///
+/// This is the question to ask when holding a ticket for one camel that competes with another.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::matchup;
+/// let race = "r,,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// assert_eq!(matchup(&race, &dice, Camel::Red, Camel::Yellow), Fraction::new(2, 3));
+/// ```
+pub fn matchup(race: &Race, dice: &Dice, camel: Camel, rival: Camel) -> Fraction {
+    let counter = visit_leaves(
+        race,
+        dice,
+        MatchupCounter {
+            camel,
+            rival,
+            ahead: 0,
+            total: 0,
+        },
+    );
+
+    Fraction::new(counter.ahead as i64, counter.total as u64)
+}
+
+struct MatchupCounter {
+    camel: Camel,
+    rival: Camel,
+    ahead: usize,
+    total: usize,
+}
+
+impl LeafVisitor for MatchupCounter {
+    fn visit(&mut self, race: &Race, multiplicity: usize) {
+        let ranking = race.ranking();
+        let place = |camel| ranking.iter().position(|candidate| *candidate == camel);
+        if let (Some(camel), rival) = (place(self.camel), place(self.rival)) {
+            if rival.is_none_or(|rival| camel < rival) {
+                self.ahead += multiplicity;
+            }
+        }
+        self.total += multiplicity;
+    }
+}
+
+impl ReducibleVisitor for MatchupCounter {
+    fn identity(&self) -> Self {
+        Self {
+            ahead: 0,
+            total: 0,
+            ..*self
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            ahead: self.ahead + other.ahead,
+            total: self.total + other.total,
+            ..self
+        }
+    }
+}
+
+/// The chance that one camel is ahead of another at the end of the leg, for every pair of camels in the race.
+///
+/// The key `(camel, rival)` holds the chance that `camel` is ahead of `rival`, like `matchup` does, but with a single expansion of the tree of rolls.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::matchups;
+/// let race = "r,,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+///
+/// let chances = matchups(&race, &dice);
+///
+/// assert_eq!(chances[&(Camel::Red, Camel::Yellow)], Fraction::new(2, 3));
+/// assert_eq!(chances[&(Camel::Yellow, Camel::Red)], Fraction::new(1, 3));
+/// ```
+pub fn matchups(race: &Race, dice: &Dice) -> BTreeMap<(Camel, Camel), Fraction> {
+    let counter = visit_leaves(race, dice, MatchupsCounter::default());
+
+    let camels: Vec<Camel> = race.ranking();
+    let mut chances = BTreeMap::new();
+    for camel in &camels {
+        for rival in camels.iter().filter(|rival| *rival != camel) {
+            let ahead = counter.ahead.get(&(*camel, *rival)).copied().unwrap_or(0);
+            chances.insert(
+                (*camel, *rival),
+                Fraction::new(ahead as i64, counter.total as u64),
+            );
+        }
+    }
+    chances
+}
+
+#[derive(Default)]
+struct MatchupsCounter {
+    ahead: HashMap<(Camel, Camel), usize>,
+    total: usize,
+}
+
+impl LeafVisitor for MatchupsCounter {
+    fn visit(&mut self, race: &Race, multiplicity: usize) {
+        let ranking = race.ranking();
+        for (place, camel) in ranking.iter().enumerate() {
+            for rival in &ranking[place + 1..] {
+                *self.ahead.entry((*camel, *rival)).or_insert(0) += multiplicity;
+            }
+        }
+        self.total += multiplicity;
+    }
+}
+
+impl ReducibleVisitor for MatchupsCounter {
+    fn identity(&self) -> Self {
+        Self::default()
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        add_counts(&mut self.ahead, other.ahead);
+        self.total += other.total;
+        self
+    }
+}
+
+/// Adds the counts of `from` to the counts of `into`.
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+fn add_counts<K, C>(into: &mut HashMap<K, C>, from: HashMap<K, C>)
+where
+    K: Eq + Hash,
+    C: Count,
+{
+    for (key, count) in from {
+        *into.entry(key).or_default() += count;
+    }
+}
+
+/// The number of tiles between the front of the race and the finish that `anchor` adds.
+const ANCHOR_TILES: usize = 32;
+
+/// Makes sure the race has a finish, so that positions can be measured against it.
+///
+/// A leg can not move a camel further than the finish that is added, unless the race is too long to add every tile. Camels that cross the finish are counted with negative tiles to go, so positions are measured all the same.
+fn anchor(race: &Race) -> Race {
+    if race.positions.contains(&Marker::Finish) {
+        *race
+    } else {
+        let tiles = ANCHOR_TILES.min(CAPACITY.saturating_sub(race.positions.len() + 1));
+        let positions: Vec<Marker> = race
+            .positions
+            .iter()
+            .chain(repeat_n(Marker::Divider, tiles))
+            .chain(once(Marker::Finish))
+            .collect();
+        Race::from(positions).following(race)
+    }
+}
+
+/// Counts the number of tiles between the marker at `index` and the finish.
+fn tiles_to_finish(race: &Race, index: usize) -> i64 {
+    let finish = race
+        .positions
+        .iter()
+        .position(|marker| marker == Marker::Finish)
+        .unwrap(/* finish is present because of anchor */);
+    let (from, to, sign) = if index <= finish {
+        (index, finish, 1)
+    } else {
+        (finish, index, -1)
+    };
+    let dividers = race
+        .positions
+        .iter()
+        .skip(from)
+        .take(to - from)
+        .filter(|marker| *marker == Marker::Divider)
+        .count() as i64;
+    sign * dividers
+}
+
+struct PositionAccumulator {
+    start: i64,
+    total: usize,
+    positions: HashMap<Camel, i64>,
+}
+
+impl PositionAccumulator {
+    fn new(start: i64) -> Self {
+        Self {
+            start,
+            total: 0,
+            positions: HashMap::new(),
+        }
+    }
+
+    fn expected(&self) -> HashMap<Camel, Fraction> {
+        self.positions
+            .iter()
+            .map(|(camel, sum)| (*camel, Fraction::new(*sum, self.total as u64)))
+            .collect()
+    }
+}
+
+impl LeafVisitor for PositionAccumulator {
+    fn visit(&mut self, race: &Race, multiplicity: usize) {
+        for (index, marker) in race.positions.iter().enumerate() {
+            if let Marker::Camel(camel) = marker {
+                *self.positions.entry(camel).or_insert(0) +=
+                    (self.start - tiles_to_finish(race, index)) * multiplicity as i64;
+            }
+        }
+        self.total += multiplicity;
+    }
+}
+
+impl ReducibleVisitor for PositionAccumulator {
+    fn identity(&self) -> Self {
+        Self::new(self.start)
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (camel, sum) in other.positions {
+            *self.positions.entry(camel).or_insert(0) += sum;
+        }
+        self.total += other.total;
+        self
+    }
+}
+
+/// All the relevant chances for each camel at the end of a leg.
+///
+/// I.e. which camel is winning, which is losing, which is the runner up. These are the chances to value leg betting tickets with, the chances for bets on the outcome of the race are `RaceChances`.
+///
+/// A race with fewer camels than `DISTINCT_PLACES` does not give a camel two places. With a single camel the runner up and loser distributions are empty, with two camels the loser distribution is.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let chances = project(&race, &"ry".parse::<Dice>().expect("to parse"));
+///
+/// assert_eq!(chances.camels(), 2);
+/// assert_eq!(chances.runner_up.values().count(), 2);
+/// assert_eq!(chances.loser.values().count(), 0);
+/// ```
+pub struct LegChances {
     /// Distribution of the chance to win.
     pub winner: Distribution,
     /// Distribution of the chance to be runner up.
     pub runner_up: Distribution,
     /// Distribution of the chance to lose.
     pub loser: Distribution,
+    /// Distribution of the chance for each place, starting with the first place.
+    pub places: Vec<Distribution>,
+}
+
+impl LegChances {
+    /// The number of camels that were ranked, i.e. the number of places.
+    pub fn camels(&self) -> usize {
+        self.places.len()
+    }
+
+    /// Whether every distribution that has a camel sums to one.
+    ///
+    /// A race with a single camel has no runner up, and a race with fewer than `DISTINCT_PLACES` camels has no loser, their distributions are empty. This makes a handy invariant for property tests.
+    ///
+    /// ```
+    /// # use camel_up::prelude::*;
+    /// let race = "r,+,y".parse::<Race>().expect("to parse");
+    /// let chances = project(&race, &"ry".parse::<Dice>().expect("to parse"));
+    ///
+    /// assert!(chances.sums_to_one());
+    /// ```
+    pub fn sums_to_one(&self) -> bool {
+        let sums_to = |camels: usize| {
+            if self.camels() >= camels {
+                Fraction::one()
+            } else {
+                Fraction::zero()
+            }
+        };
+        self.winner.total() == sums_to(1)
+            && self.runner_up.total() == sums_to(2)
+            && self.loser.total() == sums_to(DISTINCT_PLACES)
+            && self
+                .places
+                .iter()
+                .all(|place| place.total() == Fraction::one())
+    }
+}
+
+/// The chances for each camel to be the overall winner and loser of the race.
+///
+/// Bets on the outcome of the race are valued with these, see `sample_race`. They are kept apart from `LegChances` so that the chances of a leg can not be mistaken for those of the race.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::sample_race;
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// let race = "r,y!".parse::<Race>().expect("to parse");
+/// let dice = "ry".parse::<Dice>().expect("to parse");
+///
+/// let chances = sample_race(&race, &dice, 100, &mut StdRng::seed_from_u64(0));
+///
+/// assert_eq!(chances.camels(), 2);
+/// assert_eq!(chances.winner.total(), Fraction::one());
+/// ```
+pub struct RaceChances {
+    /// Distribution of the chance to win the race.
+    pub winner: Distribution,
+    /// Distribution of the chance to lose the race.
+    pub loser: Distribution,
+    /// Distribution of the chance for each place at the end of the race, starting with the first place.
+    pub places: Vec<Distribution>,
+}
+
+impl RaceChances {
+    /// The number of camels that were ranked, i.e. the number of places.
+    pub fn camels(&self) -> usize {
+        self.places.len()
+    }
+}
+
+/// The change in chances between two scenarios, e.g. a race with and without a trap.
+pub struct Diff {
+    /// Change in the chance to win.
+    pub winner: Distribution,
+    /// Change in the chance to be runner up.
+    pub runner_up: Distribution,
+    /// Change in the chance to lose.
+    pub loser: Distribution,
+}
+
+/// Determines how the chances change from scenario `before` to scenario `after`, for every camel in either.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::oracle::diff;
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let before = project(&"r,,y".parse::<Race>().expect("to parse"), &dice);
+/// let after = project(&"r,,,y".parse::<Race>().expect("to parse"), &dice);
+///
+/// let change = diff(&before, &after);
+///
+/// assert_eq!(change.winner[&Camel::Red], -Fraction::new(1, 3));
+/// assert_eq!(change.winner[&Camel::Yellow], Fraction::new(1, 3));
+/// ```
+pub fn diff(before: &LegChances, after: &LegChances) -> Diff {
+    let camels: HashSet<Camel> = [before, after]
+        .iter()
+        .flat_map(|chances| {
+            chances
+                .winner
+                .values()
+                .chain(chances.runner_up.values())
+                .chain(chances.loser.values())
+                .map(|(camel, _)| *camel)
+        })
+        .collect();
+    let delta = |outcome: fn(&LegChances) -> &Distribution| {
+        let distribution: HashMap<Camel, Fraction> = camels
+            .iter()
+            .map(|camel| (*camel, outcome(after)[camel] - outcome(before)[camel]))
+            .collect();
+        Distribution::from(distribution)
+    };
+
+    Diff {
+        winner: delta(|chances| &chances.winner),
+        runner_up: delta(|chances| &chances.runner_up),
+        loser: delta(|chances| &chances.loser),
+    }
 }
 
 /// The chances for a specific situation for each camel.
 pub struct Distribution {
-    distribution: HashMap<Camel, Fraction>,
+    distribution: BTreeMap<Camel, Fraction>,
     default: Fraction,
 }
 
 impl Distribution {
     /// Returns an iterator that iterates over the chances.
     ///
-    /// I.e. iterates over `(&Camel, &Fraction)` values.
+    /// I.e. iterates over `(&Camel, &Fraction)` values, in the order of the camels, red first and white last.
     pub fn values(&self) -> impl Iterator<Item = (&Camel, &Fraction)> + '_ {
         self.distribution.iter()
     }
+
+    /// The sum of the chances of every camel, one for a distribution over the camels of a race and zero for an empty distribution.
+    pub fn total(&self) -> Fraction {
+        self.distribution.values().sum()
+    }
 }
 
-impl From<HashMap<Camel, Fraction>> for Distribution {
-    fn from(distribution: HashMap<Camel, Fraction>) -> Self {
+impl From<BTreeMap<Camel, Fraction>> for Distribution {
+    fn from(distribution: BTreeMap<Camel, Fraction>) -> Self {
         Self {
             distribution,
             default: Fraction::default(),
@@ -59,6 +1175,12 @@ impl From<HashMap<Camel, Fraction>> for Distribution {
     }
 }
 
+impl From<HashMap<Camel, Fraction>> for Distribution {
+    fn from(distribution: HashMap<Camel, Fraction>) -> Self {
+        Self::from(distribution.into_iter().collect::<BTreeMap<_, _>>())
+    }
+}
+
 impl Index<&Camel> for Distribution {
     type Output = Fraction;
 
@@ -67,67 +1189,272 @@ impl Index<&Camel> for Distribution {
     }
 }
 
-struct LeafCounter {
-    total: usize,
-    winner: HashMap<Camel, usize>,
-    runner_up: HashMap<Camel, usize>,
-    loser: HashMap<Camel, usize>,
+/// Counts the places of the camels in the leaves it visits, in `C`.
+#[derive(Default)]
+struct LeafCounter<C = usize> {
+    total: C,
+    winner: HashMap<Camel, C>,
+    runner_up: HashMap<Camel, C>,
+    loser: HashMap<Camel, C>,
+    places: Vec<HashMap<Camel, C>>,
 }
 
-impl LeafCounter {
-    fn chances(&self) -> Chances {
-        let winner: HashMap<Camel, Fraction> = self
-            .winner
-            .iter()
-            .map(|(camel, count)| (*camel, Fraction::new(*count as i64, self.total as u64)))
-            .collect();
-        let runner_up: HashMap<Camel, Fraction> = self
-            .runner_up
-            .iter()
-            .map(|(camel, count)| (*camel, Fraction::new(*count as i64, self.total as u64)))
-            .collect();
-        let loser: HashMap<Camel, Fraction> = self
-            .loser
-            .iter()
-            .map(|(camel, count)| (*camel, Fraction::new(*count as i64, self.total as u64)))
-            .collect();
-        Chances {
-            winner: Distribution::from(winner),
-            runner_up: Distribution::from(runner_up),
-            loser: Distribution::from(loser),
+impl<C> LeafCounter<C>
+where
+    C: Count,
+{
+    fn chances(&self) -> LegChances {
+        let distribution = |counts: &HashMap<Camel, C>| {
+            let chances: HashMap<Camel, Fraction> = counts
+                .iter()
+                .map(|(camel, count)| {
+                    let chance = count
+                        .ratio(&self.total)
+                        .expect("the leaves of a tree in memory to fit a fraction");
+                    (*camel, chance)
+                })
+                .collect();
+            Distribution::from(chances)
+        };
+        let winner = distribution(&self.winner);
+        let runner_up = distribution(&self.runner_up);
+        let loser = distribution(&self.loser);
+        let places: Vec<Distribution> = self.places.iter().map(distribution).collect();
+        LegChances {
+            winner,
+            runner_up,
+            loser,
+            places,
         }
     }
-}
 
-impl Default for LeafCounter {
-    fn default() -> Self {
-        Self {
-            total: 0,
-            winner: HashMap::new(),
-            runner_up: HashMap::new(),
-            loser: HashMap::new(),
+    /// The chances of the counted races, when every race ran to its end.
+    fn race_chances(&self) -> RaceChances {
+        let LegChances {
+            winner,
+            loser,
+            places,
+            ..
+        } = self.chances();
+        RaceChances {
+            winner,
+            loser,
+            places,
         }
     }
 }
 
-impl LeafVisitor for LeafCounter {
-    fn visit(&mut self, race: &Race) {
+impl<C> LeafVisitor for LeafCounter<C>
+where
+    C: Count,
+{
+    fn visit(&mut self, race: &Race, multiplicity: usize) {
+        let multiplicity = C::of(multiplicity);
         if let Some(winner) = race.winner() {
-            *self.winner.entry(winner).or_insert(0) += 1;
+            *self.winner.entry(winner).or_default() += multiplicity.clone();
         };
         if let Some(runner_up) = race.runner_up() {
-            *self.runner_up.entry(runner_up).or_insert(0) += 1;
+            *self.runner_up.entry(runner_up).or_default() += multiplicity.clone();
         };
         if let Some(loser) = race.loser() {
-            *self.loser.entry(loser).or_insert(0) += 1;
+            *self.loser.entry(loser).or_default() += multiplicity.clone();
         };
-        self.total += 1;
+        for (place, camel) in race.ranking().into_iter().enumerate() {
+            if self.places.len() <= place {
+                self.places.push(HashMap::new());
+            }
+            *self.places[place].entry(camel).or_default() += multiplicity.clone();
+        }
+        self.total += multiplicity;
+    }
+}
+
+impl<C> ReducibleVisitor for LeafCounter<C>
+where
+    C: Count,
+{
+    fn identity(&self) -> Self {
+        Self::default()
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.total += other.total;
+        add_counts(&mut self.winner, other.winner);
+        add_counts(&mut self.runner_up, other.runner_up);
+        add_counts(&mut self.loser, other.loser);
+        for (place, counts) in other.places.into_iter().enumerate() {
+            if self.places.len() <= place {
+                self.places.push(HashMap::new());
+            }
+            add_counts(&mut self.places[place], counts);
+        }
+        self
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn identical_races_are_aggregated() {
+        let race = "r,,,,y".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+
+        let races = final_races(&race, &dice);
+
+        assert_eq!(races.len(), 5);
+        assert_eq!(races[&race], Fraction::new(6, 18));
+    }
+
+    #[test]
+    fn visitors_of_branches_merge_into_the_visitor_of_the_leg() {
+        let race = "r,o,+,y,,g".parse::<Race>().expect("to parse");
+        let dice = "ryg".parse::<Dice>().expect("to parse");
+        let prototype = MatchupsCounter::default();
+
+        let mut whole = prototype.identity();
+        let mut tree = Tree::singleton(race);
+        tree.expand(&dice);
+        tree.visit_leaves(&mut whole);
+        let merged = dice
+            .iter()
+            .flat_map(|camel| {
+                Face::values()
+                    .into_iter()
+                    .map(move |face| (race.perform(Roll::from((camel, face))), dice.remove(camel)))
+            })
+            .map(|(race, dice)| {
+                let mut branch = prototype.identity();
+                let mut tree = Tree::singleton(race);
+                tree.expand(&dice);
+                tree.visit_leaves(&mut branch);
+                branch
+            })
+            .fold(prototype.identity(), MatchupsCounter::merge);
+
+        assert_eq!(merged.total, whole.total);
+        assert_eq!(merged.ahead, whole.ahead);
+    }
+
+    #[test]
+    fn decided_rankings_are_counted_at_once() {
+        let race = "r,,,,,y,,+,,,,g".parse::<Race>().expect("to parse");
+        let dice = "ryg".parse::<Dice>().expect("to parse");
+        assert!(is_decided(&race, &dice));
+        let within_reach = "r,,,,y".parse::<Race>().expect("to parse");
+        assert!(!is_decided(&within_reach, &dice));
+        let stacked = "ry,,,,,,g".parse::<Race>().expect("to parse");
+        assert!(!is_decided(&stacked, &dice));
+
+        let chances = project_count_only(&race, &dice);
+
+        assert_eq!(chances.winner[&Camel::Green], Fraction::one());
+        assert_eq!(chances.runner_up[&Camel::Yellow], Fraction::one());
+        assert_eq!(chances.loser[&Camel::Red], Fraction::one());
+    }
+
+    #[test]
+    fn counting_agrees_with_the_tree() {
+        let race = "w,g,o,+,r,y".parse::<Race>().expect("to parse");
+        let dice = "wgry".parse::<Dice>().expect("to parse");
+
+        let tree = project(&race, &dice);
+        let counted = project_count_only(&race, &dice);
+
+        for (expected, actual) in [
+            (&tree.winner, &counted.winner),
+            (&tree.runner_up, &counted.runner_up),
+            (&tree.loser, &counted.loser),
+        ] {
+            assert_eq!(actual.values().count(), expected.values().count());
+            for (camel, chance) in expected.values() {
+                assert_eq!(actual[camel], *chance);
+            }
+        }
+        assert_eq!(counted.places.len(), tree.places.len());
+    }
+
+    #[test]
+    fn races_without_room_for_a_leg_are_not_projected() {
+        let mut markers = vec![Marker::Camel(Camel::Red)];
+        markers.extend(repeat_n(Marker::Divider, CAPACITY - 2));
+        markers.push(Marker::Camel(Camel::Yellow));
+        let race = Race::from(markers);
+        let dice = "y".parse::<Dice>().expect("to parse");
+
+        assert_eq!(
+            project_checked(&race, &dice).err(),
+            Some(MoveError::TooManyMarkers)
+        );
+        assert!(project_checked(&race, &"".parse::<Dice>().expect("to parse")).is_ok());
+    }
+
+    #[test]
+    fn counts_of_any_width_project_alike() {
+        let race = "w,g,o,+,r,y".parse::<Race>().expect("to parse");
+        let dice = "wgry".parse::<Dice>().expect("to parse");
+
+        let narrow = project_count_only(&race, &dice);
+        let wide = project_count_only_in::<u128>(&race, &dice).expect("the chances to fit");
+
+        assert_eq!(wide.places.len(), narrow.places.len());
+        for (place, distribution) in narrow.places.iter().enumerate() {
+            for (camel, chance) in distribution.values() {
+                assert_eq!(wide.places[place][camel], *chance);
+            }
+        }
+    }
+
+    #[test]
+    fn small_fields_do_not_give_a_camel_two_places() {
+        for (race, runner_up, loser) in [
+            ("r", false, false),
+            ("r,y", true, false),
+            ("r,y,g", true, true),
+        ] {
+            let race = race.parse::<Race>().expect("to parse");
+            let dice = "r".parse::<Dice>().expect("to parse");
+
+            for chances in [project(&race, &dice), project_count_only(&race, &dice)] {
+                assert_eq!(chances.camels(), race.ranking().len());
+                assert_eq!(chances.runner_up.values().count() > 0, runner_up);
+                assert_eq!(chances.loser.values().count() > 0, loser);
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_projections_agree_with_the_tree() {
+        let race = "r,o,-,y,,g".parse::<Race>().expect("to parse");
+        let dice = "rog".parse::<Dice>().expect("to parse");
+
+        let (tree, enumeration) = project_within(&race, &dice, u64::MAX);
+        assert_eq!(enumeration, Enumeration::Tree);
+        let (streamed, enumeration) = project_within(&race, &dice, 0);
+        assert_eq!(enumeration, Enumeration::Streaming);
+
+        for (place, distribution) in tree.places.iter().enumerate() {
+            for (camel, chance) in distribution.values() {
+                assert_eq!(streamed.places[place][camel], *chance);
+            }
+        }
+    }
+
+    #[test]
+    fn distributions_iterate_in_the_order_of_the_camels() {
+        let race = "w,g,o,r".parse::<Race>().expect("to parse");
+        let dice = "wgor".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+
+        let camels: Vec<Camel> = chances.winner.values().map(|(camel, _)| *camel).collect();
+
+        assert_eq!(
+            camels,
+            vec![Camel::Red, Camel::Orange, Camel::Green, Camel::White]
+        );
+    }
 
     #[test]
     fn should_have_a_clear_winner() {
@@ -147,4 +1474,168 @@ mod test {
         assert_eq!(chances.winner[&Camel::Red], Fraction::new(2, 3));
         assert_eq!(chances.winner[&Camel::Yellow], Fraction::new(1, 3));
     }
+
+    #[test]
+    fn should_approximate_chances_by_sampling() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "r".parse::<Dice>().expect("to parse");
+        let mut rng = StdRng::seed_from_u64(37);
+        let chances = sample(&race, &dice, 3000, &mut rng);
+
+        let red = chances.winner[&Camel::Red].to_f64();
+        assert!((red - 2.0 / 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn sampling_is_reproducible() {
+        let race = "r,,y,o".parse::<Race>().expect("to parse");
+        let dice = "ryo".parse::<Dice>().expect("to parse");
+
+        let first = sample(&race, &dice, 100, &mut StdRng::seed_from_u64(1));
+        let second = sample(&race, &dice, 100, &mut StdRng::seed_from_u64(1));
+
+        for camel in &[Camel::Red, Camel::Yellow, Camel::Orange] {
+            assert_eq!(first.winner[camel], second.winner[camel]);
+        }
+    }
+
+    #[test]
+    fn progress_ends_with_the_total() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let mut reports = Vec::new();
+
+        project_with_progress(&race, &dice, |done, total| reports.push((done, total)));
+
+        assert_eq!(reports.last(), Some(&(tree_size(&dice), tree_size(&dice))));
+    }
+
+    #[test]
+    fn matchups_are_complementary() {
+        let race = "r,o,,y".parse::<Race>().expect("to parse");
+        let dice = "roy".parse::<Dice>().expect("to parse");
+
+        let ahead = matchup(&race, &dice, Camel::Red, Camel::Yellow);
+        let behind = matchup(&race, &dice, Camel::Yellow, Camel::Red);
+
+        assert_eq!(ahead + behind, Fraction::one());
+    }
+
+    #[test]
+    fn all_matchups_agree_with_a_single_matchup() {
+        let race = "r,o,,y".parse::<Race>().expect("to parse");
+        let dice = "roy".parse::<Dice>().expect("to parse");
+
+        let chances = matchups(&race, &dice);
+
+        assert_eq!(chances.len(), 6);
+        for ((camel, rival), chance) in chances {
+            assert_eq!(chance, matchup(&race, &dice, camel, rival));
+        }
+    }
+
+    #[test]
+    fn explanations_agree_with_projections() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+        let explanation = explain(&race, &dice, 1);
+
+        assert_eq!(explanation.total, 18);
+        for camel in [Camel::Red, Camel::Yellow] {
+            assert_eq!(
+                Fraction::new(explanation.winner[&camel] as i64, 18),
+                chances.winner[&camel]
+            );
+        }
+        assert_eq!(
+            explanation.examples[&Camel::Red],
+            vec![vec![
+                Roll::from((Camel::Red, Face::Two)),
+                Roll::from((Camel::Yellow, Face::One))
+            ]]
+        );
+    }
+
+    #[test]
+    fn camels_without_a_die_do_not_land() {
+        let race = "r,+,y".parse::<Race>().expect("to parse");
+        let dice = "y".parse::<Dice>().expect("to parse");
+
+        assert_eq!(expected_landings(&race, &dice, 1), Some(Fraction::zero()));
+    }
+
+    #[test]
+    fn landings_accumulate_over_the_leg() {
+        let race = "r,y,,+".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+
+        assert_eq!(
+            expected_landings(&race, &dice, 3),
+            Some(Fraction::new(2, 3))
+        );
+    }
+
+    #[test]
+    fn landings_need_a_trap() {
+        let empty = "".parse::<Race>().expect("to parse");
+        let dice = "".parse::<Dice>().expect("to parse");
+
+        assert_eq!(expected_landings(&empty, &dice, 1), None);
+        assert_eq!(
+            expected_landings(&"r,,+".parse::<Race>().expect("to parse"), &dice, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn races_without_camels_have_no_chances() {
+        let dice = "".parse::<Dice>().expect("to parse");
+        let mut rng = StdRng::seed_from_u64(37);
+
+        for race in ["!", ",,!"] {
+            let race = race.parse::<Race>().expect("to parse");
+            let chances = sample_race(&race, &dice, 100, &mut rng);
+
+            assert_eq!(chances.camels(), 0);
+            assert_eq!(chances.winner.values().count(), 0);
+        }
+    }
+
+    #[test]
+    fn should_sample_complete_races() {
+        let race = "r,,,,,,,,,y,!".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let mut rng = StdRng::seed_from_u64(37);
+        let chances = sample_race(&race, &dice, 100, &mut rng);
+
+        assert!(chances.winner[&Camel::Yellow] > chances.winner[&Camel::Red]);
+        assert_eq!(
+            chances.winner[&Camel::Yellow] + chances.winner[&Camel::Red],
+            Fraction::one()
+        );
+    }
+
+    #[test]
+    fn should_determine_places() {
+        let race = "r,,y,o".parse::<Race>().expect("to parse");
+        let dice = "r".parse::<Dice>().expect("to parse");
+        let chances = project(&race, &dice);
+
+        assert_eq!(chances.places.len(), 3);
+        assert_eq!(chances.places[0][&Camel::Orange], Fraction::new(2, 3));
+        assert_eq!(chances.places[1][&Camel::Yellow], Fraction::new(1, 3));
+        assert_eq!(chances.places[1][&Camel::Red], Fraction::new(1, 3));
+        assert_eq!(chances.places[2][&Camel::Red], Fraction::new(1, 3));
+    }
+
+    #[test]
+    fn should_determine_expected_positions() {
+        let race = "r,y,,!".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+        let expected = expected_positions(&race, &dice);
+
+        assert_eq!(expected[&Camel::Red], Fraction::new(7, 3));
+        assert_eq!(expected[&Camel::Yellow], Fraction::from(3));
+    }
 }
@@ -0,0 +1,99 @@
+//! Projections on a pool of threads.
+//!
+//! The tree of rolls is split into the branches of the first rolls, which are expanded and counted on a [rayon](https://docs.rs/rayon) thread pool. Threads that run out of branches steal them from busy threads, and the counts of the branches are merged into a single projection.
+//!
+//! The other counting queries of the oracle, like `matchups` and `expected_positions`, split their leaves the same way on the global thread pool when this feature is enabled.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::oracle::project_parallel;
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//! let dice = "ry".parse::<Dice>().expect("to parse");
+//!
+//! let chances = project_parallel(&race, &dice, Some(2)).expect("a thread pool");
+//!
+//! assert_eq!(chances.winner[&Camel::Red], project(&race, &dice).winner[&Camel::Red]);
+//! ```
+//!
+//! This module is only available with the `parallel` feature.
+use super::{perform, LeafCounter, LegChances};
+use crate::{
+    camel::{Dice, Face, Race},
+    tree::{ReducibleVisitor, Tree},
+};
+use rayon::{prelude::*, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// The number of rolls that are split into branches. Every branch of the first roll is as large as the others, so the second roll is split as well to keep the threads busy until the end.
+const SPLIT_DEPTH: usize = 2;
+
+/// Determines the win chances for each camel, like `project`, on a pool of threads.
+///
+/// Without a number of threads, the pool has as many threads as the `RAYON_NUM_THREADS` environment variable asks for, or else one per CPU. Fails when the pool can not be built.
+pub fn project_parallel(
+    race: &Race,
+    dice: &Dice,
+    threads: Option<usize>,
+) -> Result<LegChances, ThreadPoolBuildError> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
+        .build()?;
+    let counter = pool.install(|| visit(race, dice, &LeafCounter::<usize>::default()));
+    Ok(counter.chances())
+}
+
+/// Visits the leaves of the leg with visitors like `prototype`, on the current thread pool, and merges them.
+pub(super) fn visit<V>(race: &Race, dice: &Dice, prototype: &V) -> V
+where
+    V: ReducibleVisitor,
+{
+    visit_branches(race, dice, prototype, SPLIT_DEPTH)
+}
+
+fn visit_branches<V>(race: &Race, dice: &Dice, prototype: &V, depth: usize) -> V
+where
+    V: ReducibleVisitor,
+{
+    if depth == 0 || dice.is_empty() {
+        let mut tree = Tree::singleton(*race);
+        tree.expand(dice);
+        let mut visitor = prototype.identity();
+        tree.visit_leaves(&mut visitor);
+        return visitor;
+    }
+    let branches: Vec<(Race, Dice)> = dice
+        .iter()
+        .flat_map(|camel| {
+            Face::values()
+                .into_iter()
+                .map(move |face| (perform(race, (camel, face)), dice.remove(camel)))
+        })
+        .collect();
+    branches
+        .par_iter()
+        .map(|(race, dice)| visit_branches(race, dice, prototype, depth - 1))
+        .reduce(|| prototype.identity(), V::merge)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle::project;
+
+    #[test]
+    fn parallel_projections_agree_with_projections() {
+        let race = "r,o,+,y,,g,w".parse::<Race>().expect("to parse");
+        let dice = "royg".parse::<Dice>().expect("to parse");
+
+        let parallel = project_parallel(&race, &dice, Some(3)).expect("a thread pool");
+        let sequential = project(&race, &dice);
+
+        for (camel, chance) in sequential.winner.values() {
+            assert_eq!(parallel.winner[camel], *chance);
+        }
+        for (place, distribution) in sequential.places.iter().enumerate() {
+            for (camel, chance) in distribution.values() {
+                assert_eq!(parallel.places[place][camel], *chance);
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Enumerates the ways a leg can play out without building a tree.
+//!
+//! Every way the leg can play out is an order of the dice, together with a face for each die. `Permutations` goes over them one by one and yields the race at the end of each, so pure counting queries can fold over the races without keeping any of them.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::oracle::Permutations;
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//! let dice = "ry".parse::<Dice>().expect("to parse");
+//!
+//! let legs = Permutations::new(&race, &dice);
+//! assert_eq!(legs.len(), 2 * 3 * 3);
+//!
+//! let red_wins = legs.filter(|race| race.winner() == Some(Camel::Red)).count();
+//! assert_eq!(Fraction::new(red_wins as i64, 18), project(&race, &dice).winner[&Camel::Red]);
+//! ```
+use super::perform;
+use crate::camel::{Dice, Face, Race};
+use std::iter::FusedIterator;
+
+const FACES: [Face; 3] = [Face::One, Face::Two, Face::Three];
+
+/// Iterates over the races at the end of every way the leg can play out.
+///
+/// The rolls are counted like an odometer. The digit of a roll picks one of the remaining dice and its face, and the race after each roll is kept, so that moving to the next way only performs the rolls after the digit that changed.
+pub struct Permutations {
+    /// The digit of each roll, a remaining die times three plus the face.
+    digits: Vec<usize>,
+    /// The dice in the pyramid before each roll.
+    dice: Vec<Dice>,
+    /// The race before each roll, and the race at the end of the leg.
+    races: Vec<Race>,
+    remaining: usize,
+}
+
+impl Permutations {
+    /// The ways the leg of the race can play out with the dice.
+    pub fn new(race: &Race, dice: &Dice) -> Self {
+        let rolls = dice.len();
+        let mut permutations = Self {
+            digits: vec![0; rolls],
+            dice: vec![*dice; rolls + 1],
+            races: vec![*race; rolls + 1],
+            remaining: (1..=rolls)
+                .map(|remaining| remaining * FACES.len())
+                .product(),
+        };
+        permutations.perform_from(0);
+        permutations
+    }
+
+    /// Performs the rolls from the given one, according to the digits.
+    fn perform_from(&mut self, start: usize) {
+        for roll in start..self.digits.len() {
+            let digit = self.digits[roll];
+            let camel = self.dice[roll]
+                .iter()
+                .nth(digit / FACES.len())
+                .unwrap(/* the digit stays below three times the remaining dice */);
+            let face = FACES[digit % FACES.len()];
+            self.races[roll + 1] = perform(&self.races[roll], (camel, face));
+            self.dice[roll + 1] = self.dice[roll].remove(camel);
+        }
+    }
+
+    /// Moves the digits to the next way the leg can play out.
+    fn advance(&mut self) {
+        let last = self
+            .digits
+            .iter()
+            .enumerate()
+            .rposition(|(roll, digit)| digit + 1 < self.dice[roll].len() * FACES.len());
+        if let Some(roll) = last {
+            self.digits[roll] += 1;
+            for digit in &mut self.digits[roll + 1..] {
+                *digit = 0;
+            }
+            self.perform_from(roll);
+        }
+    }
+}
+
+impl Iterator for Permutations {
+    type Item = Race;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let race = self.races[self.digits.len()];
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.advance();
+        }
+        Some(race)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Permutations {}
+
+impl FusedIterator for Permutations {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{fraction::Fraction, oracle::final_races};
+    use std::collections::HashMap;
+
+    #[test]
+    fn permutations_end_in_the_races_of_the_tree() {
+        let race = "r,o,+,y,,g".parse::<Race>().expect("to parse");
+        let dice = "ryg".parse::<Dice>().expect("to parse");
+
+        let mut counts: HashMap<Race, usize> = HashMap::new();
+        for race in Permutations::new(&race, &dice) {
+            *counts.entry(race).or_insert(0) += 1;
+        }
+        let expected = final_races(&race, &dice);
+
+        assert_eq!(counts.values().sum::<usize>(), 3 * 3 * 2 * 3 * 3);
+        assert_eq!(counts.len(), expected.len());
+        for (race, count) in counts {
+            assert_eq!(
+                expected[&race],
+                Fraction::new(count as i64, 3 * 3 * 2 * 3 * 3)
+            );
+        }
+    }
+
+    #[test]
+    fn without_dice_the_leg_ends_where_it_starts() {
+        let race = "r,y".parse::<Race>().expect("to parse");
+
+        let legs: Vec<Race> =
+            Permutations::new(&race, &"".parse::<Dice>().expect("to parse")).collect();
+
+        assert_eq!(legs, vec![race]);
+    }
+}
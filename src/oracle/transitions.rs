@@ -0,0 +1,81 @@
+//! A table of the races that rolls lead to, shared between projections.
+//!
+//! Projecting a race performs every roll of every remaining die on every race in the tree. Workflows that ask about the same board over and over, like a session that follows a game or an advisor weighing its options, perform the same rolls on the same races each time. A `Transitions` table remembers the outcome of every roll it performed, so later projections look the outcome up instead.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::oracle::{project_with_transitions, Transitions};
+//! let race = "r,y".parse::<Race>().expect("to parse");
+//! let dice = "ry".parse::<Dice>().expect("to parse");
+//! let mut transitions = Transitions::precomputed(&race, &dice);
+//! let known = transitions.len();
+//!
+//! let chances = project_with_transitions(&race, &dice, &mut transitions);
+//!
+//! assert_eq!(chances.winner[&Camel::Red], project(&race, &dice).winner[&Camel::Red]);
+//! assert_eq!(transitions.len(), known);
+//! ```
+use crate::camel::{Dice, Face, Race, Roll};
+use std::collections::HashMap;
+
+/// The race that a roll leads to, for every race and roll performed so far.
+#[derive(Clone, Debug, Default)]
+pub struct Transitions {
+    table: HashMap<(Race, Roll), Race>,
+}
+
+impl Transitions {
+    /// A table with the transitions of every roll of the dice that can occur in the leg.
+    pub fn precomputed(race: &Race, dice: &Dice) -> Self {
+        let mut transitions = Self::default();
+        transitions.precompute(race, dice);
+        transitions
+    }
+
+    /// Adds the transitions of every roll of the dice that can occur in the leg.
+    pub fn precompute(&mut self, race: &Race, dice: &Dice) {
+        for camel in dice {
+            let remaining = dice.remove(camel);
+            for face in Face::values() {
+                let next = self.perform(race, Roll::from((camel, face)));
+                self.precompute(&next, &remaining);
+            }
+        }
+    }
+
+    /// The race that the roll leads to, performed only when it is not in the table yet.
+    pub fn perform(&mut self, race: &Race, roll: Roll) -> Race {
+        *self
+            .table
+            .entry((*race, roll))
+            .or_insert_with(|| super::perform(race, roll))
+    }
+
+    /// The number of transitions in the table.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Whether the table has no transitions.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn identical_rolls_are_performed_once() {
+        let race = "r,,,,y,,,,g".parse::<Race>().expect("to parse");
+        let dice = "ryg".parse::<Dice>().expect("to parse");
+
+        let transitions = Transitions::precomputed(&race, &dice);
+
+        assert!(transitions.len() < 3 * 3 + 3 * 3 * 2 * 3 + 3 * 3 * 2 * 3 * 3);
+        for (race, roll) in transitions.table.keys() {
+            assert_eq!(transitions.table[&(*race, *roll)], race.perform(*roll));
+        }
+    }
+}
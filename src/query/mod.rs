@@ -0,0 +1,346 @@
+//! A small language to ask the oracle questions in a single string.
+//!
+//! A query is a function applied to an event, given a number of conditions: `P(win=red | race=r,,w; rolled=w2)` asks for the chance that red wins the leg, after white rolled a two. The answer is tagged with the function that was asked for.
+//!
+//! | Function | Events                                  | Answer                                      |
+//! |----------|-----------------------------------------|---------------------------------------------|
+//! | `P`      | `win=r`, `second=r`, `lose=r`           | the chance the camel ends the leg like that |
+//! | `EV`     | `ticket=r5`                             | the coins the leg betting ticket earns      |
+//!
+//! The conditions are separated by `;`.
+//!
+//! | Condition        | Meaning                                                                         |
+//! |------------------|---------------------------------------------------------------------------------|
+//! | `race=r,,w`      | the race, this condition is required                                            |
+//! | `dice=rw`        | the dice in the pyramid, defaults to the dice of every camel in the race        |
+//! | `rolled=w2`      | a roll that happened, the die leaves the pyramid. Several rolls are separated by `,` and performed in order |
+//! | `edition=second` | the edition of the rules, defaults to the first edition                         |
+//!
+//! Camels are written with their letter or their color, i.e. `r` or `red`.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::query::{ask, Answer};
+//! let answer = ask("P(win=red | race=r,,y; dice=r)").expect("to answer");
+//!
+//! assert_eq!(answer, Answer::Probability(Fraction::new(2, 3)));
+//! assert_eq!(answer.to_string(), "P=2/3");
+//! ```
+use crate::{
+    bets::{Ticket, TicketParseError},
+    camel::{
+        Camel, Dice, Edition, Marker, MoveError, NoDice, NoEdition, Race, RaceParseError, Roll,
+        RollParseError,
+    },
+    fraction::Fraction,
+    oracle::project,
+    wire,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Answers the query, see the module documentation for the language.
+pub fn ask(query: &str) -> Result<Answer, QueryError> {
+    query.parse::<Query>().map(|query| query.answer())
+}
+
+/// The answer to a query, tagged with the function that was asked for.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Answer {
+    /// The chance of an event, asked for with `P`.
+    Probability(Fraction),
+    /// The expected number of coins, asked for with `EV`.
+    ExpectedValue(Fraction),
+}
+
+impl Answer {
+    /// The value of the answer, regardless of its tag.
+    pub fn value(&self) -> Fraction {
+        match self {
+            Answer::Probability(value) | Answer::ExpectedValue(value) => *value,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Probability(value) => write!(f, "P={}", value),
+            Answer::ExpectedValue(value) => write!(f, "EV={}", value),
+        }
+    }
+}
+
+/// The event a query asks about.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Event {
+    /// The camel wins the leg.
+    Win(Camel),
+    /// The camel is runner up at the end of the leg.
+    Second(Camel),
+    /// The camel is last at the end of the leg.
+    Lose(Camel),
+    /// The coins the leg betting ticket earns.
+    Ticket(Ticket),
+}
+
+/// A parsed query, with the rolls that happened performed on the race.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Query {
+    /// What is asked for.
+    pub event: Event,
+    /// The race, after the rolls.
+    pub race: Race,
+    /// The dice in the pyramid, after the rolls.
+    pub dice: Dice,
+}
+
+impl Query {
+    /// Asks the oracle.
+    pub fn answer(&self) -> Answer {
+        let chances = project(&self.race, &self.dice);
+        match self.event {
+            Event::Win(camel) => Answer::Probability(chances.winner[&camel]),
+            Event::Second(camel) => Answer::Probability(chances.runner_up[&camel]),
+            Event::Lose(camel) => Answer::Probability(chances.loser[&camel]),
+            Event::Ticket(ticket) => Answer::ExpectedValue(ticket.expected_value(&chances)),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let (function, rest) = input
+            .split_once('(')
+            .ok_or_else(|| QueryError::NotAQuery(input.to_owned()))?;
+        let inner = rest
+            .strip_suffix(')')
+            .ok_or_else(|| QueryError::NotAQuery(input.to_owned()))?;
+        let (event, conditions) = inner.split_once('|').unwrap_or((inner, ""));
+        let event = event_of(function.trim(), event.trim())?;
+
+        let mut race = None;
+        let mut dice = None;
+        let mut rolls = Vec::new();
+        let mut edition = Edition::default();
+        for condition in conditions.split(';').map(str::trim) {
+            if condition.is_empty() {
+                continue;
+            }
+            let (key, value) = condition
+                .split_once('=')
+                .ok_or_else(|| QueryError::NotACondition(condition.to_owned()))?;
+            let value = value.trim();
+            match key.trim() {
+                "race" => race = Some(value.parse::<Race>()?),
+                "dice" => dice = Some(value.parse::<Dice>()?),
+                "rolled" => {
+                    for roll in value.split(',') {
+                        rolls.push(roll.trim().parse::<Roll>()?);
+                    }
+                }
+                "edition" => edition = value.parse::<Edition>()?,
+                _ => return Err(QueryError::NotACondition(condition.to_owned())),
+            }
+        }
+        let mut race = race.ok_or(QueryError::NoRace)?.with_edition(edition);
+        let mut dice = dice.unwrap_or_else(|| Dice::from(&race));
+        race.check_dice(&dice)?;
+        for roll in rolls {
+            if !dice.contains(roll.camel()) {
+                return Err(QueryError::NotInPyramid(roll.camel()));
+            }
+            race = race.perform_checked(roll)?;
+            dice = dice.remove(roll.camel());
+        }
+        Ok(Self { event, race, dice })
+    }
+}
+
+fn event_of(function: &str, event: &str) -> Result<Event, QueryError> {
+    let (kind, value) = event
+        .split_once('=')
+        .map(|(kind, value)| (kind.trim(), value.trim()))
+        .ok_or_else(|| QueryError::NotAnEvent(event.to_owned()))?;
+    match (function, kind) {
+        ("P", "win") => camel_of(value).map(Event::Win),
+        ("P", "second") => camel_of(value).map(Event::Second),
+        ("P", "lose") => camel_of(value).map(Event::Lose),
+        ("EV", "ticket") => value
+            .parse::<Ticket>()
+            .map(Event::Ticket)
+            .map_err(|problem| QueryError::Ticket(value.to_owned(), problem)),
+        ("P", _) | ("EV", _) => Err(QueryError::NotAnEvent(event.to_owned())),
+        _ => Err(QueryError::UnknownFunction(function.to_owned())),
+    }
+}
+
+/// The camel for its letter or its color.
+fn camel_of(input: &str) -> Result<Camel, QueryError> {
+    input
+        .parse::<Marker>()
+        .ok()
+        .and_then(Marker::to_camel)
+        .or_else(|| {
+            [
+                Camel::Red,
+                Camel::Orange,
+                Camel::Yellow,
+                Camel::Green,
+                Camel::White,
+            ]
+            .iter()
+            .copied()
+            .find(|camel| wire::name(*camel) == input)
+        })
+        .ok_or_else(|| QueryError::NotACamel(input.to_owned()))
+}
+
+/// When a query can not be answered, this enumeration tells you precisely what went down.
+#[derive(PartialEq, Debug)]
+pub enum QueryError {
+    /// The input is not of the form `P(...)`.
+    NotAQuery(String),
+    /// The function is not one of `P` or `EV`.
+    UnknownFunction(String),
+    /// The event is not one the function knows about.
+    NotAnEvent(String),
+    /// The condition is not one of `race`, `dice`, `rolled` or `edition`.
+    NotACondition(String),
+    /// The input is neither the letter nor the color of a camel.
+    NotACamel(String),
+    /// The query does not tell the race.
+    NoRace,
+    /// The race could not be parsed.
+    Race(RaceParseError),
+    /// The dice could not be parsed.
+    Dice(NoDice),
+    /// A roll could not be parsed.
+    Roll(RollParseError),
+    /// The ticket could not be parsed. The argument tells you what it was.
+    Ticket(String, TicketParseError),
+    /// The edition is not known.
+    Edition(NoEdition),
+    /// A roll is for a die that is not in the pyramid.
+    NotInPyramid(Camel),
+    /// A die is of a camel that is not in the race.
+    Move(MoveError),
+}
+
+impl From<RaceParseError> for QueryError {
+    fn from(problem: RaceParseError) -> Self {
+        Self::Race(problem)
+    }
+}
+
+impl From<NoDice> for QueryError {
+    fn from(problem: NoDice) -> Self {
+        Self::Dice(problem)
+    }
+}
+
+impl From<RollParseError> for QueryError {
+    fn from(problem: RollParseError) -> Self {
+        Self::Roll(problem)
+    }
+}
+
+impl From<MoveError> for QueryError {
+    fn from(problem: MoveError) -> Self {
+        Self::Move(problem)
+    }
+}
+
+impl From<NoEdition> for QueryError {
+    fn from(problem: NoEdition) -> Self {
+        Self::Edition(problem)
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::NotAQuery(input) => write!(f, "'{}' is not of the form P(...)", input),
+            QueryError::UnknownFunction(function) => {
+                write!(f, "'{}' is not one of the functions P or EV", function)
+            }
+            QueryError::NotAnEvent(event) => write!(f, "'{}' is not an event", event),
+            QueryError::NotACondition(condition) => {
+                write!(f, "'{}' is not a condition", condition)
+            }
+            QueryError::NotACamel(input) => write!(f, "'{}' is not a camel", input),
+            QueryError::NoRace => write!(f, "the race is missing"),
+            QueryError::Race(problem) => write!(f, "could not parse race: {}", problem),
+            QueryError::Dice(problem) => write!(f, "could not parse dice: {}", problem),
+            QueryError::Roll(problem) => write!(f, "could not parse roll: {}", problem),
+            QueryError::Ticket(input, _) => write!(f, "'{}' is not a ticket", input),
+            QueryError::Edition(problem) => write!(f, "{}", problem),
+            QueryError::NotInPyramid(camel) => {
+                write!(f, "the {:?} die is not in the pyramid", camel)
+            }
+            QueryError::Move(problem) => write!(f, "{}", problem),
+        }
+    }
+}
+
+impl Error for QueryError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rolls_are_performed_before_answering() {
+        let answer = ask("P(win=r | race=r,y,w; dice=r; rolled=r3)").expect("to answer");
+
+        assert_eq!(answer, Answer::Probability(Fraction::one()));
+    }
+
+    #[test]
+    fn tickets_have_an_expected_value() {
+        let answer = ask("EV(ticket=r5 | race=r,,y; dice=r)").expect("to answer");
+
+        assert_eq!(answer, Answer::ExpectedValue(Fraction::new(11, 3)));
+        assert_eq!(answer.to_string(), "EV=11/3");
+    }
+
+    #[test]
+    fn camels_can_be_written_with_their_color() {
+        let by_letter = ask("P(lose=y | race=r,,y; dice=r)").expect("to answer");
+        let by_color = ask("P(lose = yellow | race = r,,y ; dice = r)").expect("to answer");
+
+        assert_eq!(by_letter, by_color);
+    }
+
+    #[test]
+    fn problems_are_reported() {
+        assert_eq!(ask("win=r"), Err(QueryError::NotAQuery("win=r".to_owned())));
+        assert_eq!(
+            ask("Q(win=r | race=r)"),
+            Err(QueryError::UnknownFunction("Q".to_owned()))
+        );
+        assert_eq!(
+            ask("EV(win=r | race=r)"),
+            Err(QueryError::NotAnEvent("win=r".to_owned()))
+        );
+        assert_eq!(ask("P(win=r)"), Err(QueryError::NoRace));
+        assert_eq!(
+            ask("P(win=r | race=r,y; dice=y; rolled=r1)"),
+            Err(QueryError::NotInPyramid(Camel::Red))
+        );
+        assert_eq!(
+            ask("P(win=r | race=r,y; dice=rw)"),
+            Err(QueryError::Move(MoveError::CamelNotInRace(Camel::White)))
+        );
+        assert_eq!(
+            ask("P(win=r | race=r; weather=sunny)").map_err(|problem| problem.to_string()),
+            Err("'weather=sunny' is not a condition".to_owned())
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! The rules a game follows.
+//!
+//! The editions of Camel Up differ in their rules, and groups of players like to adjust them. A `RuleSet` gathers the rules that vary, so that the engine and the oracle follow them rather than assume them.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::camel::{Edition, Stacking};
+//! let rules = RuleSet {
+//!     fata_morgana: Stacking::Under,
+//!     leg_tickets: vec![1, 2, 3, 5],
+//!     ..RuleSet::from(Edition::Classic)
+//! };
+//! let race = "r,y,,,,!".parse::<Race>().expect("to parse");
+//!
+//! let state = GameState::with_rules(race, 2, rules).expect("to fit the track");
+//!
+//! assert_eq!(state.race.stacking(), Stacking::Under);
+//! assert_eq!(state.leg_tickets[&Camel::Red], vec![1, 2, 3, 5]);
+//! ```
+//!
+//! Races carry the rules that decide how camels move, see `Race::with_rules`. The oracle projects a race with the rules it carries. A game refuses races that are longer than its track.
+use crate::{
+    advisor::OVERALL_PENALTY,
+    camel::{Edition, Stacking},
+    game::{leg_tickets, setup::TRACK_LENGTH, OVERALL_PAYOUTS, START_COINS},
+};
+use std::ops::RangeInclusive;
+
+/// The numbers of players the official rules are written for.
+pub const PLAYERS: RangeInclusive<usize> = 3..=8;
+
+/// What every player starts with, which can depend on the number of players.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Seating {
+    /// The coins each player starts the game with.
+    pub start_coins: i64,
+    /// The number of spectator tiles each player can have on the track at the same time.
+    pub spectator_tiles: usize,
+}
+
+impl Default for Seating {
+    fn default() -> Self {
+        Self {
+            start_coins: START_COINS,
+            spectator_tiles: 1,
+        }
+    }
+}
+
+/// The modules of the Supercup expansion.
+///
+/// The expansion is off by default, see `RuleSet::with_supercup`. This crate models the modules as follows:
+///
+/// * the extension board adds tiles to the track,
+/// * once per leg a player can place the betting photographer on a tile, and earns a coin for every camel unit that lands on it. The photographer does not move camels and returns at the end of the leg,
+/// * extra wager tiles are added to the bottom of the stack of leg betting tickets of every camel.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Supercup {
+    /// The number of tiles the extension board adds to the track.
+    pub extension: usize,
+    /// Whether players can place the betting photographer.
+    pub photographer: bool,
+    /// The values of the extra wager tiles for every camel, from the bottom of the stack up.
+    pub wager_tiles: Vec<u8>,
+}
+
+impl Default for Supercup {
+    fn default() -> Self {
+        Self {
+            extension: 4,
+            photographer: true,
+            wager_tiles: vec![1],
+        }
+    }
+}
+
+/// The rules that can vary between games.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RuleSet {
+    /// The edition the rules are based on.
+    pub edition: Edition,
+    /// Where camel units end up when they land on a fata morgana.
+    pub fata_morgana: Stacking,
+    /// The number of tiles on the track, the finish lies beyond the last one.
+    pub board_length: usize,
+    /// Whether players can form partnerships and share the winnings of their leg bets.
+    pub partnerships: bool,
+    /// The values of the leg betting tickets of a camel, from the bottom of the stack to the top.
+    pub leg_tickets: Vec<u8>,
+    /// The payouts for correct bets on the overall winner or loser, in the order the bets were placed. Later correct bets are paid the last payout.
+    pub overall_payouts: Vec<i64>,
+    /// The coins lost for an incorrect bet on the overall winner or loser.
+    pub overall_penalty: i64,
+    /// The numbers of players the rules are written for.
+    pub players: RangeInclusive<usize>,
+    /// What the players start with for every number of players, starting with the fewest players in `players`.
+    pub seating: Vec<Seating>,
+    /// The modules of the Supercup expansion that are played, if any.
+    pub supercup: Option<Supercup>,
+}
+
+impl RuleSet {
+    /// Returns these rules, playing with the modules of the Supercup expansion.
+    ///
+    /// The track becomes longer and the wager tiles join the stacks of leg betting tickets.
+    ///
+    /// ```
+    /// # use camel_up::rules::{RuleSet, Supercup};
+    /// let rules = RuleSet::default().with_supercup(Supercup::default());
+    ///
+    /// assert_eq!(rules.board_length, 20);
+    /// assert_eq!(rules.leg_tickets, vec![1, 2, 3, 5]);
+    /// assert!(rules.has_photographer());
+    /// ```
+    pub fn with_supercup(self, supercup: Supercup) -> Self {
+        let mut leg_tickets = supercup.wager_tiles.clone();
+        leg_tickets.extend(self.leg_tickets);
+        Self {
+            board_length: self.board_length + supercup.extension,
+            leg_tickets,
+            supercup: Some(supercup),
+            ..self
+        }
+    }
+
+    /// Whether players can place the betting photographer of the Supercup expansion.
+    pub fn has_photographer(&self) -> bool {
+        self.supercup
+            .as_ref()
+            .is_some_and(|supercup| supercup.photographer)
+    }
+
+    /// The payout of a correct bet on the outcome of the race, when the given number of correct bets were placed before it.
+    pub fn overall_payout(&self, earlier: usize) -> i64 {
+        self.overall_payouts
+            .get(earlier)
+            .or_else(|| self.overall_payouts.last())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// What every player starts with in a game with the number of players.
+    ///
+    /// Numbers outside of `players` follow the nearest number the rules are written for.
+    pub fn seating(&self, players: usize) -> Seating {
+        let index = players.saturating_sub(*self.players.start());
+        self.seating
+            .get(index)
+            .or_else(|| self.seating.last())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl From<Edition> for RuleSet {
+    fn from(edition: Edition) -> Self {
+        Self {
+            edition,
+            fata_morgana: Stacking::from(edition),
+            board_length: TRACK_LENGTH,
+            partnerships: edition.has_partner_bets(),
+            leg_tickets: leg_tickets(edition).to_vec(),
+            overall_payouts: OVERALL_PAYOUTS.to_vec(),
+            overall_penalty: OVERALL_PENALTY,
+            players: PLAYERS,
+            seating: PLAYERS.map(|_| Seating::default()).collect(),
+            supercup: None,
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::from(Edition::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::advisor::OVERALL_REWARD;
+
+    #[test]
+    fn editions_determine_the_defaults() {
+        let rules = RuleSet::from(Edition::Second);
+
+        assert_eq!(rules.fata_morgana, Stacking::Under);
+        assert!(rules.partnerships);
+        assert_eq!(rules.leg_tickets, vec![2, 2, 3, 5]);
+        assert_eq!(RuleSet::default().leg_tickets, vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn seating_follows_the_number_of_players() {
+        let rules = RuleSet {
+            seating: vec![
+                Seating {
+                    start_coins: 5,
+                    spectator_tiles: 2,
+                },
+                Seating::default(),
+            ],
+            ..RuleSet::default()
+        };
+
+        assert_eq!(rules.seating(2).start_coins, 5);
+        assert_eq!(rules.seating(3).spectator_tiles, 2);
+        assert_eq!(rules.seating(4), Seating::default());
+        assert_eq!(rules.seating(8), Seating::default());
+    }
+
+    #[test]
+    fn late_bets_are_paid_the_last_payout() {
+        let rules = RuleSet::default();
+
+        assert_eq!(rules.overall_payout(0), OVERALL_REWARD);
+        assert_eq!(rules.overall_payout(7), 1);
+    }
+}
@@ -0,0 +1,193 @@
+//! Answers questions for the oracle over HTTP, for clients that can not link Rust directly.
+//!
+//! The handlers speak the JSON wire format of the `wire` module.
+//!
+//! | Request                              | Response                                          |
+//! |--------------------------------------|---------------------------------------------------|
+//! | `GET /chances?race=r,,w&dice=rw`     | the chances at the end of the leg                 |
+//! | `GET /schema`                        | the JSON Schema of the wire format                |
+//!
+//! The dice default to the dice of every camel in the race, and `edition=second` selects the rules of the second edition. An oasis can be sent as `+` or as `%2B`. Problems are answered with a status code and a JSON object with an `error`.
+//!
+//! A request never builds a tree of rolls larger than `MEMORY_BUDGET` bytes, larger projections stream their leaves instead. The `X-Enumeration` header of the answer tells which was used.
+//!
+//! ```
+//! # use camel_up::server::handle;
+//! let response = handle("GET", "/chances?race=r,y&dice=r");
+//!
+//! assert_eq!(response.status, 200);
+//! assert!(response.body.starts_with(r#"{"winner":{"red":"1"}"#));
+//! ```
+//!
+//! This module is only available with the `server` feature.
+use crate::{
+    camel::{Edition, Race},
+    oracle::Enumeration,
+    wire::{Request, SCHEMA},
+};
+use std::error::Error;
+use tiny_http::{Header, Response as HttpResponse, Server};
+
+/// The most bytes a request may take for the tree of rolls, see `oracle::project_within`.
+pub const MEMORY_BUDGET: u64 = 256 * 1024 * 1024;
+
+/// The answer to a request.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The JSON body.
+    pub body: String,
+    /// How the chances were projected, for answers with chances.
+    pub enumeration: Option<Enumeration>,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self {
+            status: 200,
+            body,
+            enumeration: None,
+        }
+    }
+
+    fn error(status: u16, problem: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": problem }).to_string(),
+            enumeration: None,
+        }
+    }
+}
+
+/// Answers the request with the method for the url, which holds the path and the query.
+pub fn handle(method: &str, url: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, "only GET is supported");
+    }
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (url, ""),
+    };
+    match path {
+        "/chances" => chances(query).unwrap_or_else(|problem| Response::error(400, &problem)),
+        "/schema" => Response::ok(SCHEMA.to_owned()),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn chances(query: &str) -> Result<Response, String> {
+    let mut race = None;
+    let mut dice = None;
+    let mut edition = Edition::default();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode(value).ok_or_else(|| format!("'{}' is not encoded properly", value))?;
+        match key {
+            "race" => {
+                race = Some(
+                    value
+                        .parse::<Race>()
+                        .map_err(|problem| format!("could not parse race: {}", problem))?,
+                )
+            }
+            "dice" => {
+                dice = Some(
+                    value
+                        .parse()
+                        .map_err(|problem| format!("could not parse dice: {}", problem))?,
+                )
+            }
+            "edition" => {
+                edition = value
+                    .parse::<Edition>()
+                    .map_err(|problem| problem.to_string())?
+            }
+            _ => return Err(format!("unknown parameter '{}'", key)),
+        }
+    }
+    let race = race.ok_or("the race is missing")?.with_edition(edition);
+    let request = Request { race, dice };
+    let (chances, enumeration) = request
+        .answer_within(MEMORY_BUDGET)
+        .map_err(|problem| problem.to_string())?;
+    serde_json::to_string(&chances)
+        .map(|body| Response {
+            enumeration: Some(enumeration),
+            ..Response::ok(body)
+        })
+        .map_err(|problem| problem.to_string())
+}
+
+/// Decodes the percent encoded value, a `+` stands for an oasis rather than a space.
+fn decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.bytes();
+    while let Some(byte) = rest.next() {
+        if byte == b'%' {
+            let high = (rest.next()? as char).to_digit(16)?;
+            let low = (rest.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Serves requests on the address, e.g. `127.0.0.1:8080`, until the process ends.
+pub fn serve(address: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server = Server::http(address)?;
+    let json =
+        Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("a valid header");
+    for request in server.incoming_requests() {
+        let response = handle(request.method().as_str(), request.url());
+        let mut answer = HttpResponse::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(json.clone());
+        if let Some(enumeration) = response.enumeration {
+            let header = Header::from_bytes(&b"X-Enumeration"[..], enumeration.to_string())
+                .expect("a valid header");
+            answer.add_header(header);
+        }
+        request.respond(answer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chances_can_be_asked_for() {
+        let response = handle("GET", "/chances?race=r%2C%2Cw&dice=w");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.enumeration, Some(Enumeration::Tree));
+        assert_eq!(
+            response.body,
+            r#"{"winner":{"white":"1"},"runner_up":{"red":"1"},"loser":{},"places":[{"white":"1"},{"red":"1"}]}"#
+        );
+    }
+
+    #[test]
+    fn oases_can_be_sent_as_is() {
+        assert_eq!(decode("r,+,w"), Some("r,+,w".to_owned()));
+        assert_eq!(decode("r,%2B,w"), Some("r,+,w".to_owned()));
+        assert_eq!(decode("%zz"), None);
+    }
+
+    #[test]
+    fn problems_are_reported() {
+        assert_eq!(handle("GET", "/chances?race=r|w").status, 400);
+        assert_eq!(handle("GET", "/chances?dice=r").status, 400);
+        assert_eq!(handle("GET", "/chances?race=r,y&dice=w").status, 400);
+        assert_eq!(handle("GET", "/chances?race=r&edition=third").status, 400);
+        assert_eq!(handle("GET", "/unknown").status, 404);
+        assert_eq!(handle("POST", "/chances").status, 405);
+        assert!(handle("GET", "/chances?race=r|w")
+            .body
+            .starts_with(r#"{"error":"could not parse race"#));
+    }
+}
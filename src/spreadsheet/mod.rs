@@ -0,0 +1,267 @@
+//! Exports the chances of a leg to spreadsheets, for players who do their analysis in Excel or Sheets.
+//!
+//! An export holds three tables.
+//!
+//! | Table             | Rows   | Columns                        | Cells                                                   |
+//! |-------------------|--------|--------------------------------|---------------------------------------------------------|
+//! | `placements`      | camels | the places, from first to last | the chance the camel ends the leg in that place         |
+//! | `matchups`        | camels | camels                         | the chance the camel of the row is ahead of the other   |
+//! | `expected_values` | camels | the values of the tickets      | the coins the leg betting ticket is expected to earn    |
+//!
+//! The tables are written as a bundle of CSV files in a directory, or as the sheets of a single xlsx workbook. LegChances are written as decimals, since spreadsheets read fractions like `2/9` as dates.
+//!
+//! ```no_run
+//! # use camel_up::prelude::*;
+//! # use camel_up::spreadsheet::Export;
+//! let race = "r,,y".parse::<Race>().expect("to parse");
+//! let dice = "ry".parse::<Dice>().expect("to parse");
+//!
+//! let export = Export::new(&race, &dice, &RuleSet::default());
+//!
+//! export.write_csv("leg").expect("to write CSV files");
+//! export.write_xlsx("leg.xlsx").expect("to write a workbook");
+//! ```
+//!
+//! This module is only available with the `spreadsheet` feature.
+use crate::{
+    bets::Ticket,
+    camel::{Camel, Dice, Race},
+    fraction::Fraction,
+    i18n::Language,
+    oracle::{matchups, project},
+    rules::RuleSet,
+};
+use rust_xlsxwriter::{Workbook, XlsxError};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+};
+
+/// A table of chances, with a row per camel.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Table {
+    /// The name of the table, used for the name of the CSV file and the sheet.
+    pub name: &'static str,
+    /// The headings of the columns, starting with the column of camels.
+    pub header: Vec<String>,
+    /// The camel of each row, with a cell for every column after the first. Cells without a value are empty.
+    pub rows: Vec<(Camel, Vec<Option<Fraction>>)>,
+}
+
+impl Table {
+    /// The table as CSV, with a line per row.
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![self.header.join(",")];
+        lines.extend(self.rows.iter().map(|(camel, cells)| {
+            let mut line = vec![Language::English.camel(*camel).to_owned()];
+            line.extend(cells.iter().map(|cell| {
+                cell.map(|value| value.to_f64().to_string())
+                    .unwrap_or_default()
+            }));
+            line.join(",")
+        }));
+        let mut csv = lines.join("\n");
+        csv.push('\n');
+        csv
+    }
+}
+
+/// The placements, matchups and expected values of a leg.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Export {
+    /// The chance of each camel for every place.
+    pub placements: Table,
+    /// The chance of each camel to be ahead of every other camel.
+    pub matchups: Table,
+    /// The expected value of the leg betting tickets of each camel.
+    pub expected_values: Table,
+}
+
+impl Export {
+    /// Projects the race and gathers the tables, with a column for every distinct ticket value of the rules.
+    pub fn new(race: &Race, dice: &Dice, rules: &RuleSet) -> Self {
+        let chances = project(race, dice);
+        let mut camels = race.ranking();
+        camels.sort();
+        camels.dedup();
+
+        let mut header = vec!["camel".to_owned()];
+        header.extend((1..=chances.places.len()).map(|place| place.to_string()));
+        let placements = Table {
+            name: "placements",
+            header,
+            rows: camels
+                .iter()
+                .map(|camel| {
+                    let cells = chances.places.iter().map(|place| Some(place[camel]));
+                    (*camel, cells.collect())
+                })
+                .collect(),
+        };
+
+        let ahead = matchups(race, dice);
+        let mut header = vec!["camel".to_owned()];
+        header.extend(
+            camels
+                .iter()
+                .map(|camel| Language::English.camel(*camel).to_owned()),
+        );
+        let matchups = Table {
+            name: "matchups",
+            header,
+            rows: camels
+                .iter()
+                .map(|camel| {
+                    let cells = camels
+                        .iter()
+                        .map(|rival| ahead.get(&(*camel, *rival)).copied());
+                    (*camel, cells.collect())
+                })
+                .collect(),
+        };
+
+        let mut values = rules.leg_tickets.clone();
+        values.sort_by(|left, right| right.cmp(left));
+        values.dedup();
+        let mut header = vec!["camel".to_owned()];
+        header.extend(values.iter().map(|value| value.to_string()));
+        let expected_values = Table {
+            name: "expected_values",
+            header,
+            rows: camels
+                .iter()
+                .map(|camel| {
+                    let cells = values
+                        .iter()
+                        .map(|value| Ticket::from((*camel, *value)))
+                        .map(|ticket| Some(ticket.expected_value(&chances)));
+                    (*camel, cells.collect())
+                })
+                .collect(),
+        };
+
+        Self {
+            placements,
+            matchups,
+            expected_values,
+        }
+    }
+
+    /// The tables of the export.
+    pub fn tables(&self) -> [&Table; 3] {
+        [&self.placements, &self.matchups, &self.expected_values]
+    }
+
+    /// Writes every table to a CSV file, named after the table, in the directory. The directory is created when it is missing.
+    pub fn write_csv<P: AsRef<Path>>(&self, directory: P) -> Result<(), ExportError> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+        for table in self.tables() {
+            fs::write(
+                directory.join(format!("{}.csv", table.name)),
+                table.to_csv(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The tables as the sheets of an xlsx workbook.
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, ExportError> {
+        Ok(self.workbook()?.save_to_buffer()?)
+    }
+
+    /// Writes the tables as the sheets of an xlsx workbook to the file.
+    pub fn write_xlsx<P: AsRef<Path>>(&self, path: P) -> Result<(), ExportError> {
+        Ok(self.workbook()?.save(path.as_ref())?)
+    }
+
+    fn workbook(&self) -> Result<Workbook, XlsxError> {
+        let mut workbook = Workbook::new();
+        for table in self.tables() {
+            let sheet = workbook.add_worksheet();
+            sheet.set_name(table.name)?;
+            for (column, heading) in table.header.iter().enumerate() {
+                sheet.write_string(0, column as u16, heading)?;
+            }
+            for (row, (camel, cells)) in table.rows.iter().enumerate() {
+                let row = row as u32 + 1;
+                sheet.write_string(row, 0, Language::English.camel(*camel))?;
+                for (column, cell) in cells.iter().enumerate() {
+                    if let Some(value) = cell {
+                        sheet.write_number(row, column as u16 + 1, value.to_f64())?;
+                    }
+                }
+            }
+        }
+        Ok(workbook)
+    }
+}
+
+/// When an export can not be written, this enumeration tells you why.
+#[derive(Debug)]
+pub enum ExportError {
+    /// A file or directory could not be written.
+    Io(io::Error),
+    /// The workbook could not be put together.
+    Xlsx(XlsxError),
+}
+
+impl From<io::Error> for ExportError {
+    fn from(problem: io::Error) -> Self {
+        Self::Io(problem)
+    }
+}
+
+impl From<XlsxError> for ExportError {
+    fn from(problem: XlsxError) -> Self {
+        Self::Xlsx(problem)
+    }
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(problem) => write!(f, "could not write export: {}", problem),
+            ExportError::Xlsx(problem) => write!(f, "could not make workbook: {}", problem),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tables_have_a_row_per_camel() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "r".parse::<Dice>().expect("to parse");
+
+        let export = Export::new(&race, &dice, &RuleSet::default());
+
+        assert_eq!(
+            export.placements.to_csv(),
+            "camel,1,2\nRed,0.6666666666666666,0.3333333333333333\nYellow,0.3333333333333333,0.6666666666666666\n"
+        );
+        assert_eq!(
+            export.matchups.to_csv(),
+            "camel,Red,Yellow\nRed,,0.6666666666666666\nYellow,0.3333333333333333,\n"
+        );
+        assert_eq!(export.expected_values.header, vec!["camel", "5", "3", "2"]);
+    }
+
+    #[test]
+    fn workbooks_are_zip_archives() {
+        let race = "r,,y".parse::<Race>().expect("to parse");
+        let dice = "ry".parse::<Dice>().expect("to parse");
+
+        let workbook = Export::new(&race, &dice, &RuleSet::default())
+            .to_xlsx()
+            .expect("to make a workbook");
+
+        assert!(workbook.starts_with(b"PK"));
+    }
+}
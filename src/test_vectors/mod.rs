@@ -0,0 +1,119 @@
+//! Canonical positions with their exact chances, to verify an oracle against.
+//!
+//! The vectors live in `test-vectors/projections.json`, in the JSON wire format of the `wire` module, so that reimplementations in other languages can read them too. Every vector has a name, a race, the dice in the pyramid, optionally the edition of the rules, and the chances at the end of the leg.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::test_vectors::projections;
+//! for vector in projections() {
+//!     let chances = project(&vector.race, &vector.dice);
+//!
+//!     assert!(vector.matches(&chances), "{} does not match", vector.name);
+//! }
+//! ```
+use crate::{
+    camel::{Camel, Dice, Edition, Race},
+    oracle::{Distribution, LegChances},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// The test vectors for projections, as JSON.
+pub const PROJECTIONS: &str = include_str!("../../test-vectors/projections.json");
+
+/// A position with the chances an oracle should project for it.
+pub struct TestVector {
+    /// What the position shows.
+    pub name: String,
+    /// The race, following the rules of the edition of the vector.
+    pub race: Race,
+    /// The dice in the pyramid.
+    pub dice: Dice,
+    /// The chances at the end of the leg.
+    pub chances: LegChances,
+}
+
+impl TestVector {
+    /// Whether the chances are exactly the expected chances.
+    pub fn matches(&self, chances: &LegChances) -> bool {
+        same(&self.chances.winner, &chances.winner)
+            && same(&self.chances.runner_up, &chances.runner_up)
+            && same(&self.chances.loser, &chances.loser)
+            && self.chances.places.len() == chances.places.len()
+            && self
+                .chances
+                .places
+                .iter()
+                .zip(chances.places.iter())
+                .all(|(expected, actual)| same(expected, actual))
+    }
+}
+
+fn same(expected: &Distribution, actual: &Distribution) -> bool {
+    let camels: HashSet<Camel> = expected
+        .values()
+        .chain(actual.values())
+        .map(|(camel, _)| *camel)
+        .collect();
+    camels.iter().all(|camel| expected[camel] == actual[camel])
+}
+
+/// A test vector as it is written in the JSON.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    name: String,
+    race: Race,
+    dice: Dice,
+    #[serde(default)]
+    edition: Edition,
+    chances: LegChances,
+}
+
+impl From<Entry> for TestVector {
+    fn from(entry: Entry) -> Self {
+        Self {
+            name: entry.name,
+            race: entry.race.with_edition(entry.edition),
+            dice: entry.dice,
+            chances: entry.chances,
+        }
+    }
+}
+
+/// The test vectors for projections.
+pub fn projections() -> Vec<TestVector> {
+    let entries: Vec<Entry> =
+        serde_json::from_str(PROJECTIONS).expect("the test vectors to be valid JSON");
+    entries.into_iter().map(TestVector::from).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle::project;
+
+    #[test]
+    fn the_oracle_projects_every_test_vector() {
+        let vectors = projections();
+
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let chances = project(&vector.race, &vector.dice);
+
+            assert!(vector.matches(&chances), "{} does not match", vector.name);
+        }
+    }
+
+    #[test]
+    fn different_chances_do_not_match() {
+        let vector = projections()
+            .into_iter()
+            .find(|vector| vector.name == "catching up")
+            .expect("a vector that is catching up");
+
+        let chances = project(&vector.race, &"ry".parse::<Dice>().expect("to parse"));
+
+        assert!(!vector.matches(&chances));
+    }
+}
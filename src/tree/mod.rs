@@ -1,5 +1,16 @@
-use crate::camel::{Dice, Face, Race, Roll};
-use std::collections::HashMap;
+use crate::{
+    camel::{Dice, Face, Race, Roll},
+    oracle::{self, Transitions},
+};
+use std::{collections::HashMap, mem::size_of};
+
+/// The number of nodes between two progress reports.
+const PROGRESS_INTERVAL: usize = 1 << 16;
+
+/// The number of bytes a node of the tree takes, with its entry in the children of its parent.
+///
+/// The children of a node are kept in a hash map, which reserves room for about twice as many entries as it holds.
+pub const NODE_BYTES: usize = size_of::<Node>() + 2 * (size_of::<(Roll, usize)>() + 1);
 
 pub struct Tree {
     nodes: Vec<Node>,
@@ -16,32 +27,74 @@ impl Tree {
     }
 
     pub fn expand(&mut self, dice: &Dice) {
-        self.expand_roots(dice);
+        self.expand_with_progress(dice, &mut |_| {});
+    }
+
+    /// Expands the tree, calling `progress` with the number of nodes every so often.
+    pub fn expand_with_progress(&mut self, dice: &Dice, progress: &mut dyn FnMut(usize)) {
+        self.expand_roots(
+            dice,
+            &mut |race, roll| oracle::perform(race, roll),
+            progress,
+        );
+        progress(self.nodes.len());
+    }
+
+    /// Expands the tree like `expand_with_progress`, looking up the outcome of rolls in the transitions.
+    pub fn expand_with_transitions(
+        &mut self,
+        dice: &Dice,
+        transitions: &mut Transitions,
+        progress: &mut dyn FnMut(usize),
+    ) {
+        self.expand_roots(
+            dice,
+            &mut |race, roll| transitions.perform(race, roll),
+            progress,
+        );
+        progress(self.nodes.len());
+    }
+
+    /// The number of nodes in the tree.
+    pub fn size(&self) -> usize {
+        self.nodes.len()
     }
 
-    fn expand_roots(&mut self, dice: &Dice) {
-        let root_indices: Vec<usize> = self.roots.iter().copied().collect();
+    fn expand_roots(
+        &mut self,
+        dice: &Dice,
+        perform: &mut dyn FnMut(&Race, Roll) -> Race,
+        progress: &mut dyn FnMut(usize),
+    ) {
+        let root_indices: Vec<usize> = self.roots.to_vec();
         for index in root_indices {
-            self.expand_node(index, dice);
+            self.expand_node(index, dice, perform, progress);
         }
     }
 
-    fn expand_node(&mut self, index: usize, dice: &Dice) {
-        for camel in dice.clone() {
+    fn expand_node(
+        &mut self,
+        index: usize,
+        dice: &Dice,
+        perform: &mut dyn FnMut(&Race, Roll) -> Race,
+        progress: &mut dyn FnMut(usize),
+    ) {
+        for camel in dice {
             let remaining_dice = dice.remove(camel);
             for face in Face::values() {
                 let roll = Roll::from((camel, face));
-                let race = self.perform_on(index, roll);
+                let race = perform(&self.nodes[index].race, roll);
                 let child_index = self.add_child(index, roll, race);
-                self.expand_node(child_index, &remaining_dice);
+                if child_index.is_multiple_of(PROGRESS_INTERVAL) {
+                    progress(child_index);
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(nodes = child_index, "expanding");
+                }
+                self.expand_node(child_index, &remaining_dice, perform, progress);
             }
         }
     }
 
-    fn perform_on(&mut self, index: usize, roll: Roll) -> Race {
-        self.nodes[index].race.perform(roll)
-    }
-
     fn add_child(&mut self, index: usize, roll: Roll, race: Race) -> usize {
         let child = Node::new(race);
         self.nodes.push(child);
@@ -52,12 +105,24 @@ impl Tree {
         child_index
     }
 
+    /// Visits every distinct leaf once, with the number of leaves that hold the same race.
+    ///
+    /// Many orders of the rolls lead to the same race, so visitors do less work than when every leaf is visited.
     pub fn visit_leaves(&self, visitor: &mut dyn LeafVisitor) {
+        for (race, multiplicity) in self.leaves() {
+            visitor.visit(&race, multiplicity);
+        }
+    }
+
+    /// The distinct races in the leaves, with the number of leaves that hold them.
+    pub fn leaves(&self) -> HashMap<Race, usize> {
+        let mut leaves = HashMap::new();
         for candidate in &self.nodes {
             if candidate.is_leaf() {
-                visitor.visit(&candidate.race);
+                *leaves.entry(candidate.race).or_insert(0) += 1;
             }
         }
+        leaves
     }
 }
 
@@ -83,5 +148,18 @@ impl Node {
 }
 
 pub trait LeafVisitor {
-    fn visit(&mut self, race: &Race);
+    /// Visits a race in the leaves of the tree, which `multiplicity` leaves hold.
+    fn visit(&mut self, race: &Race, multiplicity: usize);
+}
+
+/// A leaf visitor whose counts can be split over parts of the leaves and merged afterwards.
+///
+/// The parts can be visited on different threads, which is how the parallel backend counts.
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub trait ReducibleVisitor: LeafVisitor + Send + Sync + Sized {
+    /// A visitor like this one that has not visited any leaves yet.
+    fn identity(&self) -> Self;
+
+    /// The counts of both visitors together.
+    fn merge(self, other: Self) -> Self;
 }
@@ -0,0 +1,302 @@
+//! Markdown formatting, e.g. for chat services like Discord.
+use crate::advisor::Action;
+use crate::camel::{Camel, CAMELS};
+use crate::fraction::Fraction;
+use crate::i18n::{Label, Language};
+use crate::oracle::LegChances;
+use crate::vis::render::render_board_plain;
+use crate::vis::types::Board;
+
+/// The number of characters in a single Discord message.
+pub const DISCORD_LIMIT: usize = 2000;
+
+/// The number of characters in a single Telegram message.
+pub const TELEGRAM_LIMIT: usize = 4096;
+
+/// The number of actions of the advice that a chat summary shows.
+const ADVICE: usize = 3;
+
+/// Marks the lines of a chat summary that are left out.
+const ELLIPSIS: &str = "…";
+
+/// Formats the board and the chances as Discord flavoured markdown.
+///
+/// The board is put in a code block, followed by a line per camel with the chances in bold. Camels are ordered by their chance of winning.
+pub fn discord(board: &Board, chances: &LegChances) -> String {
+  discord_in(Language::English, board, chances)
+}
+
+/// Formats the board and the chances as Discord flavoured markdown, with the names and labels in the language.
+pub fn discord_in(language: Language, board: &Board, chances: &LegChances) -> String {
+  let mut lines = vec!["```".to_string()];
+  lines.extend(
+    render_board_plain(board)
+      .into_iter()
+      .map(|line| line.trim_end().to_string()),
+  );
+  lines.push("```".to_string());
+  lines.extend(chance_lines(language, chances));
+
+  lines.join("\n")
+}
+
+/// Formats the chances of the given camels as a markdown table, which GitHub and Discord render alike.
+///
+/// The camels are listed in the order given, each chance is written with `format`.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::vis::markdown::table;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let chances = project(&race, &dice);
+///
+/// let table = table(&chances, &[Camel::Red], &|chance| chance.to_string());
+///
+/// assert_eq!(
+///     table,
+///     "| camel | winner | runner up | loser |\n|:--|--:|--:|--:|\n| Red | 1 | 0 | 0 |"
+/// );
+/// ```
+pub fn table(
+  chances: &LegChances,
+  camels: &[Camel],
+  format: &dyn Fn(&Fraction) -> String,
+) -> String {
+  table_in(Language::English, chances, camels, format)
+}
+
+/// Formats the chances of the given camels as a markdown table, with the names and labels in the language.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::vis::markdown::table_in;
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let chances = project(&race, &dice);
+///
+/// let table = table_in(Language::German, &chances, &[Camel::Red], &|chance| chance.to_string());
+///
+/// assert!(table.starts_with("| Kamel | Sieger | Zweiter | Verlierer |"));
+/// ```
+pub fn table_in(
+  language: Language,
+  chances: &LegChances,
+  camels: &[Camel],
+  format: &dyn Fn(&Fraction) -> String,
+) -> String {
+  let mut lines = vec![
+    format!(
+      "| {} | {} | {} | {} |",
+      language.label(Label::Camel),
+      language.label(Label::Winner),
+      language.label(Label::RunnerUp),
+      language.label(Label::Loser)
+    ),
+    "|:--|--:|--:|--:|".to_string(),
+  ];
+  lines.extend(camels.iter().map(|camel| {
+    format!(
+      "| {} | {} | {} | {} |",
+      language.camel(*camel),
+      format(&chances.winner[camel]),
+      format(&chances.runner_up[camel]),
+      format(&chances.loser[camel]),
+    )
+  }));
+
+  lines.join("\n")
+}
+
+/// Summarizes the chances and the best actions of the advice in a single chat message of at most `limit` characters.
+///
+/// Every camel gets a line with its colour as emoji and its chances, ordered by the chance of winning. The three best actions of the advice follow, with their expected number of coins. Lines that do not fit are left out and replaced by an ellipsis. Characters are counted in UTF-16, like Telegram does, so the summary fits both Discord and Telegram. The names and labels are in the language, the actions are described in English.
+///
+/// ```
+/// # use camel_up::prelude::*;
+/// # use camel_up::advisor::rank;
+/// # use camel_up::vis::markdown::{chat, DISCORD_LIMIT};
+/// let race = "r,y".parse::<Race>().expect("to parse");
+/// let dice = "r".parse::<Dice>().expect("to parse");
+/// let chances = project(&race, &dice);
+/// let advice = rank(&chances, &[Ticket::from((Camel::Red, 5))], None);
+///
+/// let message = chat(Language::English, &chances, &advice, DISCORD_LIMIT);
+///
+/// assert_eq!(
+///     message,
+///     "🔴 **Red** win 100.0% · runner up 0.0% · last 0.0%\n\
+///      🟡 **Yellow** win 0.0% · runner up 100.0% · last 0.0%\n\
+///      💡 take Red ticket worth 5: **5.0** coins\n\
+///      💡 roll a die: **1.0** coins"
+/// );
+/// ```
+pub fn chat(
+  language: Language,
+  chances: &LegChances,
+  advice: &[(Action, Fraction)],
+  limit: usize,
+) -> String {
+  let camels = camels_by_winning(chances).into_iter().map(|camel| {
+    format!(
+      "{} **{}** {} {} · {} {} · {} {}",
+      emoji(camel),
+      language.camel(camel),
+      language.label(Label::Win),
+      percentage(chances.winner[&camel].to_f64()),
+      language.label(Label::RunnerUp),
+      percentage(chances.runner_up[&camel].to_f64()),
+      language.label(Label::Last),
+      percentage(chances.loser[&camel].to_f64()),
+    )
+  });
+  let actions = advice
+    .iter()
+    .take(ADVICE)
+    .map(|(action, coins)| format!("💡 {}: **{:.1}** coins", action, coins.to_f64()));
+
+  let mut message = String::new();
+  for line in camels.chain(actions) {
+    let separator = if message.is_empty() { "" } else { "\n" };
+    if length(&message) + length(separator) + length(&line) > limit {
+      if length(&message) + length(separator) + length(ELLIPSIS) <= limit {
+        message.push_str(separator);
+        message.push_str(ELLIPSIS);
+      }
+      break;
+    }
+    message.push_str(separator);
+    message.push_str(&line);
+  }
+
+  message
+}
+
+/// The length of the text, as chat services count it.
+fn length(text: &str) -> usize {
+  text.encode_utf16().count()
+}
+
+fn emoji(camel: Camel) -> &'static str {
+  match camel {
+    Camel::Red => "🔴",
+    Camel::Orange => "🟠",
+    Camel::Yellow => "🟡",
+    Camel::Green => "🟢",
+    Camel::White => "⚪",
+  }
+}
+
+/// The camels with a chance, ordered by their chance of winning.
+fn camels_by_winning(chances: &LegChances) -> Vec<Camel> {
+  let mut camels: Vec<Camel> = CAMELS
+    .iter()
+    .copied()
+    .filter(|camel| {
+      chances.winner.values().any(|(c, _)| c == camel)
+        || chances.runner_up.values().any(|(c, _)| c == camel)
+        || chances.loser.values().any(|(c, _)| c == camel)
+    })
+    .collect();
+  camels.sort_by(|left, right| chances.winner[right].cmp(&chances.winner[left]));
+
+  camels
+}
+
+fn chance_lines(language: Language, chances: &LegChances) -> Vec<String> {
+  camels_by_winning(chances)
+    .iter()
+    .map(|camel| {
+      format!(
+        "{}: {} **{}**, {} **{}**, {} **{}**",
+        language.camel(*camel),
+        language.label(Label::Win),
+        percentage(chances.winner[camel].to_f64()),
+        language.label(Label::RunnerUp),
+        percentage(chances.runner_up[camel].to_f64()),
+        language.label(Label::Last),
+        percentage(chances.loser[camel].to_f64()),
+      )
+    })
+    .collect()
+}
+
+fn percentage(chance: f64) -> String {
+  format!("{:.1}%", chance * 100.0)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::advisor::rank;
+  use crate::camel::{Dice, Race};
+  use crate::oracle::project;
+
+  #[test]
+  fn chances_are_listed_in_bold() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let dice = "r".parse::<Dice>().expect("to parse");
+    let chances = project(&race, &dice);
+
+    let message = discord(&Board::from(&race), &chances);
+    let lines: Vec<&str> = message.lines().collect();
+
+    assert_eq!(lines[0], "```");
+    assert_eq!(lines[7], "```");
+    assert_eq!(
+      lines[8],
+      "Red: win **66.7%**, runner up **33.3%**, last **0.0%**"
+    );
+    assert_eq!(
+      lines[9],
+      "Yellow: win **33.3%**, runner up **66.7%**, last **0.0%**"
+    );
+  }
+
+  #[test]
+  fn tables_have_a_row_per_camel() {
+    let race = "r,,y".parse::<Race>().expect("to parse");
+    let dice = "r".parse::<Dice>().expect("to parse");
+    let chances = project(&race, &dice);
+
+    let table = table(&chances, &[Camel::Red, Camel::Yellow], &|chance| {
+      percentage(chance.to_f64())
+    });
+    let lines: Vec<&str> = table.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[2], "| Red | 66.7% | 33.3% | 0.0% |");
+    assert_eq!(lines[3], "| Yellow | 33.3% | 66.7% | 0.0% |");
+  }
+
+  #[test]
+  fn chat_summaries_fit_the_limit() {
+    let race = "r,o,y,g,w".parse::<Race>().expect("to parse");
+    let dice = "roygw".parse::<Dice>().expect("to parse");
+    let chances = project(&race, &dice);
+    let advice = rank(&chances, &[], None);
+
+    let full = chat(Language::English, &chances, &advice, TELEGRAM_LIMIT);
+    let short = chat(Language::English, &chances, &advice, 120);
+
+    assert_eq!(full.lines().count(), 6);
+    assert!(length(&short) <= 120);
+    assert_eq!(short.lines().count(), 3);
+    assert_eq!(short.lines().last(), Some(ELLIPSIS));
+  }
+
+  #[test]
+  fn chances_are_listed_in_the_language() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let dice = "r".parse::<Dice>().expect("to parse");
+    let chances = project(&race, &dice);
+
+    let message = discord_in(Language::Dutch, &Board::from(&race), &chances);
+    let lines: Vec<&str> = message.lines().collect();
+
+    assert_eq!(
+      lines[8],
+      "Rood: winst **66.7%**, tweede **33.3%**, laatste **0.0%**"
+    );
+  }
+}
@@ -1,3 +1,4 @@
 //! Visualization routines
+pub mod markdown;
 pub mod render;
 pub mod types;
@@ -1,53 +1,170 @@
 //! Rendering routines
 #![allow(missing_docs)]
 use ansi_term::ANSIString;
+use crate::bets::Ticket;
+use crate::camel::{Face, Race, Roll};
+use crate::fraction::Fraction;
+use crate::oracle::LegChances;
 use crate::prelude::Camel;
 use crate::vis::types::BOARD_SIZE;
 use crate::vis::types::Board;
+use crate::vis::types::CrazyCamel;
+use crate::vis::types::Racer;
+use crate::vis::types::start_tile;
 use crate::vis::types::Tile;
 use crate::vis::types::Trap;
 use crate::vis::types::TrapType;
 use crate::vis::types::Player;
 
 use ansi_term::Color;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
 
 const CAMEL_COUNT: usize = 5;
 
+/// How the board is painted.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Style {
+  /// Colored glyphs using ANSI escape codes, for terminals.
+  Colored,
+  /// Letters and symbols of the race notation, guaranteed free of escape sequences.
+  Plain,
+}
+
 pub fn render_board(board: &Board) -> Vec<String> {
-  let mut screen: [[String; BOARD_SIZE + 1]; CAMEL_COUNT] = Default::default();
-  for i in 0..CAMEL_COUNT {
-    for j in 0..BOARD_SIZE + 1 {
-      screen[i][j] = " ".to_string();
-    }
+  render_board_styled(board, Style::Colored)
+}
+
+/// Renders the board without any ANSI escape codes, suitable for snapshot tests, logs and markdown code blocks.
+pub fn render_board_plain(board: &Board) -> Vec<String> {
+  render_board_styled(board, Style::Plain)
+}
+
+pub fn render_board_styled(board: &Board, style: Style) -> Vec<String> {
+  render_board_laid_out(board, style, &Layout::default())
+}
+
+/// Determines the dimensions of the rendered board.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Layout {
+  /// The number of terminal columns each tile occupies.
+  pub cell_width: usize,
+}
+
+impl Layout {
+  pub fn new(cell_width: usize) -> Self {
+    Self { cell_width }
+  }
+}
+
+impl Default for Layout {
+  fn default() -> Self {
+    Self::new(4)
+  }
+}
+
+/// The painted contents of a cell together with the number of columns it occupies on screen.
+#[derive(Clone)]
+struct Cell {
+  painted: String,
+  width: usize,
+}
+
+impl Cell {
+  fn new<S: AsRef<str>>(painted: String, visible: S) -> Self {
+    let width = UnicodeWidthStr::width(visible.as_ref());
+    Self { painted, width }
+  }
+
+  fn blank() -> Self {
+    Self::new(" ".to_string(), " ")
+  }
+
+  fn centered(&self, layout: &Layout) -> String {
+    let room = layout.cell_width.saturating_sub(self.width);
+    let left = room.div_ceil(2);
+    let right = room - left;
+    format!("{}{}{}", " ".repeat(left), self.painted, " ".repeat(right))
   }
+}
+
+pub fn render_board_laid_out(board: &Board, style: Style, layout: &Layout) -> Vec<String> {
+  render(board, None, style, layout)
+}
+
+/// Renders the board with, next to each camel, the tile it is expected to finish the leg on as a superscript number.
+///
+/// The expected tiles are indices into the board, see `expected_tiles` to obtain them from the oracle.
+pub fn render_board_annotated(board: &Board, expected: &HashMap<Camel, Fraction>, style: Style, layout: &Layout) -> Vec<String> {
+  render(board, Some(expected), style, layout)
+}
+
+/// Translates positions relative to the start of the race, as returned by `expected_positions`, to tiles on the board.
+pub fn expected_tiles(race: &Race, positions: &HashMap<Camel, Fraction>) -> HashMap<Camel, Fraction> {
+  let start = start_tile(race) as i64;
+  positions.iter().map(|(camel, position)| (*camel, *position + start)).collect()
+}
+
+fn render(board: &Board, expected: Option<&HashMap<Camel, Fraction>>, style: Style, layout: &Layout) -> Vec<String> {
+  let rows = board.tiles.iter().fold(CAMEL_COUNT, |rows, tile| match tile {
+    Tile::Camels(racers) => rows.max(racers.len()),
+    _ => rows,
+  });
+  let mut screen: Vec<Vec<Cell>> = vec![vec![Cell::blank(); BOARD_SIZE]; rows];
 
   for (i, tile) in board.tiles.iter().enumerate() {
     match tile {
       Tile::Nothing => (),
-      Tile::Trap(t) => screen[0][i] = format!("{}", render_trap(t)),
-      Tile::Camels(camels) => {
-        for (j, camel) in camels.iter().enumerate() {
-          screen[j][i] = format!("{}", render_camel(camel));
+      Tile::Trap(t) => screen[0][i] = Cell::new(paint_trap(t, style), trap_glyph(t.trap_type, style)),
+      Tile::Camels(racers) => {
+        for (j, racer) in racers.iter().enumerate() {
+          screen[j][i] = match racer {
+            Racer::Camel(camel) => match expected.and_then(|expected| expected.get(camel)) {
+              Some(tile) => {
+                let annotation = to_super_nr(format!("{}", (tile.to_f64() + 1.0).round()));
+                Cell::new(format!("{}{}", paint_camel(camel, style), annotation), format!("{}{}", camel_glyph(*camel, style), annotation))
+              }
+              None => Cell::new(paint_camel(camel, style), camel_glyph(*camel, style)),
+            },
+            Racer::Crazy(camel) => Cell::new(paint_crazy_camel(*camel, style), crazy_camel_glyph(*camel, style)),
+          };
         }
       }
     }
   }
 
-  for i in 0..CAMEL_COUNT {
-    screen[i][BOARD_SIZE] = format!("{}", Color::White.paint("┇"));
+  let separator = match style {
+    Style::Colored => format!("{}", Color::White.paint("┇")),
+    Style::Plain => "┇".to_string(),
+  };
+  let camel_legend = match style {
+    Style::Colored => paint_camel(&Camel::Green, style),
+    Style::Plain => "r/o/y/g/w".to_string(),
+  };
+  let mut legend = vec![String::new(); rows];
+  legend[0] = format!("  {} camel", camel_legend);
+  legend[1] = format!("  {} oasis", paint_trap(&Trap { trap_type: TrapType::Oasis, player: Player::BobbyTheBooky }, style));
+  legend[2] = format!("  {} fata morgana", paint_trap(&Trap { trap_type: TrapType::FataMorgana, player: Player::BobbyTheBooky }, style));
+  if board.has_crazy_camels() {
+    let crazy_legend = match style {
+      Style::Colored => paint_crazy_camel(CrazyCamel::Black, style),
+      Style::Plain => "B/W".to_string(),
+    };
+    legend[3] = format!("  {} crazy camel", crazy_legend);
   }
 
-  screen[0][BOARD_SIZE] = format!("{}  {} camel", screen[0][BOARD_SIZE], render_camel(&Camel::Green));
-  screen[1][BOARD_SIZE] = format!("{}  {} oasis", screen[1][BOARD_SIZE], render_trap(&Trap { trap_type: TrapType::Oasis, player: Player::BobbyTheBooky }));
-  screen[2][BOARD_SIZE] = format!("{}  {} fata morgana", screen[2][BOARD_SIZE], render_trap(&Trap { trap_type: TrapType::FataMorgana, player: Player::BobbyTheBooky }));
-
-  let mut ret = Vec::with_capacity(CAMEL_COUNT + 1);
+  let mut ret = Vec::with_capacity(rows + 1);
 
-  for i in (0..CAMEL_COUNT).rev() {
-    ret.push(screen[i].iter().map(|s| format!("  {} ", s)).collect::<String>());
+  for i in (0..rows).rev() {
+    let track = screen[i].iter().map(|cell| cell.centered(layout)).collect::<String>();
+    let banner = if board.finish { paint_finish(i, style) } else { String::new() };
+    ret.push(format!("{}{}  {}{} ", track, banner, separator, legend[i]));
   }
 
-  ret.push((0..16).map(|i| to_super_nr(format!(" {:2} ", i + 1))).collect::<String>());
+  ret.push((1..=BOARD_SIZE).map(|i| {
+    let number = to_super_nr(format!("{}", i));
+    Cell::new(number.clone(), number).centered(layout)
+  }).collect::<String>());
 
   ret
 }
@@ -74,16 +191,133 @@ pub fn print_board(board: &Board) {
   }
 }
 
+/// Renders the rolls of a leg in order, as colored die icons showing their faces.
+pub fn render_rolls(rolls: &[Roll], style: Style) -> String {
+  rolls.iter().map(|roll| paint_roll(roll, style)).collect::<Vec<String>>().join(" ")
+}
+
+pub fn print_rolls(rolls: &[Roll]) {
+  println!("{}", render_rolls(rolls, Style::Colored));
+}
+
+/// Renders the available leg betting tickets, each annotated with its expected value.
+pub fn render_tickets(tickets: &[Ticket], chances: &LegChances) -> Vec<String> {
+  render_tickets_styled(tickets, chances, Style::Colored)
+}
 
-fn render_trap(trap: &Trap) -> ANSIString {
-  player_color(trap.player).bold().paint(match trap.trap_type {
-    TrapType::Oasis => "ꕄ",
-    TrapType::FataMorgana => "௫",
-  })
+pub fn render_tickets_styled(tickets: &[Ticket], chances: &LegChances, style: Style) -> Vec<String> {
+  tickets.iter().map(|ticket| {
+    format!("{} {} EV {}", paint_camel(&ticket.camel, style), ticket.value, ticket.expected_value(chances))
+  }).collect()
 }
 
-fn render_camel(camel: &Camel) -> ANSIString {
-  camel_color(*camel).bold().paint("ന")
+/// Renders the board with a compact panel of betting tickets next to it.
+pub fn render_board_with_tickets(board: &Board, tickets: &[Ticket], chances: &LegChances) -> Vec<String> {
+  render_board_with_tickets_styled(board, tickets, chances, Style::Colored)
+}
+
+pub fn render_board_with_tickets_styled(board: &Board, tickets: &[Ticket], chances: &LegChances, style: Style) -> Vec<String> {
+  let panel = render_tickets_styled(tickets, chances, style);
+  let mut ret = render_board_styled(board, style);
+  if ret.len() < panel.len() {
+    ret.resize(panel.len(), String::new());
+  }
+  for (line, ticket) in ret.iter_mut().zip(panel) {
+    line.push_str(&format!("  {}", ticket));
+  }
+
+  ret
+}
+
+pub fn print_board_with_tickets(board: &Board, tickets: &[Ticket], chances: &LegChances) {
+  for line in render_board_with_tickets(board, tickets, chances) {
+    println!("{}", line);
+  }
+}
+
+
+fn paint_trap(trap: &Trap, style: Style) -> String {
+  match style {
+    Style::Colored => format!("{}", render_trap(trap)),
+    Style::Plain => trap_glyph(trap.trap_type, style).to_string(),
+  }
+}
+
+fn paint_camel(camel: &Camel, style: Style) -> String {
+  match style {
+    Style::Colored => format!("{}", render_camel(camel)),
+    Style::Plain => camel_glyph(*camel, style).to_string(),
+  }
+}
+
+fn paint_roll(roll: &Roll, style: Style) -> String {
+  match style {
+    Style::Colored => {
+      let die = match roll.face() {
+        Face::One => "⚀",
+        Face::Two => "⚁",
+        Face::Three => "⚂",
+      };
+      format!("{}", camel_color(roll.camel()).bold().paint(die))
+    }
+    Style::Plain => format!("{}{}", camel_glyph(roll.camel(), style), usize::from(roll.face())),
+  }
+}
+
+fn paint_crazy_camel(camel: CrazyCamel, style: Style) -> String {
+  match style {
+    Style::Colored => format!("{}", crazy_camel_color(camel).bold().reverse().paint(crazy_camel_glyph(camel, style))),
+    Style::Plain => crazy_camel_glyph(camel, style).to_string(),
+  }
+}
+
+/// The finish banner is a checkered column, alternating per row.
+fn paint_finish(row: usize, style: Style) -> String {
+  let glyph = if row.is_multiple_of(2) { "▚" } else { "▞" };
+  match style {
+    Style::Colored => format!("{}", Color::White.paint(glyph)),
+    Style::Plain => glyph.to_string(),
+  }
+}
+
+fn render_trap(trap: &Trap) -> ANSIString<'static> {
+  player_color(trap.player).bold().paint(trap_glyph(trap.trap_type, Style::Colored))
+}
+
+fn render_camel(camel: &Camel) -> ANSIString<'static> {
+  camel_color(*camel).bold().paint(camel_glyph(*camel, Style::Colored))
+}
+
+fn trap_glyph(trap_type: TrapType, style: Style) -> &'static str {
+  match (style, trap_type) {
+    (Style::Colored, TrapType::Oasis) => "ꕄ",
+    (Style::Colored, TrapType::FataMorgana) => "௫",
+    (Style::Plain, TrapType::Oasis) => "+",
+    (Style::Plain, TrapType::FataMorgana) => "-",
+  }
+}
+
+fn camel_glyph(camel: Camel, style: Style) -> &'static str {
+  match style {
+    Style::Colored => "ന",
+    Style::Plain => match camel {
+      Camel::Red => "r",
+      Camel::Orange => "o",
+      Camel::Yellow => "y",
+      Camel::Green => "g",
+      Camel::White => "w",
+    },
+  }
+}
+
+fn crazy_camel_glyph(camel: CrazyCamel, style: Style) -> &'static str {
+  match style {
+    Style::Colored => "ന",
+    Style::Plain => match camel {
+      CrazyCamel::Black => "B",
+      CrazyCamel::White => "W",
+    },
+  }
 }
 
 fn player_color(player: Player) -> Color {
@@ -100,6 +334,13 @@ fn player_color(player: Player) -> Color {
   }
 }
 
+fn crazy_camel_color(camel: CrazyCamel) -> Color {
+  match camel {
+    CrazyCamel::Black => Color::Fixed(240),
+    CrazyCamel::White => Color::White,
+  }
+}
+
 fn camel_color(camel: Camel) -> Color {
   match camel {
     Camel::Green => Color::Green,
@@ -109,3 +350,113 @@ fn camel_color(camel: Camel) -> Color {
     Camel::Yellow => Color::Yellow,
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::camel::Dice;
+  use crate::oracle::{expected_positions, project};
+
+  #[test]
+  fn plain_board_is_free_of_escape_sequences() {
+    let race = "r,+,yg,,,,,,,,,,,,-,!".parse::<Race>().expect("to parse");
+    let board = Board::from(&race);
+
+    let lines = render_board_plain(&board);
+
+    assert!(lines.iter().all(|line| !line.contains('\u{1b}')));
+    assert_eq!(lines[3].trim_end(), "          g                                                     ▞  ┇  + oasis");
+    assert_eq!(lines[4].trim_end(), "  r   +   y                                               -     ▚  ┇  r/o/y/g/w camel");
+  }
+
+  #[test]
+  fn cells_are_padded_to_the_configured_width() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let board = Board::from(&race);
+
+    let lines = render_board_laid_out(&board, Style::Plain, &Layout::new(2));
+
+    assert_eq!(lines[4].trim_end(), " r   y                          ▚  ┇  r/o/y/g/w camel");
+    assert_eq!(lines[5], " ¹ ² ³ ⁴ ⁵ ⁶ ⁷ ⁸ ⁹¹⁰¹¹¹²¹³¹⁴¹⁵¹⁶");
+  }
+
+  #[test]
+  fn crazy_camels_are_shown_on_top_of_units() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let mut board = Board::from(&race);
+    board.place_crazy_camel(2, CrazyCamel::Black);
+    board.place_crazy_camel(15, CrazyCamel::White);
+
+    let lines = render_board_plain(&board);
+
+    assert_eq!(lines[1].trim_end(), "                                                                ▞  ┇  B/W crazy camel");
+    assert_eq!(lines[3].trim_end(), "          B                                                     ▞  ┇  + oasis");
+    assert_eq!(lines[4].trim_end(), "  r       y                                                   W ▚  ┇  r/o/y/g/w camel");
+  }
+
+  #[test]
+  fn crazy_camels_can_top_a_stack_of_all_camels() {
+    let race = "roygw,,,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let mut board = Board::from(&race);
+    board.place_crazy_camel(0, CrazyCamel::Black);
+
+    let lines = render_board_plain(&board);
+
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0].trim_end(), "  B                                                             ▞  ┇");
+    assert_eq!(lines[1].trim_end(), "  w                                                             ▚  ┇");
+    assert_eq!(lines[2].trim_end(), "  g                                                             ▞  ┇  B/W crazy camel");
+    assert_eq!(lines[5].trim_end(), "  r                                                             ▚  ┇  r/o/y/g/w camel");
+  }
+
+  #[test]
+  fn boards_without_finish_have_no_banner() {
+    let lines = render_board_plain(&Board::new());
+
+    assert_eq!(lines[4].trim_end(), "                                                                  ┇  r/o/y/g/w camel");
+  }
+
+  #[test]
+  fn camels_can_be_annotated_with_their_expected_finish() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let dice = "r".parse::<Dice>().expect("to parse");
+    let board = Board::from(&race);
+    let expected = expected_tiles(&race, &expected_positions(&race, &dice));
+
+    let lines = render_board_annotated(&board, &expected, Style::Plain, &Layout::default());
+
+    assert_eq!(lines[4].trim_end(), " r³      y³                                                     ▚  ┇  r/o/y/g/w camel");
+  }
+
+  #[test]
+  fn tickets_beyond_the_board_get_lines_of_their_own() {
+    let race = "r,,y,,,,,,,,,,,,,!".parse::<Race>().expect("to parse");
+    let dice = "ry".parse::<Dice>().expect("to parse");
+    let board = Board::from(&race);
+    let chances = project(&race, &dice);
+    let tickets: Vec<Ticket> = ["r5", "y5", "r3", "y3", "r2", "y2", "r1"].iter().map(|ticket| ticket.parse::<Ticket>().expect("to parse")).collect();
+
+    let lines = render_board_with_tickets_styled(&board, &tickets, &chances, Style::Plain);
+
+    assert_eq!(render_board_plain(&board).len(), 6);
+    assert_eq!(lines.len(), tickets.len());
+    assert!(lines[6].starts_with("  r 1 EV "));
+  }
+
+  #[test]
+  fn rolls_are_rendered_in_order() {
+    let rolls = vec![Roll::from((Camel::Red, Face::Two)), Roll::from((Camel::White, Face::One))];
+
+    assert_eq!(render_rolls(&rolls, Style::Plain), "r2 w1");
+    assert_eq!(render_rolls(&[], Style::Plain), "");
+  }
+
+  #[test]
+  fn wide_glyphs_take_up_more_columns() {
+    let narrow = Cell::new("y".to_string(), "y");
+    let wide = Cell::new("駱".to_string(), "駱");
+
+    assert_eq!(narrow.centered(&Layout::default()), "  y ");
+    assert_eq!(wide.centered(&Layout::default()), " 駱 ");
+  }
+}
@@ -7,7 +7,33 @@ use crate::prelude::Race;
 /// The name is rather self explanatory innit
 pub const BOARD_SIZE: usize = 16;
 
-pub type CamelUnit = Vec<Camel>;
+pub type CamelUnit = Vec<Racer>;
+
+/// The crazy camels of the second edition. They run the race in the opposite direction.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum CrazyCamel {
+    Black,
+    White,
+}
+
+/// Anything that can be part of a camel unit.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Racer {
+    Camel(Camel),
+    Crazy(CrazyCamel),
+}
+
+impl From<Camel> for Racer {
+    fn from(camel: Camel) -> Self {
+        Racer::Camel(camel)
+    }
+}
+
+impl From<CrazyCamel> for Racer {
+    fn from(camel: CrazyCamel) -> Self {
+        Racer::Crazy(camel)
+    }
+}
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub enum Player {
@@ -29,43 +55,75 @@ pub enum TrapType {
     FataMorgana,
 }
 
+/// The visible side of a double-sided spectator tile.
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub struct Trap {
   pub trap_type: TrapType,
   pub player: Player,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+impl Trap {
+    /// Turn the spectator tile over, showing its other side.
+    pub fn flip(self) -> Self {
+        let trap_type = match self.trap_type {
+            TrapType::Oasis => TrapType::FataMorgana,
+            TrapType::FataMorgana => TrapType::Oasis,
+        };
+        Trap { trap_type, ..self }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
 pub enum Tile {
     Camels(CamelUnit),
     Trap(Trap),
+    #[default]
     Nothing,
 }
 
-impl Default for Tile {
-    fn default() -> Self {
-        Tile::Nothing
-    }
-}
-
 /// A complete board
+#[derive(Default)]
 pub struct Board {
     pub tiles: [Tile; BOARD_SIZE],
+    /// Whether the finish banner is shown at the end of the track.
+    pub finish: bool,
 }
 
 impl Board {
     pub fn new() -> Self {
         Board {
             tiles: Default::default(),
+            finish: false,
         }
     }
+
+    /// Place a crazy camel on top of the unit at the given tile.
+    pub fn place_crazy_camel(&mut self, index: usize, camel: CrazyCamel) {
+        let tile = match &self.tiles[index] {
+            Tile::Camels(unit) => {
+                let mut unit = unit.clone();
+                unit.push(Racer::from(camel));
+                Tile::Camels(unit)
+            }
+            _ => Tile::Camels(vec![Racer::from(camel)]),
+        };
+        self.tiles[index] = tile;
+    }
+
+    /// Whether there are crazy camels on this board.
+    pub fn has_crazy_camels(&self) -> bool {
+        self.tiles.iter().any(|tile| match tile {
+            Tile::Camels(unit) => unit.iter().any(|racer| matches!(racer, Racer::Crazy(_))),
+            _ => false,
+        })
+    }
 }
 
 impl From<&Race> for Board {
     fn from(race: &Race) -> Self {
         match race.positions.last() {
             None => panic!("Race must have at least one element"),
-            Some(&x) => {
+            Some(x) => {
                 if x != Marker::Finish {
                     panic!("Last element of Race must be a finish");
                 }
@@ -76,7 +134,7 @@ impl From<&Race> for Board {
         let mut i = BOARD_SIZE - 1;
         for marker in race.positions.iter().rev() {
             let mutation = match marker {
-                Marker::Camel(camel) => Some(prepend_camel_to_tile(*camel, &tiles[i])),
+                Marker::Camel(camel) => Some(prepend_camel_to_tile(camel, &tiles[i])),
                 Marker::Divider => {
                     i -= 1;
                     None
@@ -97,10 +155,23 @@ impl From<&Race> for Board {
             }
         }
 
-        Board { tiles }
+        Board { tiles, finish: true }
     }
 }
 
+/// The tile on the board of the first position of the race.
+///
+/// The board is aligned with the finish of the race, or with the end of the race when there is no finish.
+pub fn start_tile(race: &Race) -> usize {
+    let dividers = race
+        .positions
+        .iter()
+        .take_while(|marker| *marker != Marker::Finish)
+        .filter(|marker| *marker == Marker::Divider)
+        .count();
+    (BOARD_SIZE - 1).saturating_sub(dividers)
+}
+
 /// Prepend a camel unit to a tile
 ///
 /// If the tile does not already contain a CamelUnit,
@@ -108,12 +179,12 @@ impl From<&Race> for Board {
 fn prepend_camel_to_tile(camel: Camel, tile: &Tile) -> Tile {
     match tile {
         Tile::Camels(ref unit) => Tile::Camels(prepend_camel_to_unit(camel, unit.clone())),
-        _ => Tile::Camels(vec![camel]),
+        _ => Tile::Camels(vec![Racer::from(camel)]),
     }
 }
 
 /// Prepend a camel to a camel unit
 fn prepend_camel_to_unit(camel: Camel, mut unit: CamelUnit) -> CamelUnit {
-    unit.insert(0, camel);
+    unit.insert(0, Racer::from(camel));
     unit
 }
@@ -0,0 +1,367 @@
+//! The JSON wire format, for clients that talk to the oracle over JSON.
+//!
+//! Races, dice and rolls are strings in the notation of the command line, camels are their lowercase color and chances are exact fractions written as strings, e.g. `"1/3"`. The format is described by a JSON Schema, see `SCHEMA`, so that clients can validate payloads and generate their own types.
+//!
+//! ```
+//! # use camel_up::prelude::*;
+//! # use camel_up::wire::Request;
+//! let request = Request {
+//!     race: "r,,w".parse::<Race>().expect("to parse"),
+//!     dice: None,
+//! };
+//!
+//! let chances = request.answer().expect("dice of camels in the race");
+//!
+//! assert!(chances.winner[&Camel::White] > chances.winner[&Camel::Red]);
+//! ```
+use crate::{
+    camel::{Camel, Dice, Edition, MoveError, Race, Roll},
+    fraction::Fraction,
+    oracle::{project, project_within, Distribution, Enumeration, LegChances},
+};
+use serde::{
+    de::{self, Deserializer},
+    ser::{SerializeMap, SerializeStruct, Serializer},
+    Deserialize, Serialize,
+};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// The JSON Schema of the wire format.
+pub const SCHEMA: &str = include_str!("../schema/wire.schema.json");
+
+/// A question for the oracle: the chances of every camel at the end of the leg.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Request {
+    /// The race to project.
+    pub race: Race,
+    /// The dice in the pyramid. Without dice, the dice of every camel in the race are in the pyramid.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dice: Option<Dice>,
+}
+
+impl Request {
+    /// The dice in the pyramid.
+    pub fn dice(&self) -> Dice {
+        self.dice.unwrap_or_else(|| Dice::from(&self.race))
+    }
+
+    /// Asks the oracle, see `oracle::project`.
+    ///
+    /// Fails when a die is of a camel that is not in the race, see `Race::check_dice`.
+    pub fn answer(&self) -> Result<LegChances, MoveError> {
+        let dice = self.dice();
+        self.race.check_dice(&dice)?;
+        Ok(project(&self.race, &dice))
+    }
+
+    /// Asks the oracle without taking more than `budget` bytes for the tree of rolls, see `oracle::project_within`.
+    ///
+    /// Fails like `answer`.
+    pub fn answer_within(&self, budget: u64) -> Result<(LegChances, Enumeration), MoveError> {
+        let dice = self.dice();
+        self.race.check_dice(&dice)?;
+        Ok(project_within(&self.race, &dice, budget))
+    }
+}
+
+/// The options of a request to `oracle_json`.
+#[derive(PartialEq, Eq, Clone, Default, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Options {
+    /// The edition whose rules the race follows.
+    #[serde(default)]
+    edition: Edition,
+}
+
+/// A request to `oracle_json`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Embedded {
+    race: Race,
+    #[serde(default)]
+    dice: Option<Dice>,
+    #[serde(default)]
+    options: Options,
+}
+
+/// Answers a request in JSON with the chances at the end of the leg in JSON, for embedders that can only exchange strings.
+///
+/// The request has a race, optionally the dice in the pyramid and optionally options, e.g. `{"race": "r,y,-", "dice": "r", "options": {"edition": "second"}}`. Without dice, the dice of every camel in the race are in the pyramid. A request that can not be answered is answered with an object with an `error`.
+///
+/// ```
+/// # use camel_up::oracle_json;
+/// let answer = oracle_json(r#"{"race": "r,y", "dice": "r"}"#);
+///
+/// assert!(answer.starts_with(r#"{"winner":{"red":"1"}"#));
+/// assert!(oracle_json(r#"{"race": "r|y"}"#).starts_with(r#"{"error":"#));
+/// assert!(oracle_json(r#"{"race": "r,y", "dice": "w"}"#).starts_with(r#"{"error":"#));
+/// ```
+pub fn oracle_json(request: &str) -> String {
+    let answer = serde_json::from_str::<Embedded>(request)
+        .map_err(|problem| problem.to_string())
+        .and_then(|request| {
+            let request = Request {
+                race: request.race.with_edition(request.options.edition),
+                dice: request.dice,
+            };
+            let chances = request.answer().map_err(|problem| problem.to_string())?;
+            serde_json::to_string(&chances).map_err(|problem| problem.to_string())
+        });
+    answer.unwrap_or_else(|problem| serde_json::json!({ "error": problem }).to_string())
+}
+
+fn serialize_notation<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+fn deserialize_notation<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse::<T>()
+        .map_err(de::Error::custom)
+}
+
+impl Serialize for Race {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_notation(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Race {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_notation(deserializer)
+    }
+}
+
+impl Serialize for Dice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_notation(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Dice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_notation(deserializer)
+    }
+}
+
+impl Serialize for Roll {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_notation(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Roll {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_notation(deserializer)
+    }
+}
+
+impl Serialize for Edition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_notation(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Edition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_notation(deserializer)
+    }
+}
+
+/// The name of the camel on the wire.
+pub(crate) fn name(camel: Camel) -> &'static str {
+    match camel {
+        Camel::Red => "red",
+        Camel::Orange => "orange",
+        Camel::Yellow => "yellow",
+        Camel::Green => "green",
+        Camel::White => "white",
+    }
+}
+
+impl Serialize for Camel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(name(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Camel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        [
+            Camel::Red,
+            Camel::Orange,
+            Camel::Yellow,
+            Camel::Green,
+            Camel::White,
+        ]
+        .iter()
+        .copied()
+        .find(|camel| name(*camel) == input)
+        .ok_or_else(|| de::Error::custom(format!("'{}' is not a camel", input)))
+    }
+}
+
+impl Serialize for Fraction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_notation(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fraction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        let mut parts = input.splitn(2, '/');
+        let numerator = parts.next().and_then(|part| part.parse::<i64>().ok());
+        let denominator = match parts.next() {
+            Some(part) => part
+                .parse::<u64>()
+                .ok()
+                .filter(|denominator| *denominator > 0),
+            None => Some(1),
+        };
+        match (numerator, denominator) {
+            (Some(numerator), Some(denominator)) => Ok(Fraction::new(numerator, denominator)),
+            _ => Err(de::Error::custom(format!("'{}' is not a fraction", input))),
+        }
+    }
+}
+
+impl Serialize for Distribution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (camel, chance) in self.values() {
+            map.serialize_entry(camel, chance)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Distribution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::<Camel, Fraction>::deserialize(deserializer).map(Distribution::from)
+    }
+}
+
+impl Serialize for LegChances {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut chances = serializer.serialize_struct("LegChances", 4)?;
+        chances.serialize_field("winner", &self.winner)?;
+        chances.serialize_field("runner_up", &self.runner_up)?;
+        chances.serialize_field("loser", &self.loser)?;
+        chances.serialize_field("places", &self.places)?;
+        chances.end()
+    }
+}
+
+/// The fields of `LegChances` on the wire.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fields {
+    winner: Distribution,
+    runner_up: Distribution,
+    loser: Distribution,
+    places: Vec<Distribution>,
+}
+
+impl<'de> Deserialize<'de> for LegChances {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(LegChances {
+            winner: fields.winner,
+            runner_up: fields.runner_up,
+            loser: fields.loser,
+            places: fields.places,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn requests_use_the_notation_of_the_command_line() {
+        let request: Request =
+            serde_json::from_str(r#"{"race": "r,,w", "dice": "w"}"#).expect("to deserialize");
+
+        assert_eq!(request.race, "r,,w".parse::<Race>().expect("to parse"));
+        assert_eq!(request.dice(), "w".parse::<Dice>().expect("to parse"));
+        assert_eq!(
+            serde_json::to_string(&request).expect("to serialize"),
+            r#"{"race":"r,,w","dice":"w"}"#
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"race": "r|w"}"#).is_err());
+    }
+
+    #[test]
+    fn chances_are_exact_fractions_by_camel() {
+        let request = Request {
+            race: "r,,y".parse::<Race>().expect("to parse"),
+            dice: Some("r".parse::<Dice>().expect("to parse")),
+        };
+
+        let json = serde_json::to_string(&request.answer().expect("dice of camels in the race"))
+            .expect("to serialize");
+        let chances: LegChances = serde_json::from_str(&json).expect("to deserialize");
+
+        assert!(json.starts_with(r#"{"winner":{"red":"2/3","yellow":"1/3"}"#));
+        assert_eq!(chances.winner[&Camel::Red], Fraction::new(2, 3));
+        assert_eq!(chances.places.len(), 2);
+    }
+
+    #[test]
+    fn dice_of_camels_that_are_not_in_the_race_are_not_answered() {
+        let request = Request {
+            race: "r,y".parse::<Race>().expect("to parse"),
+            dice: Some("w".parse::<Dice>().expect("to parse")),
+        };
+
+        assert_eq!(
+            request.answer().map(|_| ()),
+            Err(MoveError::CamelNotInRace(Camel::White))
+        );
+        assert!(request.answer_within(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn rolls_and_camels_round_trip() {
+        let roll: Roll = serde_json::from_str(r#""g3""#).expect("to deserialize");
+        let camel: Camel = serde_json::from_str(r#""orange""#).expect("to deserialize");
+
+        assert_eq!(
+            serde_json::to_string(&roll).expect("to serialize"),
+            r#""g3""#
+        );
+        assert_eq!(camel, Camel::Orange);
+        assert!(serde_json::from_str::<Fraction>(r#""1/0""#).is_err());
+    }
+
+    #[test]
+    fn embedders_can_choose_the_edition() {
+        let classic = oracle_json(r#"{"race": "r,y,-", "dice": "r"}"#);
+        let second =
+            oracle_json(r#"{"race": "r,y,-", "dice": "r", "options": {"edition": "second"}}"#);
+
+        assert!(classic.starts_with(r#"{"winner":{"red":"1"}"#));
+        assert!(second.starts_with(r#"{"winner":{"red":"2/3","yellow":"1/3"}"#));
+        assert!(oracle_json(r#"{"race": "r", "options": {"edition": "third"}}"#).contains("error"));
+    }
+
+    #[test]
+    fn the_schema_is_json() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).expect("to parse");
+
+        assert!(schema["$defs"]["chances"].is_object());
+    }
+}